@@ -3,42 +3,48 @@
 //! ```
 
 use orgize::{
-    export::HtmlExport,
-    export::{from_fn_with_ctx, Container, Event, Traverser},
+    ast::Headline,
+    export::{HtmlExport, HtmlHandler, HtmlTraverser, TraversalContext, Traverser, Visit},
     Org,
 };
 use slugify::slugify;
 use std::cmp::min;
 use std::env::args;
 
+#[derive(Default)]
+struct SlugifyHtml(HtmlExport);
+
+impl HtmlHandler for SlugifyHtml {
+    fn html(&mut self) -> &mut HtmlExport {
+        &mut self.0
+    }
+
+    fn headline(&mut self, headline: Headline, visit: Visit, ctx: &mut TraversalContext) {
+        if visit == Visit::Enter {
+            let level = min(headline.level(), 6);
+            let title = headline.title().map(|e| e.to_string()).collect::<String>();
+            self.0.push_str(format!(
+                "<h{level}><a id=\"{0}\" href=\"#{0}\">",
+                slugify!(&title)
+            ));
+            for elem in headline.title() {
+                HtmlTraverser(&mut *self).element(elem, ctx);
+            }
+            self.0.push_str(format!("</a></h{level}>"));
+        }
+    }
+}
+
 fn main() {
     let args: Vec<_> = args().collect();
 
     if args.len() < 2 {
         eprintln!("Usage: {} <org-mode-string>", args[0]);
     } else {
-        let mut html_export = HtmlExport::default();
-
-        let mut handler = from_fn_with_ctx(|event, ctx| {
-            if let Event::Enter(Container::Headline(headline)) = event {
-                let level = min(headline.level(), 6);
-                let title = headline.title().map(|e| e.to_string()).collect::<String>();
-                html_export.push_str(format!(
-                    "<h{level}><a id=\"{0}\" href=\"#{0}\">",
-                    slugify!(&title)
-                ));
-                for elem in headline.title() {
-                    html_export.element(elem, ctx);
-                }
-                html_export.push_str(format!("</a></h{level}>"));
-            } else {
-                // forward to default html export
-                html_export.event(event, ctx);
-            }
-        });
+        let mut html = SlugifyHtml::default();
 
-        Org::parse(&args[1]).traverse(&mut handler);
+        Org::parse(&args[1]).traverse(&mut HtmlTraverser(&mut html));
 
-        println!("{}", html_export.finish());
+        println!("{}", html.0.finish());
     }
 }
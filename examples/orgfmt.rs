@@ -0,0 +1,18 @@
+//! ```bash
+//! cargo run --example orgfmt notes.org
+//! ```
+
+use orgize::Org;
+use std::env::args;
+use std::fs;
+
+fn main() {
+    let args: Vec<_> = args().collect();
+
+    if args.len() < 2 {
+        eprintln!("Usage: {} <org-file>", args[0]);
+    } else {
+        let content = fs::read_to_string(&args[1]).expect("failed to read file");
+        print!("{}", Org::parse(content).format());
+    }
+}
@@ -52,4 +52,43 @@ impl AffiliatedKeyword {
             .filter_map(filter_token(SyntaxKind::TEXT))
             .last()
     }
+
+    /// Parses value of `#+ATTR_*` keyword as `:key value` pairs
+    ///
+    /// Keys are returned without the leading colon, values can contain spaces
+    /// and are empty if omitted.
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::AffiliatedKeyword};
+    ///
+    /// let keyword = Org::parse("#+ATTR_HTML: :width 300 :alt a cat :controls\n[[a.png]]").first_node::<AffiliatedKeyword>().unwrap();
+    /// assert_eq!(
+    ///     keyword.attributes(),
+    ///     vec![
+    ///         ("width".to_string(), "300".to_string()),
+    ///         ("alt".to_string(), "a cat".to_string()),
+    ///         ("controls".to_string(), "".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn attributes(&self) -> Vec<(String, String)> {
+        let mut attributes: Vec<(String, String)> = vec![];
+        let value = self.value().unwrap_or_default();
+        for word in value.split_whitespace() {
+            match (word.strip_prefix(':'), attributes.last_mut()) {
+                (Some(key), _) if !key.is_empty() => {
+                    attributes.push((key.to_string(), String::new()))
+                }
+                (_, Some((_, value))) => {
+                    if !value.is_empty() {
+                        value.push(' ');
+                    }
+                    value.push_str(word);
+                }
+                // words before the first key are ignored
+                (_, None) => {}
+            }
+        }
+        attributes
+    }
 }
@@ -0,0 +1,114 @@
+use crate::SyntaxKind;
+
+use super::{filter_token, BabelCall};
+
+impl BabelCall {
+    /// Raw value of babel call, without leading whitespace
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::BabelCall};
+    ///
+    /// let call = Org::parse("#+CALL: double[:results raw](n=4)").first_node::<BabelCall>().unwrap();
+    /// assert_eq!(call.value(), "double[:results raw](n=4)");
+    /// ```
+    pub fn value(&self) -> String {
+        self.syntax
+            .children_with_tokens()
+            .filter_map(filter_token(SyntaxKind::TEXT))
+            .nth(1)
+            .map(|t| t.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Name of the called block
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::BabelCall};
+    ///
+    /// let call = Org::parse("#+CALL: double(n=4)").first_node::<BabelCall>().unwrap();
+    /// assert_eq!(call.call(), "double");
+    /// let call = Org::parse("#+CALL: double").first_node::<BabelCall>().unwrap();
+    /// assert_eq!(call.call(), "double");
+    /// ```
+    pub fn call(&self) -> String {
+        self.parts().0
+    }
+
+    /// Header arguments applied to the called block, e.g. `[:results raw]`
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::BabelCall};
+    ///
+    /// let call = Org::parse("#+CALL: double[:results raw](n=4)").first_node::<BabelCall>().unwrap();
+    /// assert_eq!(call.inside_header().unwrap(), ":results raw");
+    /// let call = Org::parse("#+CALL: double(n=4)").first_node::<BabelCall>().unwrap();
+    /// assert!(call.inside_header().is_none());
+    /// ```
+    pub fn inside_header(&self) -> Option<String> {
+        self.parts().1
+    }
+
+    /// Arguments passed to the called block, e.g. `(n=4)`
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::BabelCall};
+    ///
+    /// let call = Org::parse("#+CALL: double(n=4, m=\"(a)\")").first_node::<BabelCall>().unwrap();
+    /// assert_eq!(call.arguments().unwrap(), "n=4, m=\"(a)\"");
+    /// ```
+    pub fn arguments(&self) -> Option<String> {
+        self.parts().2
+    }
+
+    /// Header arguments applied to the call results, e.g. `[:results html]`
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::BabelCall};
+    ///
+    /// let call = Org::parse("#+CALL: double(n=4)[:results html]").first_node::<BabelCall>().unwrap();
+    /// assert_eq!(call.end_header().unwrap(), ":results html");
+    /// ```
+    pub fn end_header(&self) -> Option<String> {
+        self.parts().3
+    }
+
+    /// Splits value into `name[inside-header](arguments)[end-header]`
+    fn parts(&self) -> (String, Option<String>, Option<String>, Option<String>) {
+        let value = self.value();
+        let mut s = value.as_str();
+
+        let name_end = s.find(['[', '(']).unwrap_or(s.len());
+        let name = s[..name_end].trim().to_string();
+        s = &s[name_end..];
+
+        let inside_header = balanced(&mut s, '[', ']');
+        let arguments = balanced(&mut s, '(', ')');
+        s = s.trim_start();
+        let end_header = balanced(&mut s, '[', ']');
+
+        (name, inside_header, arguments, end_header)
+    }
+}
+
+/// Takes content between balanced `open` and `close` at the start of `s`
+fn balanced(s: &mut &str, open: char, close: char) -> Option<String> {
+    let rest = s.strip_prefix(open)?;
+    let mut depth = 1;
+    let mut quoted = false;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            _ if quoted => {}
+            _ if c == open => depth += 1,
+            _ if c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    *s = &rest[i + 1..];
+                    return Some(rest[..i].to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
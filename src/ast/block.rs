@@ -1,6 +1,82 @@
+use rowan::ast::AstNode;
+
 use crate::SyntaxKind;
 
-use super::{filter_token, ExportBlock, SourceBlock, Token};
+use super::{filter_token, ExampleBlock, ExportBlock, SourceBlock, SpecialBlock, Token};
+
+/// A line of source block, returned by [`SourceBlock::lines`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLine {
+    /// Line number, `None` if line numbering is not enabled
+    pub number: Option<u32>,
+    /// Unescaped line content, without coderef label and line ending
+    pub content: String,
+    /// Coderef label, e.g. `label` in `(ref:label)`
+    pub label: Option<String>,
+}
+
+/// Line numbering and coderef options parsed from switches
+#[derive(Default)]
+struct Switches {
+    /// `(continued, offset)` from `-n` or `+n`
+    number_lines: Option<(bool, u32)>,
+    /// `-r`
+    remove_labels: bool,
+    /// `-k`
+    keep_labels: bool,
+    /// `-l "format"`
+    label_format: Option<String>,
+}
+
+impl Switches {
+    fn parse(s: &str) -> Self {
+        let mut switches = Switches::default();
+        let mut iter = arguments(s).peekable();
+
+        while let Some(switch) = iter.next() {
+            match switch {
+                "-n" | "+n" => {
+                    let offset = iter
+                        .next_if(|s| s.bytes().all(|b| b.is_ascii_digit()))
+                        .and_then(|s| s.parse().ok())
+                        .unwrap_or(1);
+                    switches.number_lines = Some((switch == "+n", offset));
+                }
+                "-r" => switches.remove_labels = true,
+                "-k" => switches.keep_labels = true,
+                "-l" => {
+                    if let Some(format) = iter.next() {
+                        let format = format
+                            .strip_prefix('"')
+                            .and_then(|s| s.strip_suffix('"'))
+                            .unwrap_or(format);
+                        switches.label_format = Some(format.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        switches
+    }
+}
+
+/// Splits switches by whitespace, keeping a double-quoted string as one argument
+fn arguments(mut s: &str) -> impl Iterator<Item = &str> {
+    std::iter::from_fn(move || {
+        s = s.trim_start_matches(|c: char| c.is_ascii_whitespace());
+        if s.is_empty() {
+            return None;
+        }
+        let len = match s.strip_prefix('"') {
+            Some(quoted) => quoted.find('"').map_or(s.len(), |i| i + 2),
+            None => s.find(|c: char| c.is_ascii_whitespace()).unwrap_or(s.len()),
+        };
+        let (argument, rest) = s.split_at(len);
+        s = rest;
+        Some(argument)
+    })
+}
 
 impl SourceBlock {
     /// ```rust
@@ -76,6 +152,8 @@ impl SourceBlock {
     /// assert_eq!(block.value(), "");
     /// let block = Org::parse("#+begin_src\n,* foo \n,#+ bar\n#+end_src").first_node::<SourceBlock>().unwrap();
     /// assert_eq!(block.value(), "* foo \n#+ bar\n");
+    /// let block = Org::parse("#+begin_src\n  ,#+end_src\n,,* foo\n#+end_src").first_node::<SourceBlock>().unwrap();
+    /// assert_eq!(block.value(), "  #+end_src\n,* foo\n");
     /// ````
     pub fn value(&self) -> String {
         self.syntax
@@ -86,6 +164,138 @@ impl SourceBlock {
             .filter_map(filter_token(SyntaxKind::TEXT))
             .fold(String::new(), |acc, value| acc + &value)
     }
+
+    fn parsed_switches(&self) -> Switches {
+        self.switches()
+            .map(|s| Switches::parse(&s))
+            .unwrap_or_default()
+    }
+
+    /// Returns coderef label format, defaults to `(ref:%s)`
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::SourceBlock};
+    ///
+    /// let block = Org::parse("#+begin_src c\n#+end_src").first_node::<SourceBlock>().unwrap();
+    /// assert_eq!(block.label_format(), "(ref:%s)");
+    /// let block = Org::parse("#+begin_src c -l \"<<%s>>\"\n#+end_src").first_node::<SourceBlock>().unwrap();
+    /// assert_eq!(block.label_format(), "<<%s>>");
+    /// let block = Org::parse("#+begin_src c -l \"// (%s)\" -r\nint x; // (init)\n#+end_src").first_node::<SourceBlock>().unwrap();
+    /// assert_eq!(block.label_format(), "// (%s)");
+    /// assert!(!block.retain_labels());
+    /// assert_eq!(block.lines()[0].label.as_deref(), Some("init"));
+    /// ```
+    pub fn label_format(&self) -> String {
+        self.parsed_switches()
+            .label_format
+            .unwrap_or_else(|| "(ref:%s)".into())
+    }
+
+    /// Returns `true` if coderef labels should be kept in exported code,
+    /// i.e. `-r` switch is absent, or both `-n` and `-k` are present
+    pub fn retain_labels(&self) -> bool {
+        let switches = self.parsed_switches();
+        !switches.remove_labels || (switches.number_lines.is_some() && switches.keep_labels)
+    }
+
+    /// Returns `true` if links to coderefs should display the label instead
+    /// of the line number
+    pub fn use_labels(&self) -> bool {
+        let switches = self.parsed_switches();
+        !switches.remove_labels && !switches.keep_labels
+    }
+
+    /// Returns the number of the first line, `None` if line numbering is not enabled
+    ///
+    /// `+n` continues numbering from the previous numbered source block.
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::SourceBlock};
+    /// use rowan::ast::AstNode;
+    ///
+    /// let org = Org::parse("#+begin_src c -n 10\na\nb\n#+end_src\n#+begin_src c\n#+end_src\n#+begin_src c +n\n#+end_src");
+    /// let blocks: Vec<_> = org.document().syntax().descendants().filter_map(SourceBlock::cast).collect();
+    /// assert_eq!(blocks[0].first_line_number(), Some(10));
+    /// assert_eq!(blocks[1].first_line_number(), None);
+    /// assert_eq!(blocks[2].first_line_number(), Some(12));
+    /// ```
+    pub fn first_line_number(&self) -> Option<u32> {
+        let (continued, offset) = self.parsed_switches().number_lines?;
+
+        if !continued {
+            return Some(offset);
+        }
+
+        // number of the last line in previous numbered block, which is
+        // negative after an empty block numbered from zero
+        let mut last = 0i64;
+        let root = self.syntax.ancestors().last()?;
+        for block in root.descendants().filter_map(SourceBlock::cast) {
+            if block == *self {
+                break;
+            }
+            let first = match block.parsed_switches().number_lines {
+                Some((true, offset)) => last + offset as i64,
+                Some((false, offset)) => offset as i64,
+                None => continue,
+            };
+            last = first + block.value().lines().count() as i64 - 1;
+        }
+
+        Some((last + offset as i64).clamp(0, u32::MAX as i64) as u32)
+    }
+
+    /// Returns lines of source code, with line numbers and coderef labels
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::SourceBlock};
+    ///
+    /// let block = Org::parse("#+begin_src c -n\nint a; (ref:decl)\na++;\n#+end_src").first_node::<SourceBlock>().unwrap();
+    /// let lines = block.lines();
+    /// assert_eq!(lines.len(), 2);
+    /// assert_eq!(lines[0].number, Some(1));
+    /// assert_eq!(lines[0].content, "int a;");
+    /// assert_eq!(lines[0].label.as_deref(), Some("decl"));
+    /// assert_eq!(lines[1].number, Some(2));
+    /// assert_eq!(lines[1].content, "a++;");
+    /// assert_eq!(lines[1].label, None);
+    /// ```
+    pub fn lines(&self) -> Vec<SourceLine> {
+        let format = self.label_format();
+        let first = self.first_line_number();
+
+        self.value()
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let (content, label) = match split_label(line, &format) {
+                    Some((content, label)) => (content, Some(label.to_string())),
+                    None => (line, None),
+                };
+                SourceLine {
+                    number: first.map(|n| n.saturating_add(i as u32)),
+                    content: content.to_string(),
+                    label,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Splits coderef label at the end of line, returns content and label
+fn split_label<'a>(line: &'a str, format: &str) -> Option<(&'a str, &'a str)> {
+    let (prefix, suffix) = format.split_once("%s")?;
+    let line = line.trim_end();
+    let rest = line.strip_suffix(suffix)?;
+    let start = rest.rfind(prefix)?;
+    let label = &rest[start + prefix.len()..];
+
+    let valid = label.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ' ');
+
+    valid.then(|| (line[..start].trim_end(), label))
 }
 
 impl ExportBlock {
@@ -106,4 +316,62 @@ impl ExportBlock {
             .flat_map(|n| n.children_with_tokens())
             .find_map(filter_token(SyntaxKind::EXPORT_BLOCK_TYPE))
     }
+
+    /// Unescaped block content
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::ExportBlock};
+    ///
+    /// let block = Org::parse("#+begin_export html\n<p>\n,#+not a keyword\n#+end_export").first_node::<ExportBlock>().unwrap();
+    /// assert_eq!(block.value(), "<p>\n#+not a keyword\n");
+    /// ````
+    pub fn value(&self) -> String {
+        self.syntax
+            .children()
+            .find(|e| e.kind() == SyntaxKind::BLOCK_CONTENT)
+            .into_iter()
+            .flat_map(|n| n.children_with_tokens())
+            .filter_map(filter_token(SyntaxKind::TEXT))
+            .fold(String::new(), |acc, value| acc + &value)
+    }
+}
+
+impl SpecialBlock {
+    /// Block type, e.g. `warning` of `#+begin_warning`
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::SpecialBlock};
+    ///
+    /// let block = Org::parse("#+begin_warning\ntext\n#+end_warning").first_node::<SpecialBlock>().unwrap();
+    /// assert_eq!(block.ty().unwrap(), "warning");
+    /// ````
+    pub fn ty(&self) -> Option<Token> {
+        self.syntax
+            .children()
+            .find(|e| e.kind() == SyntaxKind::BLOCK_BEGIN)
+            .into_iter()
+            .flat_map(|n| n.children_with_tokens())
+            .filter_map(filter_token(SyntaxKind::TEXT))
+            .nth(1)
+    }
+}
+
+impl ExampleBlock {
+    /// Unescaped block content
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::ExampleBlock};
+    ///
+    /// let block = Org::parse("#+begin_example\n,* foo\nbar\n#+end_example").first_node::<ExampleBlock>().unwrap();
+    /// assert_eq!(block.value(), "* foo\nbar\n");
+    /// ````
+    pub fn value(&self) -> String {
+        self.syntax
+            .children()
+            .find(|e| e.kind() == SyntaxKind::BLOCK_CONTENT)
+            .into_iter()
+            .flat_map(|n| n.children_with_tokens())
+            .filter_map(filter_token(SyntaxKind::TEXT))
+            .fold(String::new(), |acc, value| acc + &value)
+    }
 }
@@ -70,10 +70,15 @@ impl Drawer {
     /// let org = Org::parse("* Heading\n:LOGBOOK:\n:END:");
     /// let drawer = org.first_node::<Drawer>().unwrap();
     /// assert_eq!(drawer.name(), "LOGBOOK");
+    ///
+    /// let org = Org::parse("#+RESULTS:\n:results:\n:end:");
+    /// let drawer = org.first_node::<Drawer>().unwrap();
+    /// assert_eq!(drawer.name(), "results");
     /// ```
     pub fn name(&self) -> Token {
         self.syntax
-            .first_child()
+            .children()
+            .find(|n| n.kind() == SyntaxKind::DRAWER_BEGIN)
             .and_then(|n| {
                 n.children_with_tokens()
                     .filter_map(|e| e.into_token())
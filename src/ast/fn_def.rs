@@ -0,0 +1,26 @@
+use crate::syntax::SyntaxKind;
+
+use super::{filter_token, FnDef, Token};
+
+impl FnDef {
+    /// Returns footnote label
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::FnDef};
+    ///
+    /// let fn_def = Org::parse("[fn:1] https://orgmode.org").first_node::<FnDef>().unwrap();
+    /// assert_eq!(fn_def.label(), "1");
+    /// let fn_def = Org::parse("#+NAME: a\n[fn:WORD-1] text").first_node::<FnDef>().unwrap();
+    /// assert_eq!(fn_def.label(), "WORD-1");
+    /// ```
+    pub fn label(&self) -> Token {
+        self.syntax
+            .children_with_tokens()
+            .filter_map(filter_token(SyntaxKind::TEXT))
+            .nth(1)
+            .unwrap_or_else(|| {
+                debug_assert!(false, "fn def must contains two TEXT");
+                Token::default()
+            })
+    }
+}
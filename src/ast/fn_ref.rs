@@ -0,0 +1,48 @@
+use crate::syntax::SyntaxKind;
+
+use super::{filter_token, FnRef, Token};
+
+impl FnRef {
+    /// Returns footnote label, empty for anonymous footnote
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::FnRef};
+    ///
+    /// let fn_ref = Org::parse("text [fn:1]").first_node::<FnRef>().unwrap();
+    /// assert_eq!(fn_ref.label(), "1");
+    /// let fn_ref = Org::parse("text [fn:note:inline]").first_node::<FnRef>().unwrap();
+    /// assert_eq!(fn_ref.label(), "note");
+    /// let fn_ref = Org::parse("text [fn::anonymous]").first_node::<FnRef>().unwrap();
+    /// assert_eq!(fn_ref.label(), "");
+    /// ```
+    pub fn label(&self) -> Token {
+        self.syntax
+            .children_with_tokens()
+            .filter_map(filter_token(SyntaxKind::TEXT))
+            .nth(1)
+            .unwrap_or_else(|| {
+                debug_assert!(false, "fn ref must contains two TEXT");
+                Token::default()
+            })
+    }
+
+    /// Returns `true` if this footnote reference contains an inline definition
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::FnRef};
+    ///
+    /// let fn_ref = Org::parse("text [fn:1]").first_node::<FnRef>().unwrap();
+    /// assert!(!fn_ref.is_inline());
+    /// let fn_ref = Org::parse("text [fn:note:inline]").first_node::<FnRef>().unwrap();
+    /// assert!(fn_ref.is_inline());
+    /// let fn_ref = Org::parse("text [fn::]").first_node::<FnRef>().unwrap();
+    /// assert!(fn_ref.is_inline());
+    /// ```
+    pub fn is_inline(&self) -> bool {
+        self.syntax
+            .children_with_tokens()
+            .filter(|e| e.kind() == SyntaxKind::COLON)
+            .count()
+            > 1
+    }
+}
@@ -90,6 +90,7 @@ const nodes = [
   {
     struct: "BabelCall",
     kind: ["BABEL_CALL"],
+    affiliated_keywords: true,
   },
   {
     struct: "AffiliatedKeyword",
@@ -252,6 +253,7 @@ const nodes = [
   {
     struct: "LatexEnvironment",
     kind: ["LATEX_ENVIRONMENT"],
+    affiliated_keywords: true,
   },
   {
     struct: "LatexFragment",
@@ -347,6 +349,7 @@ impl ${node.struct} {
     content += `    pub fn plot(&self) -> Option<AffiliatedKeyword> { affiliated_keyword(&self.syntax, |k| k == "PLOT") }\n`;
     content += `    pub fn results(&self) -> Option<AffiliatedKeyword> { affiliated_keyword(&self.syntax, |k| k == "RESULTS") }\n`;
     content += `    pub fn attr(&self, backend: &str) -> Option<AffiliatedKeyword> { affiliated_keyword(&self.syntax, |k| k.starts_with("ATTR_") && &k[5..] == backend) }\n`;
+    content += `    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> { super::attributes(&self.syntax, backend) }\n`;
   }
   content += `}\n`;
 }
@@ -138,6 +138,9 @@ impl Paragraph {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -344,6 +347,9 @@ impl OrgTable {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -448,6 +454,9 @@ impl List {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -549,6 +558,9 @@ impl DynBlock {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -603,6 +615,29 @@ impl BabelCall {
     pub fn end(&self) -> TextSize {
         self.syntax.text_range().end()
     }
+    pub fn caption(&self) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| k == "CAPTION")
+    }
+    pub fn header(&self) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| k == "HEADER")
+    }
+    pub fn name(&self) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| k == "NAME")
+    }
+    pub fn plot(&self) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| k == "PLOT")
+    }
+    pub fn results(&self) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| k == "RESULTS")
+    }
+    pub fn attr(&self, backend: &str) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| {
+            k.starts_with("ATTR_") && &k[5..] == backend
+        })
+    }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -740,6 +775,9 @@ impl FnDef {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -793,6 +831,9 @@ impl Comment {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -876,6 +917,9 @@ impl FixedWidth {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -923,6 +967,9 @@ impl SpecialBlock {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -970,6 +1017,9 @@ impl QuoteBlock {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1017,6 +1067,9 @@ impl CenterBlock {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1064,6 +1117,9 @@ impl VerseBlock {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1111,6 +1167,9 @@ impl CommentBlock {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1158,6 +1217,9 @@ impl ExampleBlock {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1205,6 +1267,9 @@ impl ExportBlock {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1252,6 +1317,9 @@ impl SourceBlock {
             k.starts_with("ATTR_") && &k[5..] == backend
         })
     }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1744,6 +1812,29 @@ impl LatexEnvironment {
     pub fn end(&self) -> TextSize {
         self.syntax.text_range().end()
     }
+    pub fn caption(&self) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| k == "CAPTION")
+    }
+    pub fn header(&self) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| k == "HEADER")
+    }
+    pub fn name(&self) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| k == "NAME")
+    }
+    pub fn plot(&self) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| k == "PLOT")
+    }
+    pub fn results(&self) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| k == "RESULTS")
+    }
+    pub fn attr(&self, backend: &str) -> Option<AffiliatedKeyword> {
+        affiliated_keyword(&self.syntax, |k| {
+            k.starts_with("ATTR_") && &k[5..] == backend
+        })
+    }
+    pub fn attributes(&self, backend: &str) -> Vec<(String, String)> {
+        super::attributes(&self.syntax, backend)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -69,12 +69,13 @@ impl InlineCall {
     ///
     /// let call = Org::parse("call_square[:results output](4)[:results html]").first_node::<InlineCall>().unwrap();
     /// assert_eq!(call.end_header().unwrap(), ":results html");
+    /// let call = Org::parse("call_square(4)[:results html]").first_node::<InlineCall>().unwrap();
+    /// assert_eq!(call.end_header().unwrap(), ":results html");
     /// ```
     pub fn end_header(&self) -> Option<Token> {
         self.syntax
             .children_with_tokens()
-            .skip_while(|e| e.kind() != SyntaxKind::L_BRACKET)
-            .skip(1)
+            .skip_while(|e| e.kind() != SyntaxKind::R_PARENS)
             .skip_while(|e| e.kind() != SyntaxKind::L_BRACKET)
             .nth(1)
             .map(|e| {
@@ -3,12 +3,15 @@ mod generated;
 
 
 mod affiliated_keyword;
+mod babel_call;
 mod block;
 mod clock;
 mod comment;
 mod drawer;
 mod entity;
 mod fixed_width;
+mod fn_def;
+mod fn_ref;
 mod headline;
 mod inline_call;
 mod inline_src;
@@ -28,6 +31,7 @@ use std::{
     ops::Deref,
 };
 
+pub use block::*;
 pub use generated::*;
 pub use headline::*;
 pub use rowan::ast::support::*;
@@ -61,6 +65,32 @@ pub fn token(parent: &SyntaxNode, kind: SyntaxKind) -> Option<Token> {
     rowan::ast::support::token(parent, kind).map(|t| Token(Some(t)))
 }
 
+/// Returns merged attributes of all `#+ATTR_<backend>` affiliated keywords of node
+pub(crate) fn attributes(node: &SyntaxNode, backend: &str) -> Vec<(String, String)> {
+    let mut attributes: Vec<(String, String)> = vec![];
+    for keyword in node
+        .children()
+        .take_while(|n| n.kind() == SyntaxKind::AFFILIATED_KEYWORD)
+        .filter_map(AffiliatedKeyword::cast)
+    {
+        let key = keyword.key();
+        let is_backend = key
+            .get(..5)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("ATTR_"))
+            && key[5..].eq_ignore_ascii_case(backend);
+        if !is_backend {
+            continue;
+        }
+        for (key, value) in keyword.attributes() {
+            // the first occurrence wins, like `plist-get`
+            if attributes.iter().all(|(k, _)| *k != key) {
+                attributes.push((key, value));
+            }
+        }
+    }
+    attributes
+}
+
 pub fn filter_token(
     kind: SyntaxKind,
 ) -> impl Fn(NodeOrToken<SyntaxNode, SyntaxToken>) -> Option<Token> {
@@ -0,0 +1,310 @@
+use rowan::ast::AstNode;
+
+use super::{HeaderArgs, Value};
+use crate::{
+    ast::{ExampleBlock, FixedWidth, List, OrgTable, OrgTableCell, OrgTableRow, SourceBlock},
+    SyntaxKind, SyntaxNode,
+};
+
+impl OrgTable {
+    /// Converts table into rows of values, the way babel reads it as `:var` input
+    ///
+    /// Cells are parsed as numbers when possible and rule rows are dropped.
+    /// The first row is treated as column names and dropped, see
+    /// [`OrgTable::column_names`].
+    ///
+    /// ```rust
+    /// use orgize::{rowan::ast::AstNode, ast::OrgTable, babel::{HeaderArgs, Value}, Org};
+    ///
+    /// let org = Org::parse("#+NAME: inventory\n| item | qty |\n|------+-----|\n| pen  | 3   |\n| ink  |     |");
+    /// let table = org.named("inventory").and_then(OrgTable::cast).unwrap();
+    ///
+    /// assert_eq!(
+    ///     table.to_values(&HeaderArgs::default()),
+    ///     vec![
+    ///         vec![Value::String("pen".into()), Value::Number(3.)],
+    ///         vec![Value::String("ink".into()), Value::String("".into())],
+    ///     ]
+    /// );
+    ///
+    /// let rows = table.to_values(&HeaderArgs::parse(":colnames no"));
+    /// assert_eq!(rows.len(), 3);
+    /// assert_eq!(rows[0], vec![Value::String("item".into()), Value::String("qty".into())]);
+    /// ```
+    pub fn to_values(&self, args: &HeaderArgs) -> Vec<Vec<Value>> {
+        self.syntax
+            .children()
+            .filter_map(OrgTableRow::cast)
+            .filter(|row| row.is_standard())
+            .skip(usize::from(self.column_names(args).is_some()))
+            .map(|row| {
+                row.syntax
+                    .children()
+                    .filter_map(OrgTableCell::cast)
+                    .map(|cell| Value::parse(&cell.syntax.to_string()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns column names, i.e. cells of the first row, if it's a header according to `:colnames`
+    ///
+    /// - `:colnames no`: no header
+    /// - `:colnames yes`: the first row is a header
+    /// - otherwise, the first row is a header if it's followed by a rule row
+    ///
+    /// ```rust
+    /// use orgize::{ast::OrgTable, babel::HeaderArgs, Org};
+    ///
+    /// let table = Org::parse("| a | b |\n|---+---|\n| 1 | 2 |").first_node::<OrgTable>().unwrap();
+    /// assert_eq!(table.column_names(&HeaderArgs::default()).unwrap(), ["a", "b"]);
+    /// assert!(table.column_names(&HeaderArgs::parse(":colnames no")).is_none());
+    ///
+    /// let table = Org::parse("| a | b |\n| 1 | 2 |").first_node::<OrgTable>().unwrap();
+    /// assert!(table.column_names(&HeaderArgs::default()).is_none());
+    /// assert_eq!(table.column_names(&HeaderArgs::parse(":colnames yes")).unwrap(), ["a", "b"]);
+    /// ```
+    pub fn column_names(&self, args: &HeaderArgs) -> Option<Vec<String>> {
+        let mut rows = self.syntax.children().filter_map(OrgTableRow::cast);
+        let first = rows.next().filter(|row| row.is_standard())?;
+
+        let header = match args.get("colnames") {
+            Some("no") => false,
+            Some("yes") => true,
+            _ => rows.next().is_some_and(|row| row.is_rule()),
+        };
+
+        header.then(|| {
+            first
+                .syntax
+                .children()
+                .filter_map(OrgTableCell::cast)
+                .map(|cell| cell.syntax.to_string().trim().to_string())
+                .collect()
+        })
+    }
+}
+
+impl List {
+    /// Converts list into values, the way babel reads it as `:var` input
+    ///
+    /// Each item becomes its contents, parsed as a number when possible. An
+    /// item containing sublists becomes a list of its contents and the
+    /// values of its sublist items.
+    ///
+    /// ```rust
+    /// use orgize::{rowan::ast::AstNode, ast::List, babel::Value, Org};
+    ///
+    /// let org = Org::parse("#+NAME: todo\n- buy\n  - milk\n  - 2 eggs\n- 42");
+    /// let list = org.named("todo").and_then(List::cast).unwrap();
+    ///
+    /// assert_eq!(
+    ///     list.to_values(),
+    ///     vec![
+    ///         Value::List(vec![
+    ///             Value::String("buy".into()),
+    ///             Value::List(vec![
+    ///                 Value::String("milk".into()),
+    ///                 Value::String("2 eggs".into()),
+    ///             ]),
+    ///         ]),
+    ///         Value::Number(42.),
+    ///     ]
+    /// );
+    /// ```
+    pub fn to_values(&self) -> Vec<Value> {
+        self.items()
+            .map(|item| {
+                let contents = item
+                    .syntax
+                    .children()
+                    .filter(|n| n.kind() == SyntaxKind::LIST_ITEM_CONTENT)
+                    .flat_map(|n| n.children());
+
+                let mut text = String::new();
+                let mut children = vec![];
+                for node in contents {
+                    if node.kind() == SyntaxKind::LIST {
+                        children.extend(List::cast(node).unwrap().to_values());
+                    } else {
+                        text.push_str(&node_text(&node));
+                    }
+                }
+
+                // strips indentation of continuation lines
+                let text: Vec<_> = text.lines().map(str::trim).collect();
+                let value = Value::parse(&text.join("\n"));
+                if children.is_empty() {
+                    value
+                } else {
+                    Value::List(vec![value, Value::List(children)])
+                }
+            })
+            .collect()
+    }
+}
+
+/// Converts element into value
+///
+/// Tables and lists are converted by [`OrgTable::to_values`] and
+/// [`List::to_values`]. Single-line contents of other elements are parsed as
+/// numbers when possible, e.g. `: 42`.
+pub(super) fn element_value(node: &SyntaxNode, args: &HeaderArgs) -> Value {
+    let text = match node.kind() {
+        SyntaxKind::ORG_TABLE => {
+            let table = OrgTable::cast(node.clone()).unwrap();
+            return Value::List(table.to_values(args).into_iter().map(Value::List).collect());
+        }
+        SyntaxKind::LIST => return Value::List(List::cast(node.clone()).unwrap().to_values()),
+        SyntaxKind::FIXED_WIDTH => FixedWidth::cast(node.clone()).unwrap().value(),
+        SyntaxKind::EXAMPLE_BLOCK => ExampleBlock::cast(node.clone()).unwrap().value(),
+        SyntaxKind::SOURCE_BLOCK => SourceBlock::cast(node.clone()).unwrap().value(),
+        SyntaxKind::KEYWORD => String::new(),
+        SyntaxKind::DRAWER => node
+            .children()
+            .filter(|n| n.kind() == SyntaxKind::DRAWER_CONTENT)
+            .map(|n| n.to_string())
+            .collect(),
+        _ => node_text(node),
+    };
+
+    let text = text.trim_end_matches(['\n', '\r']);
+    if text.contains('\n') {
+        Value::String(text.to_string())
+    } else {
+        Value::parse(text)
+    }
+}
+
+/// Text of element, excluding affiliated keywords and trailing blank lines
+fn node_text(node: &SyntaxNode) -> String {
+    node.children_with_tokens()
+        .filter(|e| {
+            e.kind() != SyntaxKind::AFFILIATED_KEYWORD && e.kind() != SyntaxKind::BLANK_LINE
+        })
+        .map(|e| e.to_string())
+        .collect()
+}
+
+#[test]
+fn data() {
+    use crate::Org;
+
+    let org = Org::parse(
+        r#"#+NAME: inventory
+| item   | qty | price |
+|--------+-----+-------|
+| pen    |   3 |  1.50 |
+|--------+-----+-------|
+| paper  |     |    -2 |
+
+#+NAME: steps
+1. one
+   - a
+   - b
+
+   after
+2. two
+   1. nested
+      - deep
+
+#+NAME: plain
+| 1 | x |
+| 2 | y |"#,
+    );
+
+    let inventory = org.named("inventory").and_then(OrgTable::cast).unwrap();
+    assert_eq!(
+        inventory.column_names(&HeaderArgs::default()).unwrap(),
+        ["item", "qty", "price"]
+    );
+    insta::assert_debug_snapshot!(inventory.to_values(&HeaderArgs::default()), @r###"
+    [
+        [
+            String(
+                "pen",
+            ),
+            Number(
+                3.0,
+            ),
+            Number(
+                1.5,
+            ),
+        ],
+        [
+            String(
+                "paper",
+            ),
+            String(
+                "",
+            ),
+            Number(
+                -2.0,
+            ),
+        ],
+    ]
+    "###);
+    assert_eq!(
+        inventory
+            .to_values(&HeaderArgs::parse(":colnames no"))
+            .len(),
+        3
+    );
+
+    let plain = org.named("plain").and_then(OrgTable::cast).unwrap();
+    assert_eq!(plain.to_values(&HeaderArgs::default()).len(), 2);
+    assert_eq!(
+        plain.to_values(&HeaderArgs::parse(":colnames yes")),
+        vec![vec![Value::Number(2.), Value::String("y".into())]]
+    );
+
+    let steps = org.named("steps").and_then(List::cast).unwrap();
+    insta::assert_debug_snapshot!(steps.to_values(), @r###"
+    [
+        List(
+            [
+                String(
+                    "one\nafter",
+                ),
+                List(
+                    [
+                        String(
+                            "a",
+                        ),
+                        String(
+                            "b",
+                        ),
+                    ],
+                ),
+            ],
+        ),
+        List(
+            [
+                String(
+                    "two",
+                ),
+                List(
+                    [
+                        List(
+                            [
+                                String(
+                                    "nested",
+                                ),
+                                List(
+                                    [
+                                        String(
+                                            "deep",
+                                        ),
+                                    ],
+                                ),
+                            ],
+                        ),
+                    ],
+                ),
+            ],
+        ),
+    ]
+    "###);
+
+    assert!(org.named("missing").is_none());
+}
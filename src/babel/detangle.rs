@@ -0,0 +1,220 @@
+use std::fmt;
+
+use rowan::TextRange;
+
+use super::tangle::{linked_blocks, trim_body};
+use super::TangleConfig;
+use crate::{builder::escape_code, Org, SyntaxKind};
+
+/// Error returned by [`TangleConfig::detangle`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DetangleError {
+    /// Link comment has no matching `NAME ends here` comment
+    Unterminated { name: String },
+    /// No source block matches link comment
+    NotFound { name: String },
+}
+
+impl fmt::Display for DetangleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DetangleError::Unterminated { name } => {
+                write!(f, "{name} is never closed, expected \"{name} ends here\"")
+            }
+            DetangleError::NotFound { name } => write!(f, "source block {name} not found"),
+        }
+    }
+}
+
+impl std::error::Error for DetangleError {}
+
+impl TangleConfig {
+    /// Writes code from a tangled file back into source blocks, returns updated org source
+    ///
+    /// Code is located by link comments generated by `:comments link`. Only links
+    /// pointing to [`TangleConfig::source`] are handled. Lines starting with `*`
+    /// or `#+` are comma-escaped, and everything outside of updated blocks is
+    /// left untouched.
+    ///
+    /// ```rust
+    /// use orgize::{babel::TangleConfig, Org};
+    ///
+    /// let org = Org::parse("* Hello\n#+begin_src sh :tangle hello.sh :comments link\necho hello\n#+end_src\n");
+    /// let config = TangleConfig::default();
+    ///
+    /// let mut tangled = config.tangle(&org).remove(0).content;
+    /// assert_eq!(tangled, "# [[file:README.org::*Hello][Hello:1]]\necho hello\n# Hello:1 ends here\n");
+    ///
+    /// tangled = tangled.replace("echo hello", "echo world\n* done");
+    /// assert_eq!(
+    ///     config.detangle(&org, &tangled).unwrap(),
+    ///     "* Hello\n#+begin_src sh :tangle hello.sh :comments link\necho world\n,* done\n#+end_src\n"
+    /// );
+    /// ```
+    pub fn detangle(&self, org: &Org, tangled: &str) -> Result<String, DetangleError> {
+        let blocks = linked_blocks(org);
+        let mut edits: Vec<(TextRange, String)> = vec![];
+
+        let mut lines = tangled.split_inclusive('\n');
+        while let Some(line) = lines.next() {
+            let Some((heading, name)) = self.parse_link(line) else {
+                continue;
+            };
+
+            let end = format!("{name} ends here");
+            let mut body = String::new();
+            loop {
+                match lines.next() {
+                    Some(line) if line.trim_end().ends_with(&end) => break,
+                    Some(line) => body.push_str(line),
+                    None => return Err(DetangleError::Unterminated { name }),
+                }
+            }
+
+            let Some(linked) = blocks.iter().find(|b| {
+                b.name == name
+                    && (heading.is_none()
+                        || b.heading.as_ref().map(|h| h.title_raw().trim().to_string()) == heading)
+            }) else {
+                return Err(DetangleError::NotFound { name });
+            };
+
+            if !body.is_empty() && !body.ends_with('\n') {
+                body.push('\n');
+            }
+            if trim_body(&linked.block.value()) == body {
+                continue;
+            }
+
+            let Some(content) = linked
+                .block
+                .syntax
+                .children()
+                .find(|n| n.kind() == SyntaxKind::BLOCK_CONTENT)
+            else {
+                continue;
+            };
+
+            edits.push((content.text_range(), escape_code(&body)));
+        }
+
+        let mut output = org.to_org();
+        edits.sort_by_key(|(range, _)| range.start());
+        for (range, text) in edits.into_iter().rev() {
+            output.replace_range(std::ops::Range::<usize>::from(range), &text);
+        }
+        Ok(output)
+    }
+
+    /// Parses link comment like `# [[file:notes.org::*Heading][Heading:1]]`,
+    /// returns heading title and block name
+    fn parse_link(&self, line: &str) -> Option<(Option<String>, String)> {
+        let line = line.trim_end();
+        let (_, link) = line.split_once("[[")?;
+        let (target, name) = link.strip_suffix("]]")?.split_once("][")?;
+        let target = target.strip_prefix("file:")?;
+        let (path, search) = match target.split_once("::") {
+            Some((path, search)) => (path, Some(search)),
+            None => (target, None),
+        };
+        if path != self.source {
+            return None;
+        }
+        let heading = search
+            .and_then(|s| s.strip_prefix('*'))
+            .map(|s| s.trim().to_string());
+        Some((heading, name.to_string()))
+    }
+}
+
+#[test]
+fn detangle() {
+    let source = r#"#+TITLE: notes
+* Setup
+#+begin_src sh :tangle run.sh :comments link
+echo one
+#+end_src
+
+text *between*
+
+#+NAME: second
+#+begin_src sh :tangle run.sh :comments link
+  ,* quoted
+echo two
+#+end_src
+* Other
+#+begin_src sh :tangle run.sh :comments link
+#+end_src
+"#;
+    let org = Org::parse(source);
+    let config = TangleConfig {
+        source: "notes.org".into(),
+    };
+
+    let tangled = config.tangle(&org).remove(0).content;
+    insta::assert_snapshot!(tangled, @r###"
+    # [[file:notes.org::*Setup][Setup:1]]
+    echo one
+    # Setup:1 ends here
+
+    # [[file:notes.org::*Setup][second]]
+      * quoted
+    echo two
+    # second ends here
+
+    # [[file:notes.org::*Other][Other:1]]
+    # Other:1 ends here
+    "###);
+
+    // unchanged
+    assert_eq!(config.detangle(&org, &tangled).unwrap(), source);
+
+    let edited = tangled
+        .replace("echo one\n", "echo 1\n#+not keyword\n,,* comma\n")
+        .replace("  * quoted\n", "")
+        .replace("# Other:1 ends", "exit 0\n# Other:1 ends");
+    insta::assert_snapshot!(config.detangle(&org, &edited).unwrap(), @r###"
+    #+TITLE: notes
+    * Setup
+    #+begin_src sh :tangle run.sh :comments link
+    echo 1
+    ,#+not keyword
+    ,,,* comma
+    #+end_src
+
+    text *between*
+
+    #+NAME: second
+    #+begin_src sh :tangle run.sh :comments link
+    echo two
+    #+end_src
+    * Other
+    #+begin_src sh :tangle run.sh :comments link
+    exit 0
+    #+end_src
+    "###);
+
+    // links to other files are ignored
+    assert_eq!(
+        config
+            .detangle(&org, "# [[file:other.org::*Setup][Setup:1]]\n")
+            .unwrap(),
+        source
+    );
+
+    assert_eq!(
+        config.detangle(&org, "# [[file:notes.org::*Setup][Setup:1]]\necho"),
+        Err(DetangleError::Unterminated {
+            name: "Setup:1".into()
+        })
+    );
+    assert_eq!(
+        config.detangle(
+            &org,
+            "# [[file:notes.org::*Setup][Setup:3]]\n# Setup:3 ends here\n"
+        ),
+        Err(DetangleError::NotFound {
+            name: "Setup:3".into()
+        })
+    );
+}
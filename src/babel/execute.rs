@@ -0,0 +1,770 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    process::{Command, Stdio},
+    thread,
+};
+
+use rowan::ast::AstNode;
+
+use super::{data, results, HeaderArgs, VarValue};
+use crate::{
+    ast::{BabelCall, InlineCall, InlineSrc, SourceBlock},
+    Org, SyntaxKind, SyntaxNode,
+};
+
+/// Value passed to or returned from code execution
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    String(String),
+    Number(f64),
+    /// Lists, or tables as lists of rows
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// Parses a table cell or a line of output, numbers are recognized
+    pub fn parse(s: &str) -> Value {
+        let s = s.trim();
+        match s.parse::<f64>() {
+            Ok(n) if !s.is_empty() && n.is_finite() => Value::Number(n),
+            _ => Value::String(s.to_string()),
+        }
+    }
+
+    /// Returns rows of values if this value is a table, i.e. a list of lists
+    pub fn as_table(&self) -> Option<Vec<&[Value]>> {
+        match self {
+            Value::List(rows) if !rows.is_empty() => rows
+                .iter()
+                .map(|row| match row {
+                    Value::List(cells) => Some(cells.as_slice()),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    /// Writes strings and numbers as-is, lists are written as lines of
+    /// tab-separated values
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "{s}"),
+            Value::Number(n) => write!(f, "{n}"),
+            Value::List(values) => {
+                let sep = if self.as_table().is_some() {
+                    "\n"
+                } else {
+                    "\t"
+                };
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{sep}")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Error returned by code execution
+#[derive(Debug)]
+pub enum ExecuteError {
+    /// No executor is registered for language
+    UnsupportedLanguage { language: String },
+    /// Called block or referenced variable can't be found
+    NotFound { name: String },
+    /// Block references itself, directly or indirectly
+    Recursive { name: String },
+    /// Program can't be started
+    Io { program: String, error: io::Error },
+    /// Program exits with non-zero status
+    Failed {
+        program: String,
+        status: Option<i32>,
+        stderr: String,
+    },
+}
+
+impl fmt::Display for ExecuteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExecuteError::UnsupportedLanguage { language } => {
+                write!(f, "no executor for language {language:?}")
+            }
+            ExecuteError::NotFound { name } => write!(f, "{name} not found"),
+            ExecuteError::Recursive { name } => write!(f, "{name} references itself"),
+            ExecuteError::Io { program, error } => write!(f, "failed to run {program}: {error}"),
+            ExecuteError::Failed {
+                program,
+                status: Some(status),
+                stderr,
+            } => write!(f, "{program} exited with status {status}: {stderr}"),
+            ExecuteError::Failed {
+                program, stderr, ..
+            } => write!(f, "{program} terminated by signal: {stderr}"),
+        }
+    }
+}
+
+impl std::error::Error for ExecuteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExecuteError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Executes code of a language
+///
+/// ```rust
+/// use orgize::{
+///     ast::SourceBlock,
+///     babel::{ExecuteConfig, ExecuteError, Executor, HeaderArgs, Value},
+///     Org,
+/// };
+///
+/// struct Upper;
+///
+/// impl Executor for Upper {
+///     fn execute(
+///         &self,
+///         code: &str,
+///         _vars: &[(String, Value)],
+///         _args: &HeaderArgs,
+///     ) -> Result<Value, ExecuteError> {
+///         Ok(Value::String(code.to_uppercase()))
+///     }
+/// }
+///
+/// let org = Org::parse("#+begin_src upper\nhello\n#+end_src\n");
+/// let config = ExecuteConfig::new().with_executor("upper", Upper);
+///
+/// let block = org.first_node::<SourceBlock>().unwrap();
+/// assert_eq!(config.evaluate_block(&block).unwrap(), Value::String("HELLO\n".into()));
+/// assert_eq!(
+///     config.execute(&org).unwrap(),
+///     "#+begin_src upper\nhello\n#+end_src\n\n#+RESULTS:\n: HELLO\n"
+/// );
+/// ```
+pub trait Executor {
+    /// Executes `code` with resolved `:var` variables, and returns its result
+    ///
+    /// `args` contains all header arguments, e.g. `:results output` or `:dir`.
+    fn execute(
+        &self,
+        code: &str,
+        vars: &[(String, Value)],
+        args: &HeaderArgs,
+    ) -> Result<Value, ExecuteError>;
+}
+
+/// Executes shell scripts by piping them into `program`
+///
+/// Variables are assigned at the beginning of script. Tables are passed as
+/// lines of tab-separated values.
+#[derive(Clone, Debug)]
+pub struct ShellExecutor {
+    program: String,
+}
+
+impl ShellExecutor {
+    pub fn new(program: impl Into<String>) -> Self {
+        ShellExecutor {
+            program: program.into(),
+        }
+    }
+}
+
+impl Executor for ShellExecutor {
+    fn execute(
+        &self,
+        code: &str,
+        vars: &[(String, Value)],
+        args: &HeaderArgs,
+    ) -> Result<Value, ExecuteError> {
+        let mut script = String::new();
+        for (name, value) in vars {
+            let value = value.to_string().replace('\'', r"'\''");
+            script.push_str(&format!("{name}='{value}'\n"));
+        }
+        script.push_str(code);
+
+        let output = run(&self.program, &[], &script, args)?;
+        Ok(Value::String(output))
+    }
+}
+
+/// Executes python code by piping it into `program`
+///
+/// With `:results value`, which is the default, code is wrapped in a function,
+/// and the returned value is used as result. Lists are returned as tables.
+/// With `:results output`, standard output is used as result.
+#[derive(Clone, Debug)]
+pub struct PythonExecutor {
+    program: String,
+}
+
+impl PythonExecutor {
+    pub fn new(program: impl Into<String>) -> Self {
+        PythonExecutor {
+            program: program.into(),
+        }
+    }
+}
+
+/// Prints the returned value of `main`, first line is the kind of value
+const PYTHON_WRAPPER: &str = r#"
+__value = main()
+if isinstance(__value, (list, tuple)):
+    print("list")
+    __rows = __value if all(isinstance(r, (list, tuple)) for r in __value) else [__value]
+    for __row in __rows:
+        print("\t".join("" if c is None else str(c).replace("\t", " ").replace("\n", " ") for c in __row))
+else:
+    print("value")
+    print("" if __value is None else __value, end="")
+"#;
+
+impl Executor for PythonExecutor {
+    fn execute(
+        &self,
+        code: &str,
+        vars: &[(String, Value)],
+        args: &HeaderArgs,
+    ) -> Result<Value, ExecuteError> {
+        let mut script = String::new();
+        for (name, value) in vars {
+            script.push_str(&format!("{name} = {}\n", python_literal(value)));
+        }
+
+        if args.contains("results", "output") {
+            script.push_str(code);
+            let output = run(&self.program, &["-"], &script, args)?;
+            return Ok(Value::String(output));
+        }
+
+        script.push_str("def main():\n");
+        for line in code.lines() {
+            script.push_str("    ");
+            script.push_str(line);
+            script.push('\n');
+        }
+        script.push_str("    pass\n");
+        script.push_str(PYTHON_WRAPPER);
+
+        let output = run(&self.program, &["-"], &script, args)?;
+        match output.split_once('\n') {
+            Some(("list", rows)) => Ok(Value::List(
+                rows.lines()
+                    .map(|row| Value::List(row.split('\t').map(Value::parse).collect()))
+                    .collect(),
+            )),
+            Some((_, value)) => Ok(Value::parse(value)),
+            None => Ok(Value::String(String::new())),
+        }
+    }
+}
+
+fn python_literal(value: &Value) -> String {
+    match value {
+        Value::String(s) => {
+            let mut literal = String::from("\"");
+            for c in s.chars() {
+                match c {
+                    '"' => literal.push_str("\\\""),
+                    '\\' => literal.push_str("\\\\"),
+                    '\n' => literal.push_str("\\n"),
+                    c => literal.push(c),
+                }
+            }
+            literal.push('"');
+            literal
+        }
+        Value::Number(n) => n.to_string(),
+        Value::List(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(python_literal)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Runs `program` with `script` as standard input, in the directory from `:dir`
+fn run(
+    program: &str,
+    arguments: &[&str],
+    script: &str,
+    args: &HeaderArgs,
+) -> Result<String, ExecuteError> {
+    let io_error = |error: io::Error| ExecuteError::Io {
+        program: program.into(),
+        error,
+    };
+
+    let mut command = Command::new(program);
+    command
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = args.words("dir").first() {
+        command.current_dir(dir);
+    }
+
+    let mut child = command.spawn().map_err(io_error)?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+
+    // writes stdin from another thread while stdout and stderr are drained,
+    // otherwise a program filling its output pipe before reading all of its
+    // input would block forever
+    let (written, output) = thread::scope(|scope| {
+        let writer = scope.spawn(move || stdin.write_all(script.as_bytes()));
+        let output = child.wait_with_output();
+        (writer.join().expect("stdin writer panicked"), output)
+    });
+    let output = output.map_err(io_error)?;
+    match written {
+        // program exited without reading all of its input
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => {}
+        written => written.map_err(io_error)?,
+    }
+
+    if !output.status.success() {
+        return Err(ExecuteError::Failed {
+            program: program.into(),
+            status: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Execution configuration, holds executors keyed by language
+///
+/// Default config contains [`ShellExecutor`]s for `sh`, `shell` and `bash`,
+/// and a [`PythonExecutor`] for `python`, which runs `python3`.
+pub struct ExecuteConfig {
+    executors: Vec<(String, Box<dyn Executor>)>,
+}
+
+impl Default for ExecuteConfig {
+    fn default() -> Self {
+        ExecuteConfig::new()
+            .with_executor("sh", ShellExecutor::new("sh"))
+            .with_executor("shell", ShellExecutor::new("sh"))
+            .with_executor("bash", ShellExecutor::new("bash"))
+            .with_executor("python", PythonExecutor::new("python3"))
+    }
+}
+
+impl ExecuteConfig {
+    /// Creates a config without any executors
+    pub fn new() -> Self {
+        ExecuteConfig { executors: vec![] }
+    }
+
+    /// Registers executor for language, replacing existing one
+    pub fn with_executor(
+        mut self,
+        language: impl Into<String>,
+        executor: impl Executor + 'static,
+    ) -> Self {
+        let language = language.into();
+        self.executors.retain(|(l, _)| *l != language);
+        self.executors.push((language, Box::new(executor)));
+        self
+    }
+
+    /// Returns executor of language
+    pub fn executor(&self, language: &str) -> Option<&dyn Executor> {
+        self.executors
+            .iter()
+            .find(|(l, _)| l == language)
+            .map(|(_, e)| e.as_ref())
+    }
+
+    /// Executes source block
+    pub fn evaluate_block(&self, block: &SourceBlock) -> Result<Value, ExecuteError> {
+        self.block(block, &HeaderArgs::default(), &mut vec![])
+    }
+
+    /// Executes the source block called by babel call
+    pub fn evaluate_call(&self, call: &BabelCall) -> Result<Value, ExecuteError> {
+        self.call(&call.syntax, &call.call(), &call.header_args(), &mut vec![])
+    }
+
+    /// Executes the source block called by inline babel call
+    pub fn evaluate_inline_call(&self, call: &InlineCall) -> Result<Value, ExecuteError> {
+        self.call(&call.syntax, &call.call(), &call.header_args(), &mut vec![])
+    }
+
+    /// Executes inline source block
+    pub fn evaluate_inline_src(&self, src: &InlineSrc) -> Result<Value, ExecuteError> {
+        let args = src.header_args();
+        self.code(
+            &src.syntax,
+            &src.language(),
+            &src.value(),
+            &args,
+            &mut vec![],
+        )
+    }
+
+    /// Executes all source blocks, babel calls and their inline variants, and
+    /// returns org source with results inserted
+    ///
+    /// Results are formatted according to `:results`, see
+    /// [`format_results`](super::format_results). Existing `#+RESULTS:` of
+    /// the same element, or with the same `#+NAME`, are replaced. Elements with
+    /// `:eval no` or `:eval never` are skipped.
+    pub fn execute(&self, org: &Org) -> Result<String, ExecuteError> {
+        let root = org.document().syntax;
+        let mut edits = vec![];
+
+        for node in root.descendants() {
+            // e.g. source block inserted by `:results code`
+            if results::results_keyword(&node).is_some() {
+                continue;
+            }
+
+            let (value, args, language) = match node.kind() {
+                SyntaxKind::SOURCE_BLOCK => {
+                    let block = SourceBlock::cast(node.clone()).unwrap();
+                    let args = block.header_args();
+                    if !evaluable(&args) {
+                        continue;
+                    }
+                    let language = block.language().map(|l| l.to_string());
+                    (self.evaluate_block(&block)?, args, language)
+                }
+                SyntaxKind::BABEL_CALL => {
+                    let call = BabelCall::cast(node.clone()).unwrap();
+                    let args = call.header_args();
+                    if !evaluable(&args) {
+                        continue;
+                    }
+                    (self.evaluate_call(&call)?, args, None)
+                }
+                SyntaxKind::INLINE_CALL => {
+                    let call = InlineCall::cast(node.clone()).unwrap();
+                    let args = call.header_args();
+                    if !evaluable(&args) {
+                        continue;
+                    }
+                    (self.evaluate_inline_call(&call)?, args, None)
+                }
+                SyntaxKind::INLINE_SRC => {
+                    let src = InlineSrc::cast(node.clone()).unwrap();
+                    let args = src.header_args();
+                    if !evaluable(&args) {
+                        continue;
+                    }
+                    (self.evaluate_inline_src(&src)?, args, None)
+                }
+                _ => continue,
+            };
+
+            edits.extend(results::insert(&node, &value, &args, language.as_deref()));
+        }
+
+        let mut output = org.to_org();
+        edits.sort_by_key(|(range, _)| range.start());
+        for (range, text) in edits.into_iter().rev() {
+            output.replace_range(std::ops::Range::<usize>::from(range), &text);
+        }
+        Ok(output)
+    }
+
+    fn block(
+        &self,
+        block: &SourceBlock,
+        extra: &HeaderArgs,
+        stack: &mut Vec<String>,
+    ) -> Result<Value, ExecuteError> {
+        let mut args = block.header_args();
+        args.merge(extra);
+        let language = block.language().map(|l| l.to_string()).unwrap_or_default();
+        self.code(&block.syntax, &language, &block.value(), &args, stack)
+    }
+
+    fn call(
+        &self,
+        node: &SyntaxNode,
+        name: &str,
+        args: &HeaderArgs,
+        stack: &mut Vec<String>,
+    ) -> Result<Value, ExecuteError> {
+        let block = results::find_reference(node, name)
+            .and_then(SourceBlock::cast)
+            .ok_or_else(|| ExecuteError::NotFound { name: name.into() })?;
+        self.block(&block, args, stack)
+    }
+
+    fn code(
+        &self,
+        node: &SyntaxNode,
+        language: &str,
+        code: &str,
+        args: &HeaderArgs,
+        stack: &mut Vec<String>,
+    ) -> Result<Value, ExecuteError> {
+        let executor =
+            self.executor(language)
+                .ok_or_else(|| ExecuteError::UnsupportedLanguage {
+                    language: language.into(),
+                })?;
+
+        let mut vars = vec![];
+        for var in args.vars() {
+            let value = self.resolve(node, &var.value, args, stack)?;
+            vars.push((var.name, value));
+        }
+
+        executor.execute(code, &vars, args)
+    }
+
+    /// Resolves `:var` value, references to source blocks and babel calls
+    /// are resolved by executing them, see also [`Var::resolve`](super::Var::resolve)
+    ///
+    /// Tables are read according to `:colnames` of `args`.
+    fn resolve(
+        &self,
+        node: &SyntaxNode,
+        value: &VarValue,
+        args: &HeaderArgs,
+        stack: &mut Vec<String>,
+    ) -> Result<Value, ExecuteError> {
+        let (name, arguments) = match value {
+            VarValue::String(s) | VarValue::Lisp(s) => return Ok(Value::String(s.clone())),
+            VarValue::Number(n) => return Ok(Value::Number(*n)),
+            VarValue::Reference { name, args, .. } => (name, args),
+        };
+
+        if stack.contains(name) {
+            return Err(ExecuteError::Recursive { name: name.clone() });
+        }
+
+        let target = results::find_reference(node, name)
+            .ok_or_else(|| ExecuteError::NotFound { name: name.clone() })?;
+
+        stack.push(name.clone());
+        let value = match target.kind() {
+            SyntaxKind::SOURCE_BLOCK => {
+                let extra = HeaderArgs::parse(
+                    &arguments
+                        .as_ref()
+                        .map(|a| format!(":var {a}"))
+                        .unwrap_or_default(),
+                );
+                self.block(&SourceBlock::cast(target).unwrap(), &extra, stack)?
+            }
+            SyntaxKind::BABEL_CALL => {
+                let call = BabelCall::cast(target).unwrap();
+                self.call(&call.syntax, &call.call(), &call.header_args(), stack)?
+            }
+            _ => data::element_value(&target, args),
+        };
+        stack.pop();
+
+        Ok(value)
+    }
+}
+
+/// Returns `false` for `:eval no` or `:eval never`
+fn evaluable(args: &HeaderArgs) -> bool {
+    !matches!(args.get("eval"), Some("no" | "never"))
+}
+
+#[cfg(test)]
+struct Echo;
+
+#[cfg(test)]
+impl Executor for Echo {
+    fn execute(
+        &self,
+        code: &str,
+        vars: &[(String, Value)],
+        _: &HeaderArgs,
+    ) -> Result<Value, ExecuteError> {
+        match vars.iter().find(|(name, _)| name == "x") {
+            Some((_, value)) => Ok(value.clone()),
+            None => Ok(Value::parse(code)),
+        }
+    }
+}
+
+#[test]
+fn execute() {
+    let config = ExecuteConfig::new().with_executor("echo", Echo);
+
+    let org = Org::parse(
+        r#"#+NAME: tbl
+| a | 1 |
+|---+---|
+| b | 2 |
+
+#+NAME: one
+#+begin_src echo :var x=tbl
+#+end_src
+
+#+RESULTS: one
+: old
+
+#+begin_src echo :results drawer
+,* hello
+#+end_src
+#+RESULTS:
+: old
+text
+
+#+NAME: twice
+#+CALL: one(x="called")
+
+#+begin_src echo :eval no
+skipped
+#+end_src
+
+inline call_one(x=4) {{{results(=3=)}}} and src_echo[:results raw]{5}.
+
+#+begin_src echo :results list :var x=loop
+#+end_src"#,
+    );
+
+    insta::assert_snapshot!(config.execute(&org).unwrap_err(), @"loop not found");
+
+    let org = Org::parse(org.to_org().replace("x=loop", "x=tbl"));
+
+    insta::assert_snapshot!(config.execute(&org).unwrap(), @r###"
+    #+NAME: tbl
+    | a | 1 |
+    |---+---|
+    | b | 2 |
+
+    #+NAME: one
+    #+begin_src echo :var x=tbl
+    #+end_src
+
+    #+RESULTS: one
+    | b | 2 |
+
+    #+begin_src echo :results drawer
+    ,* hello
+    #+end_src
+    #+RESULTS:
+    :results:
+    * hello
+    :end:
+    text
+
+    #+NAME: twice
+    #+CALL: one(x="called")
+
+    #+RESULTS: twice
+    : called
+
+    #+begin_src echo :eval no
+    skipped
+    #+end_src
+
+    inline call_one(x=4) {{{results(=4=)}}} and src_echo[:results raw]{5} {{{results(5)}}}.
+
+    #+begin_src echo :results list :var x=tbl
+    #+end_src
+
+    #+RESULTS:
+    - b	2
+    "###);
+
+    let org = Org::parse("#+NAME: a\n#+begin_src echo :var x=a\n#+end_src");
+    assert!(matches!(
+        config.execute(&org),
+        Err(ExecuteError::Recursive { name }) if name == "a"
+    ));
+
+    let org = Org::parse("#+begin_src unknown\n#+end_src");
+    assert!(matches!(
+        config.execute(&org),
+        Err(ExecuteError::UnsupportedLanguage { language }) if language == "unknown"
+    ));
+}
+
+#[test]
+#[cfg(unix)]
+fn shell() {
+    let org = Org::parse(
+        "#+begin_src sh :var name=\"it's\" n=2 :dir /\nprintf '%s %s %s' \"$name\" \"$n\" \"$(pwd)\"\n#+end_src",
+    );
+    let block = org.first_node::<SourceBlock>().unwrap();
+    assert_eq!(
+        ExecuteConfig::default().evaluate_block(&block).unwrap(),
+        Value::String("it's 2 /".into())
+    );
+
+    let org = Org::parse("#+begin_src sh\necho error >&2; exit 3\n#+end_src");
+    let block = org.first_node::<SourceBlock>().unwrap();
+    assert!(matches!(
+        ExecuteConfig::default().evaluate_block(&block),
+        Err(ExecuteError::Failed { status: Some(3), stderr, .. }) if stderr == "error\n"
+    ));
+
+    // output fills the pipe before the whole script has been written
+    let org = Org::parse(format!(
+        "#+begin_src sh :results output\nyes | head -c 200000\n{}echo done\n#+end_src",
+        "# padding\n".repeat(20000)
+    ));
+    let block = org.first_node::<SourceBlock>().unwrap();
+    match ExecuteConfig::default().evaluate_block(&block).unwrap() {
+        Value::String(output) => {
+            assert_eq!(output.len(), 200005);
+            assert!(output.ends_with("done\n"));
+        }
+        value => panic!("unexpected value {value:?}"),
+    }
+}
+
+#[test]
+fn python() {
+    if Command::new("python3").arg("--version").output().is_err() {
+        return;
+    }
+
+    let config = ExecuteConfig::default();
+
+    let org = Org::parse(
+        "#+NAME: tbl\n| a | 1 |\n\n#+begin_src python :var t=tbl s=\"x\\\"y\"\nreturn [[t[0][0], t[0][1] + 1, s]]\n#+end_src",
+    );
+    let block = org.first_node::<SourceBlock>().unwrap();
+    assert_eq!(
+        config.evaluate_block(&block).unwrap(),
+        Value::List(vec![Value::List(vec![
+            Value::String("a".into()),
+            Value::Number(2.),
+            Value::String("x\"y".into())
+        ])])
+    );
+
+    let org = Org::parse("#+begin_src python :results output\nprint(1 + 1)\n#+end_src");
+    let block = org.first_node::<SourceBlock>().unwrap();
+    assert_eq!(
+        config.evaluate_block(&block).unwrap(),
+        Value::String("2\n".into())
+    );
+
+    let org = Org::parse("#+begin_src python\nx = 1\n#+end_src");
+    let block = org.first_node::<SourceBlock>().unwrap();
+    assert_eq!(
+        config.evaluate_block(&block).unwrap(),
+        Value::String(String::new())
+    );
+}
@@ -0,0 +1,596 @@
+use rowan::ast::{support, AstNode};
+
+use crate::{
+    ast::{
+        filter_token, AffiliatedKeyword, BabelCall, Headline, InlineCall, InlineSrc, Keyword,
+        SourceBlock,
+    },
+    SyntaxKind, SyntaxNode,
+};
+
+/// Header arguments, e.g. `:results output :var x=tbl :dir /tmp`
+///
+/// Arguments are kept in order, [`HeaderArgs::get`] returns the value of
+/// the last one with given name.
+///
+/// ```rust
+/// use orgize::babel::{HeaderArgs, VarValue};
+///
+/// let args = HeaderArgs::parse(r#":results output replace :var x=tbl y="a :b" :dir /tmp"#);
+/// assert_eq!(args.get("results"), Some("output replace"));
+/// assert_eq!(args.get("dir"), Some("/tmp"));
+/// assert_eq!(args.words("results"), vec!["output", "replace"]);
+///
+/// let vars = args.vars();
+/// assert_eq!(vars[0].name, "x");
+/// assert_eq!(vars[0].value, VarValue::Reference { name: "tbl".into(), index: None, args: None });
+/// assert_eq!(vars[1].value, VarValue::String("a :b".into()));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeaderArgs {
+    args: Vec<(String, String)>,
+}
+
+/// Variable assignment from `:var` header argument
+#[derive(Clone, Debug, PartialEq)]
+pub struct Var {
+    pub name: String,
+    pub value: VarValue,
+}
+
+/// Value of variable assignment
+#[derive(Clone, Debug, PartialEq)]
+pub enum VarValue {
+    /// Quoted string, e.g. `"hello"`
+    String(String),
+    /// Number, e.g. `42` or `3.14`
+    Number(f64),
+    /// Emacs lisp expression, e.g. `(+ 1 2)` or `'(1 2)`
+    Lisp(String),
+    /// Reference to a named table, list, block or results, e.g. `tbl`,
+    /// `tbl[1,2:3]` or `block(x=1)`
+    Reference {
+        name: String,
+        index: Option<String>,
+        args: Option<String>,
+    },
+}
+
+impl HeaderArgs {
+    /// Parses header arguments string
+    ///
+    /// A new argument starts with a colon after whitespace, which is not
+    /// inside quotes or parentheses.
+    pub fn parse(s: &str) -> HeaderArgs {
+        let mut args = vec![];
+
+        for part in split_top_level(s, |s, i| {
+            s[i..].starts_with(':') && (i == 0 || s[..i].ends_with(char::is_whitespace))
+        }) {
+            let part = part.trim();
+            let Some(part) = part.strip_prefix(':') else {
+                continue;
+            };
+            let (name, value) = part.split_once(char::is_whitespace).unwrap_or((part, ""));
+            if !name.is_empty() {
+                args.push((name.to_string(), value.trim().to_string()));
+            }
+        }
+
+        HeaderArgs { args }
+    }
+
+    /// Returns `true` if there's no argument
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// Returns an iterator over argument names and values, in order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.args.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns the value of the last argument with given name, without leading colon
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .rev()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns `true` if the value of given argument contains `word`,
+    /// e.g. `:results output replace` contains `output`
+    pub fn contains(&self, name: &str, word: &str) -> bool {
+        self.words(name).iter().any(|w| w == word)
+    }
+
+    /// Returns the value of given argument as a list of words, quotes are removed
+    pub fn words(&self, name: &str) -> Vec<String> {
+        self.get(name)
+            .map(|v| {
+                split_top_level(v, |s, i| s[i..].starts_with(char::is_whitespace))
+                    .map(|w| unquote(w.trim()).to_string())
+                    .filter(|w| !w.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns variable assignments from all `:var` arguments
+    ///
+    /// Later assignments override earlier ones with the same name.
+    pub fn vars(&self) -> Vec<Var> {
+        let mut vars: Vec<Var> = vec![];
+
+        for (_, value) in self.args.iter().filter(|(k, _)| k == "var") {
+            for assignment in split_assignments(value) {
+                let Some((name, value)) = assignment.split_once('=') else {
+                    continue;
+                };
+                let var = Var {
+                    name: name.trim().to_string(),
+                    value: VarValue::parse(value.trim()),
+                };
+                match vars.iter_mut().find(|v| v.name == var.name) {
+                    Some(v) => *v = var,
+                    None => vars.push(var),
+                }
+            }
+        }
+
+        vars
+    }
+
+    /// Sets argument, replacing existing arguments with the same name
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        self.args.retain(|(k, _)| *k != name);
+        self.args.push((name, value.into()));
+    }
+
+    /// Merges `other` into self, arguments in `other` take precedence
+    ///
+    /// Like `org-babel-merge-params`, `:var` assignments are accumulated, and
+    /// words of `:results` and `:exports` only replace words in the same
+    /// group, e.g. merging `:results replace` into `:results output` gives
+    /// `:results output replace`.
+    ///
+    /// ```rust
+    /// use orgize::babel::HeaderArgs;
+    ///
+    /// let mut args = HeaderArgs::parse(":results output silent :var a=1 :dir /tmp");
+    /// args.merge(&HeaderArgs::parse(":results replace :var b=2 :dir /home"));
+    /// assert_eq!(args.get("results"), Some("output replace"));
+    /// assert_eq!(args.get("dir"), Some("/home"));
+    /// assert_eq!(args.vars().len(), 2);
+    /// ```
+    pub fn merge(&mut self, other: &HeaderArgs) {
+        for (name, value) in &other.args {
+            match name.as_str() {
+                "var" => self.args.push((name.clone(), value.clone())),
+                "results" | "exports" => {
+                    let groups = if name == "results" {
+                        RESULTS_GROUPS
+                    } else {
+                        EXPORTS_GROUPS
+                    };
+                    let mut words = self.words(name);
+                    for word in value.split_whitespace() {
+                        let group = groups.iter().find(|g| g.contains(&word));
+                        words.retain(|w| match group {
+                            Some(group) => !group.contains(&w.as_str()),
+                            None => w != word,
+                        });
+                        words.push(word.to_string());
+                    }
+                    self.set(name.clone(), words.join(" "));
+                }
+                _ => self.set(name.clone(), value.clone()),
+            }
+        }
+    }
+}
+
+/// Mutually exclusive values of `:results`
+const RESULTS_GROUPS: &[&[&str]] = &[
+    &["file", "list", "vector", "table", "scalar", "verbatim"],
+    &[
+        "raw", "html", "latex", "org", "code", "pp", "drawer", "link", "graphics",
+    ],
+    &["replace", "silent", "none", "discard", "append", "prepend"],
+    &["output", "value"],
+];
+
+/// Mutually exclusive values of `:exports`
+const EXPORTS_GROUPS: &[&[&str]] = &[&["code", "results", "both", "none"]];
+
+impl VarValue {
+    fn parse(s: &str) -> VarValue {
+        if s.starts_with('"') {
+            return VarValue::String(unquote(s).replace("\\\"", "\""));
+        }
+        if let Ok(n) = s.parse::<f64>() {
+            return VarValue::Number(n);
+        }
+        if s.starts_with(['(', '\'', '`', '[']) {
+            return VarValue::Lisp(s.to_string());
+        }
+
+        let name_end = s.find(['[', '(']).unwrap_or(s.len());
+        let mut rest = &s[name_end..];
+        let index = rest
+            .strip_prefix('[')
+            .and_then(|r| r.split_once(']'))
+            .map(|(index, r)| {
+                rest = r;
+                index.to_string()
+            });
+        let args = rest
+            .strip_prefix('(')
+            .and_then(|r| r.rsplit_once(')'))
+            .map(|(args, _)| args.to_string());
+
+        VarValue::Reference {
+            name: s[..name_end].to_string(),
+            index,
+            args,
+        }
+    }
+}
+
+/// Splits `s` at positions where `at` returns true, ignoring positions
+/// inside double quotes, parentheses or brackets
+fn split_top_level<'a>(
+    s: &'a str,
+    at: impl Fn(&str, usize) -> bool + 'a,
+) -> impl Iterator<Item = &'a str> + 'a {
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut splits = vec![0];
+
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '(' | '[' if !quoted => depth += 1,
+            ')' | ']' if !quoted => depth = depth.saturating_sub(1),
+            _ if !quoted && depth == 0 && i > 0 && at(s, i) => splits.push(i),
+            _ => {}
+        }
+    }
+    splits.push(s.len());
+
+    (0..splits.len() - 1).map(move |i| &s[splits[i]..splits[i + 1]])
+}
+
+/// Splits `:var` value like `a=1, b="x y" c=tbl` into assignments
+fn split_assignments(s: &str) -> Vec<String> {
+    let mut assignments: Vec<String> = vec![];
+
+    for token in split_top_level(s, |s, i| s[i..].starts_with([' ', '\t', ','])) {
+        let token = token.trim_matches([' ', '\t', ',']);
+        if token.is_empty() {
+            continue;
+        }
+        match assignments.last_mut() {
+            // value with whitespace, e.g. `x=(+ 1 2)` is already balanced,
+            // so tokens without `=` belong to previous assignment
+            Some(last) if !token.contains('=') => {
+                last.push(' ');
+                last.push_str(token);
+            }
+            _ => assignments.push(token.to_string()),
+        }
+    }
+
+    assignments
+}
+
+pub(super) fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+/// Resolves header arguments of element at `node`
+///
+/// Following `org-babel-get-src-block-info`, arguments are merged in order:
+///
+/// 1. `header-args` property, from the nearest ancestor headline or `#+PROPERTY:`
+/// 2. `header-args:LANG` property, from the nearest ancestor headline or `#+PROPERTY:`
+/// 3. arguments of element itself
+/// 4. `#+HEADER:` affiliated keywords
+fn resolve(node: &SyntaxNode, language: Option<&str>, own: &[String]) -> HeaderArgs {
+    let mut args = HeaderArgs::default();
+
+    if let Some(value) = inherited_property(node, "header-args") {
+        args.merge(&HeaderArgs::parse(&value));
+    }
+    if let Some(language) = language {
+        if let Some(value) = inherited_property(node, &format!("header-args:{language}")) {
+            args.merge(&HeaderArgs::parse(&value));
+        }
+    }
+
+    for own in own {
+        args.merge(&HeaderArgs::parse(own));
+    }
+
+    for keyword in node.children().filter_map(AffiliatedKeyword::cast) {
+        let key = keyword.key();
+        if key.eq_ignore_ascii_case("HEADER") || key.eq_ignore_ascii_case("HEADERS") {
+            if let Some(value) = keyword.value() {
+                args.merge(&HeaderArgs::parse(&value));
+            }
+        }
+    }
+
+    args
+}
+
+/// Returns property value from the nearest ancestor headline, or from
+/// `#+PROPERTY:` keywords. `NAME+` properties append to the value.
+pub(crate) fn inherited_property(node: &SyntaxNode, name: &str) -> Option<String> {
+    fn append(value: &mut Option<String>, key: &str, v: &str, name: &str) {
+        if key.eq_ignore_ascii_case(name) {
+            *value = Some(v.trim().to_string());
+        } else if key
+            .strip_suffix('+')
+            .is_some_and(|k| k.eq_ignore_ascii_case(name))
+        {
+            let value = value.get_or_insert_with(String::new);
+            if !value.is_empty() {
+                value.push(' ');
+            }
+            value.push_str(v.trim());
+        }
+    }
+
+    for headline in node.ancestors().filter_map(Headline::cast) {
+        let mut value = None;
+        for property in headline
+            .properties()
+            .iter()
+            .flat_map(|p| p.node_properties())
+        {
+            let mut texts = property
+                .syntax()
+                .children_with_tokens()
+                .filter_map(filter_token(SyntaxKind::TEXT));
+            let (Some(key), v) = (texts.next(), texts.next().unwrap_or_default()) else {
+                continue;
+            };
+            if support::token(property.syntax(), SyntaxKind::PLUS).is_some() {
+                append(&mut value, &format!("{}+", key.as_ref()), &v, name);
+            } else {
+                append(&mut value, &key, &v, name);
+            }
+        }
+        if value.is_some() {
+            return value;
+        }
+    }
+
+    let root = node.ancestors().last()?;
+    let mut value = None;
+    for keyword in root.descendants().filter_map(Keyword::cast) {
+        if !keyword.key().eq_ignore_ascii_case("PROPERTY") {
+            continue;
+        }
+        let v = keyword.value();
+        let v = v.trim_start();
+        let (key, v) = v.split_once(char::is_whitespace).unwrap_or((v, ""));
+        append(&mut value, key, v, name);
+    }
+    value
+}
+
+impl SourceBlock {
+    /// Returns header arguments, merged with inherited `header-args` properties
+    /// and `#+HEADER:` keywords
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::SourceBlock};
+    ///
+    /// let org = Org::parse(r#"#+PROPERTY: header-args :results silent :dir /tmp
+    /// * Scripts
+    /// :PROPERTIES:
+    /// :header-args:sh: :dir /home
+    /// :END:
+    /// #+HEADER: :var x="b"
+    /// #+begin_src sh :results output :var x="a" y=2
+    /// echo $x
+    /// #+end_src"#);
+    ///
+    /// let args = org.first_node::<SourceBlock>().unwrap().header_args();
+    /// assert_eq!(args.get("results"), Some("silent output"));
+    /// assert_eq!(args.get("dir"), Some("/home"));
+    /// let vars = args.vars();
+    /// assert_eq!(vars.len(), 2);
+    /// assert_eq!(vars[0].name, "x");
+    /// assert_eq!(vars[0].value, orgize::babel::VarValue::String("b".into()));
+    /// ```
+    pub fn header_args(&self) -> HeaderArgs {
+        let language = self.language();
+        resolve(
+            &self.syntax,
+            language.as_deref(),
+            &self
+                .parameters()
+                .map(|p| vec![p.to_string()])
+                .unwrap_or_default(),
+        )
+    }
+}
+
+impl InlineSrc {
+    /// Returns header arguments, merged with inherited `header-args` properties
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::InlineSrc};
+    ///
+    /// let org = Org::parse("#+PROPERTY: header-args:python :results value\nsrc_python[:exports both]{1 + 1}");
+    /// let args = org.first_node::<InlineSrc>().unwrap().header_args();
+    /// assert_eq!(args.get("results"), Some("value"));
+    /// assert_eq!(args.get("exports"), Some("both"));
+    /// ```
+    pub fn header_args(&self) -> HeaderArgs {
+        resolve(
+            &self.syntax,
+            Some(&self.language()),
+            &self
+                .parameters()
+                .map(|p| vec![p.to_string()])
+                .unwrap_or_default(),
+        )
+    }
+}
+
+impl BabelCall {
+    /// Returns header arguments, merged with inherited `header-args` properties
+    /// and `#+HEADER:` keywords
+    ///
+    /// Inside header, end header and arguments (as `:var`) are applied in order.
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::BabelCall};
+    ///
+    /// let org = Org::parse("#+CALL: double[:results raw](n=4)[:results html]");
+    /// let args = org.first_node::<BabelCall>().unwrap().header_args();
+    /// assert_eq!(args.get("results"), Some("html"));
+    /// assert_eq!(args.vars()[0].name, "n");
+    /// ```
+    pub fn header_args(&self) -> HeaderArgs {
+        let mut own = vec![];
+        own.extend(self.inside_header());
+        own.extend(self.arguments().map(|a| format!(":var {a}")));
+        own.extend(self.end_header());
+        resolve(&self.syntax, None, &own)
+    }
+}
+
+impl InlineCall {
+    /// Returns header arguments, merged with inherited `header-args` properties
+    ///
+    /// Inside header, arguments (as `:var`) and end header are applied in order.
+    ///
+    /// ```rust
+    /// use orgize::{Org, ast::InlineCall};
+    ///
+    /// let org = Org::parse("call_double[:results raw](n=4)[:results html]");
+    /// let args = org.first_node::<InlineCall>().unwrap().header_args();
+    /// assert_eq!(args.get("results"), Some("html"));
+    /// assert_eq!(args.vars()[0].name, "n");
+    /// ```
+    pub fn header_args(&self) -> HeaderArgs {
+        let mut own = vec![];
+        own.extend(self.inside_header().map(|h| h.to_string()));
+        let arguments = self.arguments();
+        if !arguments.is_empty() {
+            own.push(format!(":var {}", arguments.as_ref()));
+        }
+        own.extend(self.end_header().map(|h| h.to_string()));
+        resolve(&self.syntax, None, &own)
+    }
+}
+
+#[test]
+fn parse() {
+    let args = HeaderArgs::parse(
+        ":var a=1, b=(+ 1 2)  c=\"x, y\" :var d=tbl[2,0] e=blk(x=1) :noweb yes :tangle \"a b.sh\"",
+    );
+    assert_eq!(
+        args.vars(),
+        vec![
+            Var {
+                name: "a".into(),
+                value: VarValue::Number(1.)
+            },
+            Var {
+                name: "b".into(),
+                value: VarValue::Lisp("(+ 1 2)".into())
+            },
+            Var {
+                name: "c".into(),
+                value: VarValue::String("x, y".into())
+            },
+            Var {
+                name: "d".into(),
+                value: VarValue::Reference {
+                    name: "tbl".into(),
+                    index: Some("2,0".into()),
+                    args: None
+                }
+            },
+            Var {
+                name: "e".into(),
+                value: VarValue::Reference {
+                    name: "blk".into(),
+                    index: None,
+                    args: Some("x=1".into())
+                }
+            },
+        ]
+    );
+    assert_eq!(args.get("noweb"), Some("yes"));
+    assert_eq!(args.words("tangle"), vec!["a b.sh"]);
+    assert!(args.get("missing").is_none());
+
+    assert_eq!(HeaderArgs::parse("").iter().count(), 0);
+    assert_eq!(
+        HeaderArgs::parse("  :results   :exports code")
+            .iter()
+            .collect::<Vec<_>>(),
+        vec![("results", ""), ("exports", "code")]
+    );
+}
+
+#[test]
+fn resolve_precedence() {
+    use crate::Org;
+
+    let org = Org::parse(
+        r#"#+PROPERTY: header-args :tangle no :dir /a
+#+PROPERTY: header-args+ :noweb yes
+* A
+:PROPERTIES:
+:header-args: :tangle a.sh
+:END:
+** B
+:PROPERTIES:
+:header-args:sh: :dir /b
+:header-args:sh+: :shebang #!/bin/sh
+:END:
+#+HEADER: :tangle c.sh
+#+begin_src sh :tangle b.sh :dir /c
+#+end_src
+* C
+#+begin_src python
+#+end_src
+"#,
+    );
+
+    let blocks: Vec<_> = org
+        .document()
+        .syntax()
+        .descendants()
+        .filter_map(SourceBlock::cast)
+        .map(|b| b.header_args())
+        .collect();
+
+    assert_eq!(blocks[0].get("tangle"), Some("c.sh"));
+    assert_eq!(blocks[0].get("dir"), Some("/c"));
+    assert_eq!(blocks[0].get("shebang"), Some("#!/bin/sh"));
+    // headline property shadows #+PROPERTY
+    assert_eq!(blocks[0].get("noweb"), None);
+
+    assert_eq!(blocks[1].get("tangle"), Some("no"));
+    assert_eq!(blocks[1].get("dir"), Some("/a"));
+    assert_eq!(blocks[1].get("noweb"), Some("yes"));
+}
@@ -0,0 +1,15 @@
+//! Work with source blocks, modeled on org-babel.
+
+mod data;
+mod detangle;
+mod execute;
+mod header_args;
+mod results;
+mod tangle;
+
+pub use detangle::DetangleError;
+pub use execute::{ExecuteConfig, ExecuteError, Executor, PythonExecutor, ShellExecutor, Value};
+pub use header_args::{HeaderArgs, Var, VarValue};
+pub(crate) use results::find_named;
+pub use results::{format_results, BabelResults};
+pub use tangle::{FsSink, TangleConfig, TangleSink, TangledFile};
@@ -0,0 +1,540 @@
+use rowan::{ast::AstNode, TextRange, TextSize};
+
+use super::{data::element_value, HeaderArgs, Value, Var, VarValue};
+use crate::{
+    ast::{filter_token, AffiliatedKeyword, BabelCall, Keyword, SourceBlock, Token},
+    builder::{escape_code, table_cell},
+    format::{align_table, Align},
+    Org, SyntaxKind, SyntaxNode,
+};
+
+/// Formats execution result as org source, according to `:results`
+///
+/// Returns `None` for `:results silent`, `none` or `discard`.
+///
+/// - `table` or `vector`: org table, default for lists
+/// - `list`: plain list
+/// - `verbatim` or `scalar`: fixed-width lines, or an example block if
+///   there're ten lines or more. Default for strings and numbers.
+/// - `raw`: inserted as-is
+/// - `drawer`: wrapped in a `:results:` drawer
+/// - `html` or `latex`: wrapped in an export block
+/// - `org` or `code`: wrapped in a source block
+///
+/// ```rust
+/// use orgize::babel::{format_results, HeaderArgs, Value};
+///
+/// let table = Value::List(vec![
+///     Value::List(vec![Value::String("a".into()), Value::Number(1.)]),
+///     Value::List(vec![Value::String("bc".into()), Value::Number(23.)]),
+/// ]);
+/// let args = HeaderArgs::default();
+/// assert_eq!(format_results(&table, &args, None).unwrap(), "| a  |  1 |\n| bc | 23 |\n");
+///
+/// let args = HeaderArgs::parse(":results list");
+/// assert_eq!(format_results(&table, &args, None).unwrap(), "- a\t1\n- bc\t23\n");
+///
+/// let text = Value::String("hello\n\nworld\n".into());
+/// let args = HeaderArgs::default();
+/// assert_eq!(format_results(&text, &args, None).unwrap(), ": hello\n:\n: world\n");
+///
+/// let args = HeaderArgs::parse(":results drawer");
+/// assert_eq!(format_results(&text, &args, None).unwrap(), ":results:\nhello\n\nworld\n:end:\n");
+///
+/// let args = HeaderArgs::parse(":results silent");
+/// assert!(format_results(&text, &args, None).is_none());
+/// ```
+pub fn format_results(value: &Value, args: &HeaderArgs, language: Option<&str>) -> Option<String> {
+    let words = args.words("results");
+    let has = |word: &str| words.iter().any(|w| w == word);
+
+    if has("silent") || has("none") || has("discard") {
+        return None;
+    }
+
+    let handler = ["raw", "drawer", "html", "latex", "org", "code"]
+        .into_iter()
+        .find(|h| has(h));
+
+    let untyped = !["table", "vector", "list", "verbatim", "scalar", "file"]
+        .into_iter()
+        .any(has);
+
+    let inner = if has("table") || has("vector") || (untyped && matches!(value, Value::List(_))) {
+        table(value)
+    } else if has("list") {
+        list(value)
+    } else if handler.is_some() {
+        let mut text = value.to_string();
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text
+    } else {
+        verbatim(&value.to_string())
+    };
+
+    Some(match handler {
+        Some("drawer") => format!(":results:\n{inner}:end:\n"),
+        Some(backend @ ("html" | "latex")) => {
+            format!("#+begin_export {backend}\n{inner}#+end_export\n")
+        }
+        Some("org") => format!("#+begin_src org\n{}#+end_src\n", escape_code(&inner)),
+        Some("code") => format!(
+            "#+begin_src {}\n{}#+end_src\n",
+            language.unwrap_or_default(),
+            escape_code(&inner)
+        ),
+        _ => inner,
+    })
+}
+
+/// Returns the edit inserting results of `node`, or replacing its existing results
+pub(super) fn insert(
+    node: &SyntaxNode,
+    value: &Value,
+    args: &HeaderArgs,
+    language: Option<&str>,
+) -> Option<(TextRange, String)> {
+    if matches!(
+        node.kind(),
+        SyntaxKind::INLINE_CALL | SyntaxKind::INLINE_SRC
+    ) {
+        return insert_inline(node, value, args);
+    }
+
+    let body = format_results(value, args, language)?;
+
+    let name = affiliated_keyword(node, "NAME");
+    let header = match &name {
+        Some(name) => format!("#+RESULTS: {name}\n"),
+        None => "#+RESULTS:\n".to_string(),
+    };
+
+    let existing = find_results(node);
+
+    match existing {
+        Some(existing) => Some((
+            TextRange::new(
+                existing.syntax.text_range().start(),
+                content_end(&existing.syntax),
+            ),
+            header + &body,
+        )),
+        None => {
+            // keeps one blank line between element and its results
+            let newline = if node.to_string()
+                [..usize::from(content_end(node) - node.text_range().start())]
+                .ends_with('\n')
+            {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            Some((
+                TextRange::empty(content_end(node)),
+                format!("{newline}{header}{body}"),
+            ))
+        }
+    }
+}
+
+/// Inserts `{{{results(...)}}}` macro after inline call or inline source block
+fn insert_inline(
+    node: &SyntaxNode,
+    value: &Value,
+    args: &HeaderArgs,
+) -> Option<(TextRange, String)> {
+    let words = args.words("results");
+    let has = |word: &str| words.iter().any(|w| w == word);
+
+    if has("silent") || has("none") || has("discard") {
+        return None;
+    }
+
+    let text = value
+        .to_string()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = if has("raw") {
+        text
+    } else {
+        format!("={text}=")
+    };
+
+    // replaces existing results macro, which must be separated by a single space
+    let end = node
+        .next_sibling_or_token()
+        .filter(|e| e.kind() == SyntaxKind::TEXT && e.to_string() == " ")
+        .and_then(|e| e.next_sibling_or_token())
+        .and_then(|e| e.into_node())
+        .filter(|n| {
+            n.kind() == SyntaxKind::MACROS
+                && n.children_with_tokens()
+                    .filter_map(|e| e.into_token())
+                    .any(|t| t.kind() == SyntaxKind::TEXT && t.text() == "results")
+        })
+        .map_or(node.text_range().end(), |n| n.text_range().end());
+
+    Some((
+        TextRange::new(node.text_range().end(), end),
+        format!(" {{{{{{results({text})}}}}}}"),
+    ))
+}
+
+/// Returns `#+RESULTS:` affiliated keyword of element, or `#+RESULTS:` keyword itself
+fn results_keyword_node(node: &SyntaxNode) -> Option<SyntaxNode> {
+    let is_results =
+        |key: &str| key.eq_ignore_ascii_case("RESULTS") || key.eq_ignore_ascii_case("RESULT");
+
+    if let Some(keyword) = Keyword::cast(node.clone()) {
+        return is_results(&keyword.key()).then(|| node.clone());
+    }
+    node.children()
+        .filter_map(AffiliatedKeyword::cast)
+        .find(|k| is_results(&k.key()))
+        .map(|k| k.syntax.clone())
+}
+
+/// Returns value of `#+RESULTS:` attached to element, or of `#+RESULTS:` keyword
+pub(super) fn results_keyword(node: &SyntaxNode) -> Option<String> {
+    let keyword = results_keyword_node(node)?;
+    Some(
+        keyword
+            .children_with_tokens()
+            .filter_map(filter_token(SyntaxKind::TEXT))
+            .last()
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default(),
+    )
+}
+
+/// Finds results of source block or babel call
+///
+/// Results of named element are searched in whole document, by `#+RESULTS: NAME`.
+/// Otherwise, results must be the element right after it.
+fn find_results(node: &SyntaxNode) -> Option<BabelResults> {
+    let syntax = match affiliated_keyword(node, "NAME") {
+        Some(name) => node
+            .ancestors()
+            .last()?
+            .descendants()
+            .find(|n| results_keyword(n).as_ref() == Some(&name)),
+        None => node.next_sibling().filter(|n| results_keyword(n).is_some()),
+    }?;
+    Some(BabelResults { syntax })
+}
+
+/// Finds element referenced by `name`, e.g. in `:var x=name`
+///
+/// Elements with `#+NAME: name` take precedence over `#+RESULTS: name`.
+pub(super) fn find_reference(node: &SyntaxNode, name: &str) -> Option<SyntaxNode> {
+    let root = node.ancestors().last()?;
+    find_named(&root, name).or_else(|| {
+        root.descendants()
+            .find(|n| results_keyword(n).is_some_and(|n| n == name))
+    })
+}
+
+/// Finds the first element under `root` with `#+NAME: name`
+pub(crate) fn find_named(root: &SyntaxNode, name: &str) -> Option<SyntaxNode> {
+    root.descendants()
+        .find(|n| affiliated_keyword(n, "NAME").is_some_and(|n| n == name))
+}
+
+/// Results of a source block or babel call
+///
+/// It's the element following `#+RESULTS:` keyword, e.g. a fixed-width area,
+/// table, example block, drawer or list. Empty results are a `#+RESULTS:`
+/// keyword alone.
+///
+/// ```rust
+/// use orgize::{ast::{BabelCall, SourceBlock}, babel::Value, Org};
+///
+/// let org = Org::parse(r#"#+begin_src sh
+/// echo 1
+/// #+end_src
+///
+/// #+RESULTS[a1b2]:
+/// : 1
+///
+/// #+NAME: call
+/// #+CALL: table()
+///
+/// #+RESULTS: call
+/// | a | 1 |"#);
+///
+/// let results = org.first_node::<SourceBlock>().unwrap().results_element().unwrap();
+/// assert_eq!(results.hash().unwrap(), "a1b2");
+/// assert_eq!(results.value(), Value::Number(1.));
+///
+/// let results = org.first_node::<BabelCall>().unwrap().results_element().unwrap();
+/// assert!(results.hash().is_none());
+/// assert_eq!(results.name().unwrap(), "call");
+/// assert_eq!(
+///     results.value(),
+///     Value::List(vec![Value::List(vec![Value::String("a".into()), Value::Number(1.)])])
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BabelResults {
+    syntax: SyntaxNode,
+}
+
+impl BabelResults {
+    /// Results element, including its `#+RESULTS:` keyword
+    pub fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+
+    /// Hash of cached results, e.g. `a1b2` in `#+RESULTS[a1b2]:`
+    pub fn hash(&self) -> Option<Token> {
+        results_keyword_node(&self.syntax)?
+            .children_with_tokens()
+            .skip_while(|e| e.kind() != SyntaxKind::L_BRACKET)
+            .nth(1)
+            .and_then(filter_token(SyntaxKind::TEXT))
+    }
+
+    /// Name of results, e.g. `name` in `#+RESULTS: name`
+    pub fn name(&self) -> Option<String> {
+        results_keyword(&self.syntax).filter(|name| !name.is_empty())
+    }
+
+    /// Converts results element into value
+    pub fn value(&self) -> Value {
+        element_value(&self.syntax, &HeaderArgs::default())
+    }
+}
+
+impl SourceBlock {
+    /// Returns results element of this block
+    ///
+    /// For a block with `#+NAME`, it's the element with the same `#+RESULTS:`
+    /// name in document. Otherwise, it's the element right after this block.
+    /// See [`BabelResults`] for an example.
+    pub fn results_element(&self) -> Option<BabelResults> {
+        find_results(&self.syntax)
+    }
+}
+
+impl BabelCall {
+    /// Returns results element of this call
+    ///
+    /// For a call with `#+NAME`, it's the element with the same `#+RESULTS:`
+    /// name in document. Otherwise, it's the element right after this call.
+    /// See [`BabelResults`] for an example.
+    pub fn results_element(&self) -> Option<BabelResults> {
+        find_results(&self.syntax)
+    }
+}
+
+impl Var {
+    /// Resolves value of this variable without executing any code
+    ///
+    /// References are resolved to named tables, lists and other elements, or
+    /// to the results of named source blocks and babel calls.
+    ///
+    /// ```rust
+    /// use orgize::{ast::SourceBlock, babel::Value, Org};
+    ///
+    /// let org = Org::parse(r#"#+NAME: items
+    /// - apple
+    /// - 2
+    ///
+    /// #+begin_src python :var x=items y=4 z=missing
+    /// #+end_src"#);
+    ///
+    /// let vars = org.first_node::<SourceBlock>().unwrap().header_args().vars();
+    /// assert_eq!(
+    ///     vars[0].resolve(&org).unwrap(),
+    ///     Value::List(vec![Value::String("apple".into()), Value::Number(2.)])
+    /// );
+    /// assert_eq!(vars[1].resolve(&org).unwrap(), Value::Number(4.));
+    /// assert!(vars[2].resolve(&org).is_none());
+    /// ```
+    pub fn resolve(&self, org: &Org) -> Option<Value> {
+        match &self.value {
+            VarValue::String(s) | VarValue::Lisp(s) => Some(Value::String(s.clone())),
+            VarValue::Number(n) => Some(Value::Number(*n)),
+            VarValue::Reference { name, .. } => {
+                let target = find_reference(&org.document().syntax, name)?;
+                match target.kind() {
+                    SyntaxKind::SOURCE_BLOCK | SyntaxKind::BABEL_CALL => {
+                        Some(find_results(&target)?.value())
+                    }
+                    _ => Some(element_value(&target, &HeaderArgs::default())),
+                }
+            }
+        }
+    }
+}
+
+fn affiliated_keyword(node: &SyntaxNode, key: &str) -> Option<String> {
+    node.children()
+        .filter_map(AffiliatedKeyword::cast)
+        .find(|k| k.key().eq_ignore_ascii_case(key))
+        .map(|k| k.value().map(|v| v.trim().to_string()).unwrap_or_default())
+}
+
+/// End of element, excluding trailing blank lines
+fn content_end(node: &SyntaxNode) -> TextSize {
+    node.children_with_tokens()
+        .filter(|e| e.kind() != SyntaxKind::BLANK_LINE)
+        .last()
+        .map_or(node.text_range().end(), |e| e.text_range().end())
+}
+
+fn table(value: &Value) -> String {
+    let rows: Vec<Vec<Value>> = match value {
+        Value::List(values) => match value.as_table() {
+            Some(rows) => rows.into_iter().map(|r| r.to_vec()).collect(),
+            None => vec![values.clone()],
+        },
+        value => value
+            .to_string()
+            .lines()
+            .map(|line| {
+                if line.contains('\t') {
+                    line.split('\t').map(Value::parse).collect()
+                } else {
+                    line.split_whitespace().map(Value::parse).collect()
+                }
+            })
+            .collect(),
+    };
+
+    let cells: Vec<Option<Vec<String>>> = rows
+        .iter()
+        .map(|row| Some(row.iter().map(|v| table_cell(&v.to_string())).collect()))
+        .collect();
+
+    // like org-table-align, numeric columns are aligned to the right
+    let align = |i: usize| {
+        let values: Vec<_> = rows.iter().filter_map(|row| row.get(i)).collect();
+        let numbers = values
+            .iter()
+            .filter(|v| matches!(v, Value::Number(_)))
+            .count();
+        if numbers * 2 > values.len() {
+            Align::Right
+        } else {
+            Align::Left
+        }
+    };
+
+    let mut output = String::new();
+    for line in align_table(&cells, align) {
+        output.push_str(&line);
+        output.push('\n');
+    }
+    output
+}
+
+fn list(value: &Value) -> String {
+    let items: Vec<String> = match value {
+        Value::List(values) => values.iter().map(|v| v.to_string()).collect(),
+        value => value.to_string().lines().map(str::to_string).collect(),
+    };
+
+    items.iter().map(|item| format!("- {item}\n")).collect()
+}
+
+fn verbatim(text: &str) -> String {
+    let text = text.trim_end_matches('\n');
+    if text.is_empty() {
+        return String::new();
+    }
+
+    // same as org-babel-min-lines-for-block-output
+    if text.lines().count() >= 10 {
+        return format!("#+begin_example\n{}\n#+end_example\n", escape_code(text));
+    }
+
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                ":\n".to_string()
+            } else {
+                format!(": {line}\n")
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn results() {
+    let org = Org::parse(
+        r#"#+begin_src sh
+#+end_src
+#+RESULTS:
+
+#+begin_src sh
+#+end_src
+
+text
+
+#+RESULTS:
+: not attached
+
+#+begin_src sh
+#+end_src
+
+#+RESULTS:
+:results:
+a
+b
+:end:
+
+#+NAME: later
+#+begin_src sh
+#+end_src
+
+#+RESULTS: other
+: 1
+
+#+RESULTS: later
+#+begin_example
+,* x
+#+end_example"#,
+    );
+
+    let results: Vec<_> = org
+        .document()
+        .syntax
+        .descendants()
+        .filter_map(SourceBlock::cast)
+        .map(|block| block.results_element().map(|r| r.value()))
+        .collect();
+
+    assert_eq!(
+        results,
+        vec![
+            Some(Value::String(String::new())),
+            None,
+            Some(Value::String("a\nb".into())),
+            Some(Value::String("* x".into())),
+        ]
+    );
+
+    // affiliated `#+RESULTS:` keyword of the block itself
+    let org = Org::parse("#+RESULTS: cached\n#+begin_src sh\n#+end_src\n");
+    let block = org.first_node::<SourceBlock>().unwrap();
+    assert_eq!(block.results().unwrap().value().as_deref(), Some(" cached"));
+    assert!(block.results_element().is_none());
+}
+
+#[test]
+fn format_table() {
+    let value = Value::List(vec![
+        Value::List(vec![Value::String("a|b".into()), Value::Number(1.)]),
+        Value::List(vec![Value::String("c".into()), Value::Number(10.)]),
+    ]);
+    let args = HeaderArgs::parse(":results table");
+
+    assert_eq!(
+        format_results(&value, &args, None).unwrap(),
+        "| a\\vert{}b |  1 |\n| c         | 10 |\n"
+    );
+}
@@ -0,0 +1,515 @@
+use std::{fs, io, path::PathBuf};
+
+use rowan::ast::AstNode;
+
+use super::{header_args::unquote, HeaderArgs};
+use crate::{
+    ast::{Headline, SourceBlock},
+    Org,
+};
+
+/// Tangle configuration
+///
+/// ```rust
+/// use orgize::{babel::TangleConfig, Org};
+///
+/// let org = Org::parse(r##"* Script
+/// #+begin_src sh :tangle hello.sh :shebang "#!/bin/sh" :noweb yes
+/// <<greeting>>
+/// #+end_src
+///
+/// #+NAME: greeting
+/// #+begin_src sh
+/// echo hello
+/// #+end_src"##);
+///
+/// let files = TangleConfig::default().tangle(&org);
+/// assert_eq!(files.len(), 1);
+/// assert_eq!(files[0].path, "hello.sh");
+/// assert_eq!(files[0].content, "#!/bin/sh\necho hello\n");
+/// assert!(files[0].executable);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TangleConfig {
+    /// Path of the org file, used for `:tangle yes` and `:comments link`
+    pub source: String,
+}
+
+impl Default for TangleConfig {
+    fn default() -> Self {
+        TangleConfig {
+            source: "README.org".into(),
+        }
+    }
+}
+
+/// A file generated by tangling
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TangledFile {
+    /// Path from `:tangle` header argument
+    pub path: String,
+    pub content: String,
+    /// Creates parent directories if missing, from `:mkdirp yes`
+    pub mkdirp: bool,
+    /// File starts with a `:shebang` line and should be executable
+    pub executable: bool,
+}
+
+/// Receives tangled files, see [`TangleConfig::tangle_to`]
+pub trait TangleSink {
+    fn write(&mut self, file: &TangledFile) -> io::Result<()>;
+}
+
+/// Writes tangled files to the filesystem, relative paths are resolved against `root`
+#[derive(Clone, Debug)]
+pub struct FsSink {
+    root: PathBuf,
+}
+
+impl FsSink {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FsSink { root: root.into() }
+    }
+}
+
+impl TangleSink for FsSink {
+    fn write(&mut self, file: &TangledFile) -> io::Result<()> {
+        let path = self.root.join(&file.path);
+        if file.mkdirp {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        fs::write(&path, &file.content)?;
+
+        #[cfg(unix)]
+        if file.executable {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TangleSink for Vec<TangledFile> {
+    fn write(&mut self, file: &TangledFile) -> io::Result<()> {
+        self.push(file.clone());
+        Ok(())
+    }
+}
+
+impl TangleConfig {
+    /// Collects source blocks with `:tangle` header argument into files
+    ///
+    /// Blocks are written in document order, files are ordered by their first
+    /// block. Supported header arguments are `:tangle`, `:noweb`, `:noweb-ref`,
+    /// `:noweb-sep`, `:mkdirp`, `:comments link`, `:padline` and `:shebang`.
+    pub fn tangle(&self, org: &Org) -> Vec<TangledFile> {
+        let blocks = linked_blocks(org);
+        let mut files: Vec<TangledFile> = vec![];
+
+        for LinkedBlock {
+            block,
+            args,
+            heading,
+            name,
+        } in &blocks
+        {
+            let Some(path) = self.tangle_path(block, args) else {
+                continue;
+            };
+
+            let mut body = if noweb_expands(args) {
+                expand_noweb(&block.value(), args, &blocks, &mut vec![])
+            } else {
+                block.value()
+            };
+            body = trim_body(&body);
+
+            let file = match files.iter_mut().find(|f| f.path == path) {
+                Some(file) => {
+                    if args.get("padline") != Some("no") {
+                        file.content.push('\n');
+                    }
+                    file
+                }
+                None => {
+                    let mut file = TangledFile {
+                        path,
+                        content: String::new(),
+                        mkdirp: false,
+                        executable: false,
+                    };
+                    if let Some(shebang) = args.get("shebang").map(unquote) {
+                        file.content.push_str(shebang);
+                        file.content.push('\n');
+                        file.executable = true;
+                    }
+                    files.push(file);
+                    files.last_mut().unwrap()
+                }
+            };
+
+            file.mkdirp |= args.contains("mkdirp", "yes");
+
+            let comments = args.words("comments");
+            let link = comments
+                .iter()
+                .any(|c| c == "link" || c == "yes" || c == "both");
+
+            let language = block.language();
+            let comment = comment_start(language.as_deref().unwrap_or_default());
+
+            if link {
+                let target = match heading {
+                    Some(h) => format!("file:{}::*{}", self.source, h.title_raw().trim()),
+                    None => format!("file:{}", self.source),
+                };
+                file.content
+                    .push_str(&format!("{comment} [[{target}][{name}]]\n"));
+            }
+            file.content.push_str(&body);
+            if link {
+                file.content
+                    .push_str(&format!("{comment} {name} ends here\n"));
+            }
+        }
+
+        files
+    }
+
+    /// Tangles and writes files to `sink`
+    pub fn tangle_to(&self, org: &Org, sink: &mut dyn TangleSink) -> io::Result<()> {
+        for file in self.tangle(org) {
+            sink.write(&file)?;
+        }
+        Ok(())
+    }
+
+    fn tangle_path(&self, block: &SourceBlock, args: &HeaderArgs) -> Option<String> {
+        let tangle = args.words("tangle").into_iter().next()?;
+        match tangle.as_str() {
+            "no" => None,
+            "yes" => {
+                let stem = self
+                    .source
+                    .rsplit_once('.')
+                    .map_or(self.source.as_str(), |(stem, _)| stem);
+                let language = block.language();
+                let extension = extension(language.as_deref().unwrap_or_default());
+                Some(format!("{stem}.{extension}"))
+            }
+            path => Some(path.to_string()),
+        }
+    }
+}
+
+/// A source block with its resolved header arguments and link name
+pub(super) struct LinkedBlock {
+    pub block: SourceBlock,
+    pub args: HeaderArgs,
+    /// Nearest headline
+    pub heading: Option<Headline>,
+    /// Name used in `:comments link`, either `#+NAME` of block or `Heading:N`,
+    /// where `N` counts source blocks under the same headline
+    pub name: String,
+}
+
+/// Collects all source blocks in document order
+pub(super) fn linked_blocks(org: &Org) -> Vec<LinkedBlock> {
+    let mut counter: Vec<(Option<Headline>, usize)> = vec![];
+
+    org.document()
+        .syntax
+        .descendants()
+        .filter_map(SourceBlock::cast)
+        .map(|block| {
+            let heading = block.syntax.ancestors().find_map(Headline::cast);
+            let index = match counter.iter_mut().find(|(h, _)| *h == heading) {
+                Some((_, n)) => {
+                    *n += 1;
+                    *n
+                }
+                None => {
+                    counter.push((heading.clone(), 1));
+                    1
+                }
+            };
+
+            let name = block
+                .name()
+                .and_then(|n| n.value())
+                .map(|n| n.trim().to_string())
+                .unwrap_or_else(|| {
+                    let title = heading
+                        .as_ref()
+                        .map(|h| h.title_raw().trim().to_string())
+                        .unwrap_or_else(|| "No heading".into());
+                    format!("{title}:{index}")
+                });
+
+            LinkedBlock {
+                args: block.header_args(),
+                block,
+                heading,
+                name,
+            }
+        })
+        .collect()
+}
+
+impl Org {
+    /// Tangles source blocks using default tangle config
+    pub fn tangle(&self) -> Vec<TangledFile> {
+        TangleConfig::default().tangle(self)
+    }
+}
+
+/// Returns `true` if noweb references should be handled when tangling
+fn noweb_expands(args: &HeaderArgs) -> bool {
+    matches!(
+        args.get("noweb"),
+        Some("yes" | "tangle" | "no-export" | "strip-export" | "strip-tangle")
+    )
+}
+
+/// Replaces `<<name>>` with bodies of blocks named `name`, or having
+/// `:noweb-ref name` header argument
+///
+/// Text before the reference is repeated on each expanded line, unless
+/// `:noweb-prefix no`. References to unknown blocks, or with arguments
+/// like `<<name()>>`, are kept as-is.
+fn expand_noweb(
+    body: &str,
+    args: &HeaderArgs,
+    blocks: &[LinkedBlock],
+    stack: &mut Vec<String>,
+) -> String {
+    let strip = args.get("noweb") == Some("strip-tangle");
+    let repeat_prefix = args.get("noweb-prefix") != Some("no");
+
+    let mut output = String::with_capacity(body.len());
+
+    for line in body.split_inclusive('\n') {
+        let mut offset = 0;
+
+        while let Some((start, end)) = line[offset..].find("<<").and_then(|start| {
+            let start = offset + start;
+            let end = line[start + 2..].find(">>")? + start + 2;
+            Some((start, end))
+        }) {
+            let name = &line[start + 2..end];
+            output.push_str(&line[offset..start]);
+            offset = end + 2;
+
+            if name.is_empty() || name.contains(['(', ' ', '\t']) {
+                output.push_str(&line[start..offset]);
+                continue;
+            }
+
+            if strip {
+                continue;
+            }
+
+            let expansion = if stack.iter().any(|s| s == name) {
+                None
+            } else {
+                resolve_noweb(name, blocks, stack)
+            };
+
+            match expansion {
+                Some(expansion) => {
+                    let prefix = if repeat_prefix { &line[..start] } else { "" };
+                    for (i, l) in expansion.lines().enumerate() {
+                        if i > 0 {
+                            output.push('\n');
+                            output.push_str(prefix);
+                        }
+                        output.push_str(l);
+                    }
+                }
+                None => output.push_str(&line[start..offset]),
+            }
+        }
+
+        output.push_str(&line[offset..]);
+    }
+
+    output
+}
+
+fn resolve_noweb(name: &str, blocks: &[LinkedBlock], stack: &mut Vec<String>) -> Option<String> {
+    let named = blocks
+        .iter()
+        .find(|b| b.block.name().is_some() && b.name == name);
+
+    let referenced: Vec<_> = match named {
+        Some(block) => vec![block],
+        None => blocks
+            .iter()
+            .filter(|b| b.args.get("noweb-ref") == Some(name))
+            .collect(),
+    };
+
+    if referenced.is_empty() {
+        return None;
+    }
+
+    stack.push(name.to_string());
+    let mut output = String::new();
+    for (i, LinkedBlock { block, args, .. }) in referenced.into_iter().enumerate() {
+        if i > 0 {
+            output.push_str(args.get("noweb-sep").map_or("\n", unquote));
+        }
+        let body = block.value();
+        let body = if noweb_expands(args) {
+            expand_noweb(&body, args, blocks, stack)
+        } else {
+            body
+        };
+        output.push_str(body.trim_end_matches('\n'));
+    }
+    stack.pop();
+
+    Some(output)
+}
+
+/// Removes leading and trailing blank lines, and ensures body ends with a newline
+pub(super) fn trim_body(body: &str) -> String {
+    let start = body
+        .split_inclusive('\n')
+        .take_while(|l| l.trim().is_empty())
+        .map(str::len)
+        .sum::<usize>();
+    let body = body[start..].trim_end();
+    if body.is_empty() {
+        String::new()
+    } else {
+        format!("{body}\n")
+    }
+}
+
+/// Returns line comment start of given language
+fn comment_start(language: &str) -> &'static str {
+    match language {
+        "c" | "C" | "cpp" | "C++" | "rust" | "js" | "javascript" | "typescript" | "ts" | "go"
+        | "java" | "kotlin" | "swift" | "scala" | "css" | "scss" => "//",
+        "emacs-lisp" | "elisp" | "lisp" | "scheme" | "clojure" => ";;",
+        "sql" | "lua" | "haskell" => "--",
+        "latex" | "tex" => "%",
+        _ => "#",
+    }
+}
+
+/// Returns file extension of given language
+fn extension(language: &str) -> &str {
+    match language {
+        "shell" | "bash" | "sh" | "zsh" => "sh",
+        "python" => "py",
+        "ruby" => "rb",
+        "perl" => "pl",
+        "rust" => "rs",
+        "javascript" | "js" => "js",
+        "typescript" => "ts",
+        "emacs-lisp" | "elisp" => "el",
+        "haskell" => "hs",
+        "C" => "c",
+        "C++" | "cpp" => "cpp",
+        "latex" => "tex",
+        language => language,
+    }
+}
+
+#[test]
+fn tangle() {
+    let org = Org::parse(
+        r#"#+PROPERTY: header-args:python :tangle yes
+* Setup
+#+begin_src sh :tangle scripts/run.sh :mkdirp yes :comments link
+echo one
+#+end_src
+
+#+begin_src python
+print(1)
+#+end_src
+
+#+begin_src sh :tangle scripts/run.sh :padline no
+
+echo two
+
+#+end_src
+* Ignored
+#+begin_src sh
+echo ignored
+#+end_src
+#+begin_src python :tangle no
+print(2)
+#+end_src"#,
+    );
+
+    let config = TangleConfig {
+        source: "notes.org".into(),
+    };
+    let mut files: Vec<TangledFile> = vec![];
+    config.tangle_to(&org, &mut files).unwrap();
+
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].path, "scripts/run.sh");
+    assert!(files[0].mkdirp);
+    assert!(!files[0].executable);
+    insta::assert_snapshot!(files[0].content, @r###"
+    # [[file:notes.org::*Setup][Setup:1]]
+    echo one
+    # Setup:1 ends here
+    echo two
+    "###);
+    assert_eq!(files[1].path, "notes.py");
+    assert_eq!(files[1].content, "print(1)\n");
+}
+
+#[test]
+fn noweb() {
+    let org = Org::parse(
+        r##"#+begin_src rust :tangle main.rs :noweb yes :shebang "#!/usr/bin/env run-cargo-script"
+fn main() {
+    <<body>> // done
+    <<unknown>> <<args(x=1)>>
+}
+#+end_src
+
+#+NAME: body
+#+begin_src rust :noweb yes
+let a = 1;
+<<print>>
+#+end_src
+
+#+begin_src rust :noweb-ref print
+println!("{a}");
+#+end_src
+
+#+begin_src rust :noweb-ref print :noweb yes
+println!("{}", a + 1);
+<<body>>
+#+end_src
+
+#+begin_src rust :tangle strip.rs :noweb strip-tangle
+a(<<body>>);
+#+end_src"##,
+    );
+
+    let files = org.tangle();
+    assert_eq!(files.len(), 2);
+    assert!(files[0].executable);
+    insta::assert_snapshot!(files[0].content, @r###"
+    #!/usr/bin/env run-cargo-script
+    fn main() {
+        let a = 1;
+        println!("{a}");
+        println!("{}", a + 1);
+        <<body>> // done
+        <<unknown>> <<args(x=1)>>
+    }
+    "###);
+    assert_eq!(files[1].content, "a();\n");
+}
@@ -0,0 +1,880 @@
+//! Build org-mode documents programmatically.
+//!
+//! Builders emit org-mode text which is then parsed by [`ParseConfig::parse`],
+//! so the resulting [`Org`] is exactly what the parser would produce for the
+//! generated text.
+//!
+//! ```rust
+//! use orgize::builder::{DocumentBuilder, HeadlineBuilder, SrcBlockBuilder};
+//!
+//! let org = DocumentBuilder::new()
+//!     .keyword("TITLE", "Notes")
+//!     .headline(
+//!         HeadlineBuilder::new("Project")
+//!             .todo("TODO")
+//!             .tags(["work"])
+//!             .property("CUSTOM_ID", "project")
+//!             .src_block(SrcBlockBuilder::new("org", "* not a headline")),
+//!     )
+//!     .build();
+//!
+//! assert_eq!(
+//!     org.to_org(),
+//!     r#"#+TITLE: Notes
+//! * TODO Project :work:
+//! :PROPERTIES:
+//! :CUSTOM_ID: project
+//! :END:
+//! #+begin_src org
+//! ,* not a headline
+//! #+end_src
+//! "#
+//! );
+//! ```
+
+use std::fmt::Write as _;
+
+use crate::{
+    format::{align_table, Align},
+    Org, ParseConfig,
+};
+
+macro_rules! section_methods {
+    () => {
+        /// Appends a paragraph
+        ///
+        /// Lines that would start another element, e.g. `* headline`, `#+KEY:` or
+        /// `| table |`, are prefixed with invisible entity `\zwnj{}`. Trailing line
+        /// breaks are normalized.
+        pub fn paragraph(mut self, text: impl AsRef<str>) -> Self {
+            let text = text.as_ref().trim_end_matches(['\n', '\r']);
+            if !text.is_empty() {
+                self.elements.push(format!("{}\n", escape_text(text)));
+            }
+            self
+        }
+
+        /// Appends a keyword, e.g. `#+KEY: VALUE`
+        pub fn keyword(mut self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+            self.elements.push(format!(
+                "#+{}: {}\n",
+                sanitize_key(key.as_ref()),
+                single_line(value.as_ref())
+            ));
+            self
+        }
+
+        /// Appends a table
+        pub fn table(mut self, table: TableBuilder) -> Self {
+            let table = table.to_org();
+            if !table.is_empty() {
+                self.elements.push(table);
+            }
+            self
+        }
+
+        /// Appends a plain list
+        pub fn list(mut self, list: ListBuilder) -> Self {
+            let list = list.to_org();
+            if !list.is_empty() {
+                self.elements.push(list);
+            }
+            self
+        }
+
+        /// Appends a source block
+        pub fn src_block(mut self, block: SrcBlockBuilder) -> Self {
+            self.elements.push(block.to_org());
+            self
+        }
+
+        /// Appends a child headline
+        pub fn headline(mut self, headline: HeadlineBuilder) -> Self {
+            self.headlines.push(headline);
+            self
+        }
+    };
+}
+
+/// Builder for an org-mode document
+#[derive(Default, Debug, Clone)]
+pub struct DocumentBuilder {
+    elements: Vec<String>,
+    headlines: Vec<HeadlineBuilder>,
+}
+
+impl DocumentBuilder {
+    pub fn new() -> Self {
+        DocumentBuilder::default()
+    }
+
+    section_methods!();
+
+    /// Returns generated org-mode string
+    pub fn to_org(&self) -> String {
+        let mut output = String::new();
+        write_section(&mut output, &self.elements);
+        for headline in &self.headlines {
+            headline.write(&mut output, 1);
+        }
+        output
+    }
+
+    /// Parses generated org-mode string using default parse config
+    pub fn build(self) -> Org {
+        self.build_with(ParseConfig::default())
+    }
+
+    /// Parses generated org-mode string using given parse config
+    pub fn build_with(self, config: ParseConfig) -> Org {
+        config.parse(self.to_org())
+    }
+}
+
+/// Builder for a headline and its contents
+///
+/// ```rust
+/// use orgize::{ast::Headline, builder::HeadlineBuilder};
+///
+/// let headline = HeadlineBuilder::new("Meeting\nnotes")
+///     .priority('A')
+///     .tags(["a:b", "c d"])
+///     .scheduled("<2023-05-01 Mon 10:00>")
+///     .property("LOCATION", "Room 1");
+/// assert_eq!(
+///     headline.to_org(),
+///     "* [#A] Meeting notes :a_b:c_d:\nSCHEDULED: <2023-05-01 Mon 10:00>\n:PROPERTIES:\n:LOCATION: Room 1\n:END:\n"
+/// );
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct HeadlineBuilder {
+    title: String,
+    todo: Option<String>,
+    priority: Option<char>,
+    tags: Vec<String>,
+    deadline: Option<String>,
+    scheduled: Option<String>,
+    closed: Option<String>,
+    properties: Vec<(String, String)>,
+    elements: Vec<String>,
+    headlines: Vec<HeadlineBuilder>,
+}
+
+impl HeadlineBuilder {
+    /// Creates a headline builder
+    ///
+    /// Title is joined into one line. A leading priority cookie or `COMMENT`,
+    /// and trailing tags, e.g. `title :a:`, are escaped with `\zwnj{}` so they
+    /// stay in the title.
+    pub fn new(title: impl AsRef<str>) -> Self {
+        HeadlineBuilder {
+            title: escape_title(single_line(title.as_ref())),
+            ..Default::default()
+        }
+    }
+
+    /// Sets todo keyword
+    ///
+    /// The keyword must be listed in [`ParseConfig::todo_keywords`], otherwise it
+    /// will be parsed as part of the title.
+    pub fn todo(mut self, keyword: impl AsRef<str>) -> Self {
+        self.todo = Some(sanitize_key(keyword.as_ref()));
+        self
+    }
+
+    /// Sets priority cookie, e.g. `[#A]`
+    pub fn priority(mut self, priority: char) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Appends one tag
+    ///
+    /// Characters not allowed in tags are replaced with underscores.
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        let tag = sanitize_tag(tag.as_ref());
+        if !tag.is_empty() {
+            self.tags.push(tag);
+        }
+        self
+    }
+
+    /// Appends multiple tags
+    pub fn tags<I: IntoIterator<Item = S>, S: AsRef<str>>(self, tags: I) -> Self {
+        tags.into_iter().fold(self, |b, tag| b.tag(tag))
+    }
+
+    /// Sets `DEADLINE` timestamp in planning line, e.g. `<2023-05-01 Mon>`
+    pub fn deadline(mut self, timestamp: impl AsRef<str>) -> Self {
+        self.deadline = Some(single_line(timestamp.as_ref()));
+        self
+    }
+
+    /// Sets `SCHEDULED` timestamp in planning line, e.g. `<2023-05-01 Mon>`
+    pub fn scheduled(mut self, timestamp: impl AsRef<str>) -> Self {
+        self.scheduled = Some(single_line(timestamp.as_ref()));
+        self
+    }
+
+    /// Sets `CLOSED` timestamp in planning line, e.g. `[2023-05-01 Mon]`
+    pub fn closed(mut self, timestamp: impl AsRef<str>) -> Self {
+        self.closed = Some(single_line(timestamp.as_ref()));
+        self
+    }
+
+    /// Appends a node property to property drawer
+    pub fn property(mut self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.properties.push((
+            sanitize_property_key(key.as_ref()),
+            single_line(value.as_ref()),
+        ));
+        self
+    }
+
+    section_methods!();
+
+    /// Returns generated org-mode string, as a level 1 headline
+    pub fn to_org(&self) -> String {
+        let mut output = String::new();
+        self.write(&mut output, 1);
+        output
+    }
+
+    fn write(&self, output: &mut String, level: usize) {
+        output.push_str(&"*".repeat(level));
+
+        let mut line = String::new();
+        for part in [
+            self.todo.clone(),
+            self.priority.map(|p| format!("[#{p}]")),
+            Some(self.title.clone()),
+            (!self.tags.is_empty()).then(|| format!(":{}:", self.tags.join(":"))),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        {
+            line.push(' ');
+            line.push_str(&part);
+        }
+        if line.is_empty() {
+            line.push(' ');
+        }
+        output.push_str(&line);
+        output.push('\n');
+
+        let planning = [
+            ("DEADLINE:", &self.deadline),
+            ("SCHEDULED:", &self.scheduled),
+            ("CLOSED:", &self.closed),
+        ]
+        .into_iter()
+        .filter_map(|(key, timestamp)| Some(format!("{key} {}", timestamp.as_ref()?)))
+        .collect::<Vec<_>>();
+        if !planning.is_empty() {
+            output.push_str(&planning.join(" "));
+            output.push('\n');
+        }
+
+        if !self.properties.is_empty() {
+            output.push_str(":PROPERTIES:\n");
+            for (key, value) in &self.properties {
+                let _ = writeln!(output, ":{key}: {value}");
+            }
+            output.push_str(":END:\n");
+        }
+
+        write_section(output, &self.elements);
+
+        for headline in &self.headlines {
+            headline.write(output, level + 1);
+        }
+    }
+}
+
+/// Builder for an org table
+///
+/// Columns are aligned, and vertical bars inside cells are escaped as `\vert{}`.
+///
+/// ```rust
+/// use orgize::builder::TableBuilder;
+///
+/// let table = TableBuilder::new()
+///     .header(["name", "qty"])
+///     .row(["apple | pear", "1"])
+///     .row(["kiwi"]);
+/// assert_eq!(
+///     table.to_org(),
+///     r#"| name               | qty |
+/// |--------------------+-----|
+/// | apple \vert{} pear | 1   |
+/// | kiwi               |     |
+/// "#
+/// );
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct TableBuilder {
+    rows: Vec<Option<Vec<String>>>,
+}
+
+impl TableBuilder {
+    pub fn new() -> Self {
+        TableBuilder::default()
+    }
+
+    /// Appends a row followed by a rule row
+    pub fn header<I: IntoIterator<Item = S>, S: AsRef<str>>(self, cells: I) -> Self {
+        self.row(cells).rule()
+    }
+
+    /// Appends a standard row
+    pub fn row<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, cells: I) -> Self {
+        self.rows.push(Some(
+            cells
+                .into_iter()
+                .map(|cell| table_cell(cell.as_ref()))
+                .collect(),
+        ));
+        self
+    }
+
+    /// Appends a rule row, e.g. `|---+---|`
+    pub fn rule(mut self) -> Self {
+        self.rows.push(None);
+        self
+    }
+
+    /// Returns generated org-mode string, or empty string if table contains no rows
+    pub fn to_org(&self) -> String {
+        let mut output = String::new();
+        for line in align_table(&self.rows, |_| Align::Left) {
+            output.push_str(&line);
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ListItem {
+    text: String,
+    checkbox: Option<bool>,
+    tag: Option<String>,
+    children: Option<ListBuilder>,
+}
+
+/// Builder for a plain list
+///
+/// ```rust
+/// use orgize::builder::ListBuilder;
+///
+/// let list = ListBuilder::ordered()
+///     .item("first\nline")
+///     .sublist(ListBuilder::unordered().checkbox("done", true).tagged("term", "definition"))
+///     .item("second");
+/// assert_eq!(
+///     list.to_org(),
+///     r#"1. first
+///    line
+///    - [X] done
+///    - term :: definition
+/// 2. second
+/// "#
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct ListBuilder {
+    ordered: bool,
+    items: Vec<ListItem>,
+}
+
+impl ListBuilder {
+    /// Creates a list using `-` as bullet
+    pub fn unordered() -> Self {
+        ListBuilder {
+            ordered: false,
+            items: vec![],
+        }
+    }
+
+    /// Creates a list using `1.`, `2.`, ... as bullet
+    pub fn ordered() -> Self {
+        ListBuilder {
+            ordered: true,
+            items: vec![],
+        }
+    }
+
+    /// Appends an item
+    pub fn item(mut self, text: impl AsRef<str>) -> Self {
+        self.items.push(ListItem {
+            text: text.as_ref().to_string(),
+            checkbox: None,
+            tag: None,
+            children: None,
+        });
+        self
+    }
+
+    /// Appends an item with checkbox
+    pub fn checkbox(mut self, text: impl AsRef<str>, checked: bool) -> Self {
+        self = self.item(text);
+        if let Some(item) = self.items.last_mut() {
+            item.checkbox = Some(checked);
+        }
+        self
+    }
+
+    /// Appends an item with tag, e.g. `- tag :: text`
+    ///
+    /// Tags are ignored by the parser in ordered list.
+    pub fn tagged(mut self, tag: impl AsRef<str>, text: impl AsRef<str>) -> Self {
+        self = self.item(text);
+        if let Some(item) = self.items.last_mut() {
+            item.tag = Some(single_line(tag.as_ref()));
+        }
+        self
+    }
+
+    /// Attaches a nested list to the last item
+    ///
+    /// Does nothing if this list has no items yet.
+    pub fn sublist(mut self, list: ListBuilder) -> Self {
+        if let Some(item) = self.items.last_mut() {
+            item.children = Some(list);
+        }
+        self
+    }
+
+    /// Returns generated org-mode string, or empty string if list contains no items
+    pub fn to_org(&self) -> String {
+        let mut output = String::new();
+        self.write(&mut output, 0);
+        output
+    }
+
+    fn write(&self, output: &mut String, indent: usize) {
+        for (idx, item) in self.items.iter().enumerate() {
+            let bullet = if self.ordered {
+                format!("{}.", idx + 1)
+            } else {
+                "-".to_string()
+            };
+            let content_indent = indent + bullet.len() + 1;
+
+            let _ = write!(output, "{}{bullet}", " ".repeat(indent));
+            match item.checkbox {
+                Some(true) => output.push_str(" [X]"),
+                Some(false) => output.push_str(" [ ]"),
+                None => {}
+            }
+            if let Some(tag) = &item.tag {
+                let _ = write!(output, " {tag} ::");
+            }
+
+            let text = escape_text(&item.text);
+            let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+            if let Some(line) = lines.next() {
+                let _ = write!(output, " {}", line.trim());
+            }
+            output.push('\n');
+            for line in lines {
+                let _ = writeln!(output, "{}{}", " ".repeat(content_indent), line.trim());
+            }
+
+            if let Some(children) = &item.children {
+                children.write(output, content_indent);
+            }
+        }
+    }
+}
+
+/// Builder for a source block
+///
+/// Lines starting with `*` or `#+` are comma-escaped.
+///
+/// ```rust
+/// use orgize::{ast::SourceBlock, builder::SrcBlockBuilder, Org};
+///
+/// let code = "#+TITLE: example\n  #+end_src\n,* quoted\n";
+/// let block = SrcBlockBuilder::new("org", code)
+///     .name("example")
+///     .switches("-n")
+///     .header("exports", "code");
+/// let text = block.to_org();
+/// assert_eq!(
+///     text,
+///     "#+NAME: example\n#+begin_src org -n :exports code\n,#+TITLE: example\n  ,#+end_src\n,,* quoted\n#+end_src\n"
+/// );
+///
+/// let org = Org::parse(&text);
+/// assert_eq!(org.first_node::<SourceBlock>().unwrap().value(), code);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SrcBlockBuilder {
+    name: Option<String>,
+    language: String,
+    switches: Option<String>,
+    parameters: Vec<(String, String)>,
+    code: String,
+}
+
+impl SrcBlockBuilder {
+    pub fn new(language: impl AsRef<str>, code: impl AsRef<str>) -> Self {
+        SrcBlockBuilder {
+            name: None,
+            language: language
+                .as_ref()
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect(),
+            switches: None,
+            parameters: vec![],
+            code: code.as_ref().to_string(),
+        }
+    }
+
+    /// Sets `#+NAME` affiliated keyword
+    pub fn name(mut self, name: impl AsRef<str>) -> Self {
+        self.name = Some(single_line(name.as_ref()));
+        self
+    }
+
+    /// Sets block switches, e.g. `-n -r`
+    pub fn switches(mut self, switches: impl AsRef<str>) -> Self {
+        self.switches = Some(single_line(switches.as_ref()));
+        self
+    }
+
+    /// Appends a header argument, e.g. `:results output`
+    ///
+    /// Leading colon in `key` is optional.
+    pub fn header(mut self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.parameters.push((
+            sanitize_key(key.as_ref().trim_start_matches(':')),
+            single_line(value.as_ref()),
+        ));
+        self
+    }
+
+    /// Returns generated org-mode string
+    pub fn to_org(&self) -> String {
+        let mut output = String::new();
+
+        if let Some(name) = &self.name {
+            let _ = writeln!(output, "#+NAME: {name}");
+        }
+
+        output.push_str("#+begin_src");
+        if !self.language.is_empty() {
+            let _ = write!(output, " {}", self.language);
+        }
+        if let Some(switches) = self.switches.as_ref().filter(|s| !s.is_empty()) {
+            let _ = write!(output, " {switches}");
+        }
+        for (key, value) in &self.parameters {
+            let _ = write!(output, " :{key}");
+            if !value.is_empty() {
+                let _ = write!(output, " {value}");
+            }
+        }
+        output.push('\n');
+
+        output.push_str(&escape_code(&self.code));
+        if !output.ends_with('\n') {
+            output.push('\n');
+        }
+
+        output.push_str("#+end_src\n");
+        output
+    }
+}
+
+/// Writes section elements, separated by blank lines
+fn write_section(output: &mut String, elements: &[String]) {
+    for (idx, element) in elements.iter().enumerate() {
+        if idx > 0 {
+            output.push('\n');
+        }
+        output.push_str(element);
+    }
+}
+
+/// Inserts a comma before lines starting with `*` or `#+`, optionally preceded
+/// by whitespace and commas, equivalent to `org-escape-code-in-string`
+pub(crate) fn escape_code(code: &str) -> String {
+    let mut output = String::with_capacity(code.len());
+    for line in code.split_inclusive('\n') {
+        let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+        let rest = line[indent..].trim_start_matches(',');
+        if rest.starts_with('*') || rest.starts_with("#+") {
+            output.push_str(&line[0..indent]);
+            output.push(',');
+            output.push_str(&line[indent..]);
+        } else {
+            output.push_str(line);
+        }
+    }
+    output
+}
+
+/// Prefixes lines that would start another element with `\zwnj{}`, so they're
+/// parsed as paragraph text
+fn escape_text(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let rest = line.trim_start();
+        let indent = &line[..line.len() - rest.len()];
+        output.push_str(indent);
+        if starts_element(rest) {
+            output.push_str("\\zwnj{}");
+        }
+        output.push_str(rest);
+    }
+    output
+}
+
+/// Returns true if a paragraph line starting with `line` would be parsed as
+/// a headline, list item, table, keyword, block, drawer or other element
+fn starts_element(line: &str) -> bool {
+    let separated = |rest: &str| rest.is_empty() || rest.starts_with([' ', '\t', '\n', '\r']);
+
+    let Some(first) = line.chars().next() else {
+        return false;
+    };
+    match first {
+        '*' => separated(line.trim_start_matches('*')),
+        '-' => separated(&line[1..]) || line.starts_with("-----"),
+        '+' => separated(&line[1..]),
+        '#' => line.starts_with("#+") || separated(&line[1..]),
+        '|' | ':' => true,
+        '[' => line.starts_with("[fn:"),
+        '\\' => line.starts_with("\\begin{"),
+        '%' => line.starts_with("%%("),
+        c if c.is_ascii_alphanumeric() => {
+            // ordered bullets, e.g. `1.` or `a)`
+            let counter = if c.is_ascii_digit() {
+                line.trim_start_matches(|c: char| c.is_ascii_digit())
+            } else {
+                &line[1..]
+            };
+            (counter.starts_with(['.', ')']) && separated(&counter[1..]))
+                || ["CLOCK:", "DEADLINE:", "SCHEDULED:", "CLOSED:"]
+                    .iter()
+                    .any(|planning| line.starts_with(planning))
+        }
+        _ => false,
+    }
+}
+
+/// Escapes title parts that would be parsed as priority, comment or tags
+fn escape_title(mut title: String) -> String {
+    if title.starts_with("[#") || title.split_whitespace().next() == Some("COMMENT") {
+        title.insert_str(0, "\\zwnj{}");
+    }
+    let last = title.rsplit([' ', '\t']).next().unwrap_or_default();
+    if last.len() > 1
+        && last.starts_with(':')
+        && last.ends_with(':')
+        && last
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '@' | '#' | '%' | ':'))
+    {
+        title.push_str("\\zwnj{}");
+    }
+    title
+}
+
+/// Joins lines of a table cell and escapes vertical bars as `\vert{}`
+pub(crate) fn table_cell(s: &str) -> String {
+    single_line(s).replace('|', "\\vert{}")
+}
+
+fn single_line(s: &str) -> String {
+    s.split(['\n', '\r'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn sanitize_key(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_whitespace() && *c != ':')
+        .collect()
+}
+
+fn sanitize_property_key(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_whitespace() && *c != ':' && *c != '+')
+        .collect()
+}
+
+fn sanitize_tag(s: &str) -> String {
+    s.trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '@' || c == '#' || c == '%' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn build() {
+    use crate::ast::{Headline, List, OrgTable, SourceBlock};
+    use rowan::ast::AstNode;
+
+    let org = DocumentBuilder::new()
+        .paragraph("intro\n\n")
+        .headline(
+            HeadlineBuilder::new("parent")
+                .todo("DONE")
+                .tags(["a", "b"])
+                .deadline("<2023-05-01 Mon>")
+                .closed("[2023-04-30 Sun]")
+                .property("ID", "parent-id")
+                .paragraph("text")
+                .list(ListBuilder::unordered().item("one").item("two"))
+                .table(TableBuilder::new().row(["1", "2"]))
+                .headline(HeadlineBuilder::new("child")),
+        )
+        .headline(HeadlineBuilder::new(""))
+        .build();
+
+    insta::assert_snapshot!(
+        org.to_org(),
+        @r###"
+    intro
+    * DONE parent :a:b:
+    DEADLINE: <2023-05-01 Mon> CLOSED: [2023-04-30 Sun]
+    :PROPERTIES:
+    :ID: parent-id
+    :END:
+    text
+
+    - one
+    - two
+
+    | 1 | 2 |
+    ** child
+    *
+    "###
+    );
+
+    let headlines = org.document().headlines().collect::<Vec<_>>();
+    assert_eq!(headlines.len(), 2);
+
+    let parent = &headlines[0];
+    assert_eq!(parent.todo_keyword().unwrap(), "DONE");
+    assert_eq!(parent.tags().collect::<Vec<_>>(), ["a", "b"]);
+    assert!(parent.deadline().is_some());
+    assert!(parent.closed().is_some());
+    assert_eq!(parent.properties().unwrap().get("ID").unwrap(), "parent-id");
+    assert_eq!(parent.headlines().count(), 1);
+
+    let section = parent.section().unwrap();
+    assert_eq!(
+        section.syntax().children().filter_map(List::cast).count(),
+        1
+    );
+    assert_eq!(
+        section
+            .syntax()
+            .children()
+            .filter_map(OrgTable::cast)
+            .count(),
+        1
+    );
+    assert!(org.first_node::<SourceBlock>().is_none());
+    assert_eq!(org.first_node::<Headline>().unwrap(), *parent);
+}
+
+#[test]
+fn escape() {
+    for code in [
+        "",
+        "*",
+        "* a\n** b",
+        "#+begin_src\n#+end_src",
+        "  #+END_SRC\n\t,#+end_src\n",
+        ",,,* a\n,",
+        "a\r\n#+b\r\n",
+    ] {
+        let org = DocumentBuilder::new()
+            .src_block(SrcBlockBuilder::new("", code))
+            .build();
+        let block = org.first_node::<crate::ast::SourceBlock>().unwrap();
+        let expected = if code.is_empty() || code.ends_with('\n') {
+            code.to_string()
+        } else {
+            format!("{code}\n")
+        };
+        assert_eq!(block.value(), expected);
+    }
+}
+
+#[test]
+fn escape_text_and_title() {
+    use crate::ast::Headline;
+    use crate::SyntaxKind;
+    use rowan::ast::AstNode;
+
+    for text in [
+        "* not a headline",
+        "#+TITLE: not a keyword",
+        "# not a comment",
+        "| not | a table |",
+        ": not fixed width",
+        ":DRAWER:\ntext\n:END:",
+        "- not\n+ a list\n1. either\na) really",
+        "-----",
+        "[fn:1] not a footnote",
+        "#+begin_quote\nnot a block\n#+end_quote",
+        "\\begin{equation}\nx\n\\end{equation}",
+        "text\n* still text\n  | indented",
+        "CLOCK: [2023-05-01 Mon 10:00]",
+        "plain *bold* text",
+    ] {
+        let org = DocumentBuilder::new()
+            .headline(HeadlineBuilder::new("h").paragraph(text))
+            .build();
+        let headline = org.first_node::<Headline>().unwrap();
+        assert_eq!(headline.headlines().count(), 0, "{text:?}");
+        assert!(headline.planning().is_none(), "{text:?}");
+        let section = headline.section().unwrap();
+        let children = section.syntax().children().collect::<Vec<_>>();
+        assert_eq!(children.len(), 1, "{text:?}");
+        assert_eq!(children[0].kind(), SyntaxKind::PARAGRAPH, "{text:?}");
+    }
+
+    // escaped only when needed
+    assert_eq!(
+        DocumentBuilder::new()
+            .paragraph("a *b*\n* c\n  #+d")
+            .to_org(),
+        "a *b*\n\\zwnj{}* c\n  \\zwnj{}#+d\n"
+    );
+
+    for title in ["ratio :x:", ":a:b:", "[#A] priority", "COMMENT out"] {
+        let org = DocumentBuilder::new()
+            .headline(HeadlineBuilder::new(title).tag("t"))
+            .build();
+        let headline = org.first_node::<Headline>().unwrap();
+        assert_eq!(headline.tags().collect::<Vec<_>>(), ["t"], "{title:?}");
+        assert!(headline.priority().is_none(), "{title:?}");
+        assert!(!headline.is_commented(), "{title:?}");
+    }
+    assert_eq!(HeadlineBuilder::new("a: b").to_org(), "* a: b\n");
+
+    // sublist without an item is ignored
+    assert_eq!(
+        ListBuilder::unordered()
+            .sublist(ListBuilder::unordered().item("a"))
+            .to_org(),
+        ""
+    );
+}
@@ -1,4 +1,8 @@
-use crate::syntax::document::document_node;
+use crate::include::{expand_includes, setup_files, FileLoader, IncludeError};
+#[cfg(feature = "rayon")]
+use crate::syntax::document::parallel_document_node;
+#[cfg(not(feature = "rayon"))]
+use crate::syntax::{diagnostic::DiagnosticSink, document::document_node, input::Input};
 use crate::Org;
 
 /// Parse configuration
@@ -19,13 +23,84 @@ pub struct ParseConfig {
 
 impl ParseConfig {
     /// Parses input with current config
+    ///
+    /// Todo keywords declared by in-buffer `#+TODO:`, `#+SEQ_TODO:` or `#+TYP_TODO:`
+    /// settings take precedence over [`ParseConfig::todo_keywords`], see
+    /// [`Org::todo_sequences`]. In that case, [`Org::config`] holds the
+    /// in-buffer keywords instead of the configured ones, so reparsing with it
+    /// yields the same headlines.
+    ///
+    /// Malformed elements are recorded as diagnostics, see [`Org::diagnostics`].
+    ///
+    /// With the `rayon` feature enabled, large inputs are split at level-1
+    /// headlines and parsed in parallel. The resulting tree is identical to
+    /// the one parsed sequentially.
     pub fn parse(self, input: impl AsRef<str>) -> Org {
-        let input = (input.as_ref(), &self).into();
-        let node = document_node(input).unwrap().1;
+        self.parse_with_todo_sequences(input.as_ref(), vec![])
+    }
+
+    /// Parses input after resolving `#+SETUPFILE:` and `#+INCLUDE:` keywords
+    ///
+    /// Includes are spliced into input, and todo keywords declared in setup
+    /// files are merged into this config. Other settings in setup files are
+    /// ignored. See [`include`](crate::include) for details.
+    pub fn parse_with_loader(
+        self,
+        input: impl AsRef<str>,
+        loader: &dyn FileLoader,
+    ) -> Result<Org, IncludeError> {
+        let input = expand_includes(input.as_ref(), loader)?;
+        let setup = setup_files(&input, loader)?;
+        Ok(self.parse_with_todo_sequences(&input, TodoSequence::scan(&setup)))
+    }
+
+    /// Parses input with additional todo keyword sequences, e.g. from setup files
+    pub(crate) fn parse_with_todo_sequences(
+        mut self,
+        s: &str,
+        mut todo_sequences: Vec<TodoSequence>,
+    ) -> Org {
+        todo_sequences.extend(TodoSequence::scan(s));
+        let todo_sequences = if todo_sequences.is_empty() {
+            vec![TodoSequence {
+                kind: TodoSequenceKind::Sequence,
+                todo: self.todo_keywords.0.iter().map(TodoKeyword::new).collect(),
+                done: self.todo_keywords.1.iter().map(TodoKeyword::new).collect(),
+            }]
+        } else {
+            self.todo_keywords = (
+                todo_sequences
+                    .iter()
+                    .flat_map(|seq| seq.todo.iter().map(|k| k.name.clone()))
+                    .collect(),
+                todo_sequences
+                    .iter()
+                    .flat_map(|seq| seq.done.iter().map(|k| k.name.clone()))
+                    .collect(),
+            );
+            todo_sequences
+        };
+
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "rayon")] {
+                let (node, diagnostics) = parallel_document_node(s, &self);
+            } else {
+                let sink = DiagnosticSink::new(s);
+                let input = Input {
+                    s,
+                    c: &self,
+                    d: Some(&sink),
+                };
+                let node = document_node(input).unwrap().1;
+                let diagnostics = sink.finish();
+            }
+        }
 
         Org {
             config: self,
             green: node.into_node().unwrap(),
+            diagnostics,
+            todo_sequences,
         }
     }
 }
@@ -54,3 +129,266 @@ impl Default for ParseConfig {
         }
     }
 }
+
+/// Kind of todo keyword sequence
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TodoSequenceKind {
+    /// Declared by `#+TODO:` or `#+SEQ_TODO:`, keywords are workflow states
+    Sequence,
+    /// Declared by `#+TYP_TODO:`, keywords are types, e.g. person names
+    Type,
+}
+
+/// When to record a note or timestamp for state changes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TodoLog {
+    /// `!`, records a timestamp
+    Time,
+    /// `@`, records a note with timestamp
+    Note,
+}
+
+/// A todo keyword with its fast-access settings, e.g. `WAIT(w@/!)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TodoKeyword {
+    pub name: String,
+    /// Fast-access key, e.g. `w`
+    pub key: Option<char>,
+    /// Logging when entering this state, e.g. `@`
+    pub log_enter: Option<TodoLog>,
+    /// Logging when leaving this state, e.g. `/!`
+    pub log_leave: Option<TodoLog>,
+}
+
+impl TodoKeyword {
+    fn new(name: impl Into<String>) -> Self {
+        TodoKeyword {
+            name: name.into(),
+            key: None,
+            log_enter: None,
+            log_leave: None,
+        }
+    }
+
+    /// Parses keyword like `WAIT`, `WAIT(w)` or `WAIT(w@/!)`
+    fn parse(s: &str) -> Self {
+        let Some((name, settings)) = s
+            .strip_suffix(')')
+            .and_then(|s| s.split_once('('))
+            .filter(|(name, _)| !name.is_empty())
+        else {
+            return TodoKeyword::new(s);
+        };
+
+        let log = |s: &str| match s {
+            "!" => Some(TodoLog::Time),
+            "@" => Some(TodoLog::Note),
+            _ => None,
+        };
+
+        let (enter, leave) = settings.split_once('/').unwrap_or((settings, ""));
+        let key = enter.chars().next().filter(|c| *c != '!' && *c != '@');
+        let enter = &enter[key.map_or(0, char::len_utf8)..];
+
+        TodoKeyword {
+            name: name.into(),
+            key,
+            log_enter: log(enter),
+            log_leave: log(leave),
+        }
+    }
+}
+
+/// A todo keyword sequence
+///
+/// ```rust
+/// use orgize::{Org, TodoLog};
+///
+/// let org = Org::parse("#+TODO: TODO(t) WAIT(w@/!) | DONE(d!) CANCELED(c@)\n* WAIT task");
+///
+/// let sequence = &org.todo_sequences()[0];
+/// assert_eq!(sequence.todo.len(), 2);
+/// assert_eq!(sequence.done.len(), 2);
+///
+/// let wait = org.todo_keyword("WAIT").unwrap();
+/// assert_eq!(wait.key, Some('w'));
+/// assert_eq!(wait.log_enter, Some(TodoLog::Note));
+/// assert_eq!(wait.log_leave, Some(TodoLog::Time));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TodoSequence {
+    pub kind: TodoSequenceKind,
+    /// Keywords before `|`
+    pub todo: Vec<TodoKeyword>,
+    /// Keywords after `|`, or the last keyword if there's no `|`
+    pub done: Vec<TodoKeyword>,
+}
+
+impl TodoSequence {
+    /// Collects todo keyword sequences declared in buffer
+    ///
+    /// Contents of source, example, export, comment and verse blocks are
+    /// skipped, since keywords in them are not settings.
+    pub(crate) fn scan(input: &str) -> Vec<TodoSequence> {
+        let lines: Vec<_> = input.lines().collect();
+        let mut sequences = vec![];
+
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i].trim_start();
+            i += 1;
+
+            let Some(line) = line.strip_prefix("#+") else {
+                continue;
+            };
+
+            if let Some(name) = lesser_block_name(line) {
+                // unterminated blocks are parsed as paragraphs, so their lines are kept
+                if let Some(end) = lines[i..]
+                    .iter()
+                    .take_while(|l| !is_headline(l))
+                    .position(|l| is_block_end(l, name))
+                {
+                    i += end + 1;
+                }
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let kind = if key.eq_ignore_ascii_case("TODO") || key.eq_ignore_ascii_case("SEQ_TODO") {
+                TodoSequenceKind::Sequence
+            } else if key.eq_ignore_ascii_case("TYP_TODO") {
+                TodoSequenceKind::Type
+            } else {
+                continue;
+            };
+            sequences.extend(TodoSequence::parse(kind, value));
+        }
+
+        sequences
+    }
+
+    fn parse(kind: TodoSequenceKind, value: &str) -> Option<TodoSequence> {
+        let mut words = value.split_ascii_whitespace();
+        let mut todo: Vec<_> = words
+            .by_ref()
+            .take_while(|w| *w != "|")
+            .map(TodoKeyword::parse)
+            .collect();
+        let mut done: Vec<_> = words.map(TodoKeyword::parse).collect();
+
+        if done.is_empty() {
+            done.extend(todo.pop());
+        }
+
+        if done.is_empty() {
+            None
+        } else {
+            Some(TodoSequence { kind, todo, done })
+        }
+    }
+}
+
+/// Returns name of block if line, without leading `#+`, begins a block whose
+/// contents are not parsed, e.g. `begin_src rust`
+fn lesser_block_name(line: &str) -> Option<&str> {
+    let name = line
+        .get(..6)
+        .filter(|begin| begin.eq_ignore_ascii_case("begin_"))
+        .map(|_| &line[6..])?;
+    let name = name.split_ascii_whitespace().next()?;
+    ["SRC", "EXAMPLE", "EXPORT", "COMMENT", "VERSE"]
+        .iter()
+        .any(|n| n.eq_ignore_ascii_case(name))
+        .then_some(name)
+}
+
+fn is_headline(line: &str) -> bool {
+    line.starts_with('*') && line.trim_start_matches('*').starts_with([' ', '\t'])
+}
+
+/// Returns true if line is `#+end_NAME`
+fn is_block_end(line: &str, name: &str) -> bool {
+    line.trim()
+        .strip_prefix("#+")
+        .and_then(|line| {
+            line.get(..4)
+                .filter(|end| end.eq_ignore_ascii_case("end_"))
+                .map(|_| &line[4..])
+        })
+        .is_some_and(|n| n.eq_ignore_ascii_case(name))
+}
+
+#[test]
+fn todo_sequences() {
+    let org = ParseConfig::default().parse(
+        "#+TODO: NEXT WAITING | DONE CANCELLED\n#+typ_todo: Fred Sara Lucy Mike\n#+SEQ_TODO: REPORT BUG | FIXED\n* Sara task\n* TODO task",
+    );
+
+    let sequences = org.todo_sequences();
+    assert_eq!(sequences.len(), 3);
+    assert_eq!(sequences[1].kind, TodoSequenceKind::Type);
+    assert_eq!(sequences[1].done, vec![TodoKeyword::new("Mike")]);
+    assert_eq!(
+        org.config().todo_keywords,
+        (
+            vec![
+                "NEXT".to_string(),
+                "WAITING".into(),
+                "Fred".into(),
+                "Sara".into(),
+                "Lucy".into(),
+                "REPORT".into(),
+                "BUG".into()
+            ],
+            vec![
+                "DONE".to_string(),
+                "CANCELLED".into(),
+                "Mike".into(),
+                "FIXED".into()
+            ]
+        )
+    );
+
+    // in-buffer settings replace default keywords
+    let headlines: Vec<_> = org.document().headlines().collect();
+    assert_eq!(headlines[0].todo_keyword().unwrap(), "Sara");
+    assert!(headlines[1].todo_keyword().is_none());
+
+    assert_eq!(
+        TodoKeyword::parse("WAIT(w@/!)"),
+        TodoKeyword {
+            name: "WAIT".into(),
+            key: Some('w'),
+            log_enter: Some(TodoLog::Note),
+            log_leave: Some(TodoLog::Time),
+        }
+    );
+    assert_eq!(
+        TodoKeyword::parse("DONE(!)"),
+        TodoKeyword {
+            name: "DONE".into(),
+            key: None,
+            log_enter: Some(TodoLog::Time),
+            log_leave: None,
+        }
+    );
+    assert_eq!(TodoKeyword::parse("(x)"), TodoKeyword::new("(x)"));
+
+    // keywords in source and example blocks are not settings
+    let org = Org::parse("#+begin_src org\n#+TODO: A | B\n#+end_src\n#+BEGIN_EXAMPLE\n#+TODO: C | D\n#+END_EXAMPLE\n#+begin_quote\n#+TODO: E | F\n#+end_quote\n* A task\n* E task");
+    assert_eq!(org.todo_sequences().len(), 1);
+    let headlines: Vec<_> = org.document().headlines().collect();
+    assert!(headlines[0].todo_keyword().is_none());
+    assert_eq!(headlines[1].todo_keyword().unwrap(), "E");
+
+    // unterminated blocks are paragraphs, and blocks end at headlines
+    let org = Org::parse("#+begin_src\n#+TODO: A | B\n* A task\n#+end_src");
+    assert_eq!(org.todo_sequences()[0].todo, vec![TodoKeyword::new("A")]);
+
+    // falls back to parse config
+    let org = Org::parse("* TODO task");
+    assert_eq!(org.todo_sequences()[0].todo, vec![TodoKeyword::new("TODO")]);
+}
@@ -0,0 +1,355 @@
+use rowan::{ast::AstNode, NodeOrToken};
+use std::fmt::Write as _;
+use std::mem::take;
+
+use super::event::{Container, Event};
+use super::html::{footnote_definition, headline_id};
+use super::{HtmlEscape, HtmlExport, HtmlHandler, HtmlTraverser, TraversalContext, Traverser};
+use crate::ast::{FnDef, Headline, Keyword};
+use crate::{Org, SyntaxKind};
+
+/// Default template of [`HtmlDocument`]
+pub const DEFAULT_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="{{language}}">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{{title}}</title>
+{{head}}</head>
+<body>
+{{preamble}}{{toc}}{{content}}{{footnotes}}{{postamble}}
+</body>
+</html>
+"#;
+
+/// Standalone html document configuration
+///
+/// Exports a complete html page by filling placeholders of
+/// [`template`](HtmlDocument::template):
+///
+/// | Placeholder       | Value                                                      |
+/// |-------------------|------------------------------------------------------------|
+/// | `{{language}}`    | `#+LANGUAGE`, or [`HtmlDocument::language`]                |
+/// | `{{title}}`       | `#+TITLE`                                                  |
+/// | `{{author}}`      | `#+AUTHOR`                                                 |
+/// | `{{date}}`        | `#+DATE`                                                   |
+/// | `{{description}}` | `#+DESCRIPTION`                                            |
+/// | `{{head}}`        | meta tags, stylesheets, scripts, `#+HTML_HEAD` and `#+HTML_HEAD_EXTRA` |
+/// | `{{preamble}}`    | title heading                                              |
+/// | `{{toc}}`         | table of contents                                          |
+/// | `{{content}}`     | exported document                                          |
+/// | `{{footnotes}}`   | footnote definitions                                       |
+/// | `{{postamble}}`   | author and date                                            |
+///
+/// Keyword values are html-escaped, unknown placeholders are kept as is.
+/// Footnote definitions only appear in `{{footnotes}}`, and headlines get an
+/// `id` attribute for table of contents to link to.
+///
+/// ```rust
+/// use orgize::{export::HtmlDocument, Org};
+///
+/// let org = Org::parse("#+TITLE: Notes\n#+AUTHOR: Jane\n* Intro\nSee[fn:1].\n\n[fn:1] orgmode.org");
+/// let config = HtmlDocument {
+///     template: "<title>{{title}}</title>\n{{toc}}\n{{content}}\n{{footnotes}}\n{{postamble}}".into(),
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     config.export(&org),
+///     r##"<title>Notes</title>
+/// <nav id="table-of-contents"><h2>Table of Contents</h2><ul><li><a href="#sec-1">Intro</a></li></ul></nav>
+/// <main><section></section><h1 id="sec-1">Intro</h1><section><p>See<sup><a id="fnr.1" class="footref" href="#fn.1" role="doc-noteref">1</a></sup>.
+/// </p></section></main>
+/// <div id="footnotes"><h2 class="footnotes">Footnotes</h2><div class="footdef"><sup><a id="fn.1" class="footnum" href="#fnr.1" role="doc-backlink">1</a></sup> <div class="footpara"> orgmode.org</div></div></div>
+/// <footer><p class="author">Author: Jane</p></footer>"##
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct HtmlDocument {
+    /// Page template, defaults to [`DEFAULT_HTML_TEMPLATE`]
+    pub template: String,
+
+    /// Stylesheet urls linked in `{{head}}`
+    pub stylesheets: Vec<String>,
+
+    /// Script urls loaded in `{{head}}`
+    pub scripts: Vec<String>,
+
+    /// Document language used if there's no `#+LANGUAGE` keyword
+    pub language: String,
+
+    /// Exports table of contents into `{{toc}}`
+    pub toc: bool,
+
+    /// Exports title heading into `{{preamble}}`
+    pub preamble: bool,
+
+    /// Exports author and date into `{{postamble}}`
+    pub postamble: bool,
+}
+
+impl Default for HtmlDocument {
+    fn default() -> Self {
+        HtmlDocument {
+            template: DEFAULT_HTML_TEMPLATE.to_string(),
+            stylesheets: vec![],
+            scripts: vec![],
+            language: "en".to_string(),
+            toc: true,
+            preamble: true,
+            postamble: true,
+        }
+    }
+}
+
+/// Values of export keywords, joined if a keyword appears multiple times
+#[derive(Default)]
+struct Metadata {
+    title: String,
+    author: String,
+    date: String,
+    description: String,
+    keywords: String,
+    language: String,
+    html_head: String,
+}
+
+impl Metadata {
+    fn new(org: &Org) -> Self {
+        let mut metadata = Metadata::default();
+
+        for keyword in org
+            .document()
+            .syntax
+            .descendants()
+            .filter_map(Keyword::cast)
+        {
+            let key = keyword.key();
+            let value = keyword.value();
+            let value = value.trim();
+
+            let (field, sep) = match key.to_ascii_uppercase().as_str() {
+                "TITLE" => (&mut metadata.title, " "),
+                "AUTHOR" => (&mut metadata.author, " "),
+                "DATE" => (&mut metadata.date, " "),
+                "DESCRIPTION" => (&mut metadata.description, " "),
+                "KEYWORDS" => (&mut metadata.keywords, " "),
+                "LANGUAGE" => (&mut metadata.language, " "),
+                "HTML_HEAD" | "HTML_HEAD_EXTRA" => (&mut metadata.html_head, "\n"),
+                _ => continue,
+            };
+
+            if !field.is_empty() {
+                field.push_str(sep);
+            }
+            field.push_str(value);
+        }
+
+        metadata
+    }
+}
+
+impl HtmlDocument {
+    /// Exports `org` as a standalone html document using [`HtmlExport`]
+    pub fn export(&self, org: &Org) -> String {
+        self.export_with(org, &mut HtmlExport::default())
+    }
+
+    /// Exports `org` as a standalone html document using given html handler
+    ///
+    /// The handler should be a fresh one, since its output is taken as
+    /// `{{content}}` and `{{footnotes}}`.
+    pub fn export_with<H: HtmlHandler>(&self, org: &Org, handler: &mut H) -> String {
+        let metadata = Metadata::new(org);
+
+        handler.html().headline_ids = true;
+        org.traverse(&mut Content(handler));
+        let content = take(&mut handler.html().output);
+        let footnotes = footnotes(org, handler);
+
+        let mut output = String::with_capacity(self.template.len() + content.len());
+        let mut rest = self.template.as_str();
+
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start..].find("}}") else {
+                break;
+            };
+            output += &rest[..start];
+            let name = &rest[start + 2..start + len];
+            rest = &rest[start + len + 2..];
+
+            let _ = match name {
+                "language" if metadata.language.is_empty() => {
+                    write!(&mut output, "{}", HtmlEscape(&self.language))
+                }
+                "language" => write!(&mut output, "{}", HtmlEscape(&metadata.language)),
+                "title" => write!(&mut output, "{}", HtmlEscape(&metadata.title)),
+                "author" => write!(&mut output, "{}", HtmlEscape(&metadata.author)),
+                "date" => write!(&mut output, "{}", HtmlEscape(&metadata.date)),
+                "description" => write!(&mut output, "{}", HtmlEscape(&metadata.description)),
+                "head" => self.head(&metadata, &mut output),
+                "preamble" => self.preamble(&metadata, &mut output),
+                "toc" => self.toc(org, &mut output),
+                "content" => write!(&mut output, "{content}"),
+                "footnotes" => write!(&mut output, "{footnotes}"),
+                "postamble" => self.postamble(&metadata, &mut output),
+                _ => write!(&mut output, "{{{{{name}}}}}"),
+            };
+        }
+        output += rest;
+
+        output
+    }
+
+    fn head(&self, metadata: &Metadata, output: &mut String) -> std::fmt::Result {
+        for (name, content) in [
+            ("author", &metadata.author),
+            ("description", &metadata.description),
+            ("keywords", &metadata.keywords),
+        ] {
+            if !content.is_empty() {
+                writeln!(
+                    output,
+                    r#"<meta name="{name}" content="{}">"#,
+                    HtmlEscape(content)
+                )?;
+            }
+        }
+        for href in &self.stylesheets {
+            writeln!(
+                output,
+                r#"<link rel="stylesheet" href="{}">"#,
+                HtmlEscape(href)
+            )?;
+        }
+        for src in &self.scripts {
+            writeln!(output, r#"<script src="{}"></script>"#, HtmlEscape(src))?;
+        }
+        if !metadata.html_head.is_empty() {
+            writeln!(output, "{}", metadata.html_head)?;
+        }
+        Ok(())
+    }
+
+    fn preamble(&self, metadata: &Metadata, output: &mut String) -> std::fmt::Result {
+        if self.preamble && !metadata.title.is_empty() {
+            write!(
+                output,
+                r#"<header><h1 class="title">{}</h1></header>"#,
+                HtmlEscape(&metadata.title)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn postamble(&self, metadata: &Metadata, output: &mut String) -> std::fmt::Result {
+        if !self.postamble || (metadata.author.is_empty() && metadata.date.is_empty()) {
+            return Ok(());
+        }
+        *output += "<footer>";
+        if !metadata.author.is_empty() {
+            write!(
+                output,
+                r#"<p class="author">Author: {}</p>"#,
+                HtmlEscape(&metadata.author)
+            )?;
+        }
+        if !metadata.date.is_empty() {
+            write!(
+                output,
+                r#"<p class="date">Date: {}</p>"#,
+                HtmlEscape(&metadata.date)
+            )?;
+        }
+        *output += "</footer>";
+        Ok(())
+    }
+
+    fn toc(&self, org: &Org, output: &mut String) -> std::fmt::Result {
+        fn list(headlines: impl Iterator<Item = Headline>, output: &mut String) {
+            let mut headlines = headlines.peekable();
+            if headlines.peek().is_none() {
+                return;
+            }
+            *output += "<ul>";
+            for headline in headlines {
+                let mut title = HtmlExport::default();
+                let mut ctx = TraversalContext::default();
+                for elem in headline.title() {
+                    title.element(elem, &mut ctx);
+                }
+                let _ = write!(
+                    output,
+                    r##"<li><a href="#{}">{}</a>"##,
+                    HtmlEscape(headline_id(&headline)),
+                    title.finish()
+                );
+                list(headline.headlines(), output);
+                *output += "</li>";
+            }
+            *output += "</ul>";
+        }
+
+        let document = org.document();
+        if self.toc && document.first_headline().is_some() {
+            *output += r#"<nav id="table-of-contents"><h2>Table of Contents</h2>"#;
+            list(document.headlines(), output);
+            *output += "</nav>";
+        }
+        Ok(())
+    }
+}
+
+/// Forwards events to html handler, except footnote definitions
+struct Content<'a, H>(&'a mut H);
+
+impl<H: HtmlHandler> Traverser for Content<'_, H> {
+    fn event(&mut self, event: Event, ctx: &mut TraversalContext) {
+        match event {
+            Event::Enter(Container::FnDef(_)) => ctx.skip(),
+            _ => HtmlTraverser(&mut *self.0).event(event, ctx),
+        }
+    }
+}
+
+/// Exports definitions of referenced footnotes, in order of first reference
+fn footnotes<H: HtmlHandler>(org: &Org, handler: &mut H) -> String {
+    let mut ctx = TraversalContext::default();
+    let definitions: Vec<_> = org
+        .document()
+        .syntax
+        .descendants()
+        .filter_map(FnDef::cast)
+        .collect();
+
+    // exporting a definition may reference new footnotes
+    let mut index = 0;
+    while index < handler.html().footnotes.len() {
+        let footnote = &handler.html().footnotes[index];
+        index += 1;
+
+        if let Some(fn_ref) = footnote.inline.clone() {
+            let contents = fn_ref
+                .syntax
+                .children_with_tokens()
+                .filter(|e| e.kind() != SyntaxKind::R_BRACKET)
+                .skip_while(|e| e.kind() != SyntaxKind::COLON)
+                .skip(1)
+                .skip_while(|e| e.kind() != SyntaxKind::COLON)
+                .skip(1);
+            footnote_definition(handler, index, contents, &mut ctx);
+        } else if !footnote.label.is_empty() {
+            let label = footnote.label.clone();
+            if let Some(fn_def) = definitions.iter().find(|d| d.label() == label) {
+                HtmlTraverser(&mut *handler)
+                    .element(NodeOrToken::Node(fn_def.syntax.clone()), &mut ctx);
+            }
+        }
+    }
+
+    let output = take(&mut handler.html().output);
+    if output.is_empty() {
+        output
+    } else {
+        format!(r#"<div id="footnotes"><h2 class="footnotes">Footnotes</h2>{output}</div>"#)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{ast::*, SyntaxToken};
+use crate::{ast::*, SyntaxNode, SyntaxToken};
 
 #[non_exhaustive]
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -48,6 +48,53 @@ pub enum Container {
     Keyword(Keyword),
 }
 
+impl Container {
+    /// Returns syntax node of container
+    pub fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Container::Document(n) => &n.syntax,
+            Container::Section(n) => &n.syntax,
+            Container::Paragraph(n) => &n.syntax,
+            Container::Headline(n) => &n.syntax,
+            Container::OrgTable(n) => &n.syntax,
+            Container::OrgTableRow(n) => &n.syntax,
+            Container::OrgTableCell(n) => &n.syntax,
+            Container::TableEl(n) => &n.syntax,
+            Container::List(n) => &n.syntax,
+            Container::ListItem(n) => &n.syntax,
+            Container::Drawer(n) => &n.syntax,
+            Container::DynBlock(n) => &n.syntax,
+            Container::FnDef(n) => &n.syntax,
+            Container::Comment(n) => &n.syntax,
+            Container::FixedWidth(n) => &n.syntax,
+            Container::SpecialBlock(n) => &n.syntax,
+            Container::QuoteBlock(n) => &n.syntax,
+            Container::CenterBlock(n) => &n.syntax,
+            Container::VerseBlock(n) => &n.syntax,
+            Container::CommentBlock(n) => &n.syntax,
+            Container::ExampleBlock(n) => &n.syntax,
+            Container::ExportBlock(n) => &n.syntax,
+            Container::SourceBlock(n) => &n.syntax,
+            Container::Link(n) => &n.syntax,
+            Container::RadioTarget(n) => &n.syntax,
+            Container::FnRef(n) => &n.syntax,
+            Container::Target(n) => &n.syntax,
+            Container::Bold(n) => &n.syntax,
+            Container::Strike(n) => &n.syntax,
+            Container::Italic(n) => &n.syntax,
+            Container::Underline(n) => &n.syntax,
+            Container::Verbatim(n) => &n.syntax,
+            Container::Code(n) => &n.syntax,
+            Container::Superscript(n) => &n.syntax,
+            Container::Subscript(n) => &n.syntax,
+            Container::BabelCall(n) => &n.syntax,
+            Container::PropertyDrawer(n) => &n.syntax,
+            Container::AffiliatedKeyword(n) => &n.syntax,
+            Container::Keyword(n) => &n.syntax,
+        }
+    }
+}
+
 #[non_exhaustive]
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Event {
@@ -68,3 +115,10 @@ pub enum Event {
     LatexEnvironment(LatexEnvironment),
     Entity(Entity),
 }
+
+/// Whether a container is being entered or left
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Visit {
+    Enter,
+    Leave,
+}
@@ -0,0 +1,301 @@
+use rowan::{ast::AstNode, NodeOrToken};
+
+use super::event::Event;
+use super::{TraversalContext, Traverser};
+use crate::{syntax::OrgLanguage, Org, ParseConfig, SyntaxKind, SyntaxNode};
+
+/// What to do with an element or object during export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterAction {
+    /// Exports it as is
+    Keep,
+    /// Drops it along with its descendants
+    Drop,
+    /// Exports given org source instead
+    ///
+    /// Replacement of an object is parsed as paragraph contents, and
+    /// replacement of an element is parsed as section contents. Use export
+    /// blocks or snippets to emit backend-specific output, e.g.
+    /// `#+begin_export html` or `@@html:<br>@@`.
+    ///
+    /// Replacements are parsed with the config of the filtered document,
+    /// including its in-buffer todo keywords, and exported as is. Filters
+    /// don't run on them again.
+    Replace(String),
+}
+
+type Filter = Box<dyn FnMut(&SyntaxNode) -> Option<FilterAction>>;
+
+/// A pipeline of filters that rewrite elements and objects before they reach an exporter
+///
+/// Each filter is registered for an AST node type and runs on every node of
+/// that type, in registration order. The first filter returning an action
+/// other than [`FilterAction::Keep`] wins. Since filters only see traversal
+/// events, the same pipeline works with [`HtmlExport`](super::HtmlExport)
+/// and any other [`Traverser`].
+///
+/// Filters only see nodes the traversal walks into. Nodes a traverser
+/// exports by itself, e.g. headline titles in [`HtmlExport`](super::HtmlExport),
+/// are not filtered.
+///
+/// ```rust
+/// use orgize::{
+///     ast::{Headline, Link, SpecialBlock},
+///     export::{ExportFilters, FilterAction, HtmlExport},
+///     rowan::ast::AstNode,
+///     Org, SyntaxKind,
+/// };
+///
+/// let mut filters = ExportFilters::new()
+///     // drops :noexport: subtrees
+///     .filter(|headline: &Headline| {
+///         if headline.tags().any(|tag| tag == "noexport") {
+///             FilterAction::Drop
+///         } else {
+///             FilterAction::Keep
+///         }
+///     })
+///     // rewrites .org links to .html
+///     .filter(|link: &Link| match link.path().strip_suffix(".org") {
+///         Some(path) if !link.has_description() => FilterAction::Replace(format!("[[{path}.html]]")),
+///         _ => FilterAction::Keep,
+///     })
+///     // replaces warning blocks with custom html, keeping their contents
+///     .filter(|block: &SpecialBlock| {
+///         if block.ty().is_some_and(|ty| ty.eq_ignore_ascii_case("warning")) {
+///             let contents = block
+///                 .syntax()
+///                 .children()
+///                 .find(|n| n.kind() == SyntaxKind::BLOCK_CONTENT)
+///                 .map(|n| n.to_string())
+///                 .unwrap_or_default();
+///             FilterAction::Replace(format!(
+///                 "#+begin_export html\n<div class=\"warning\">\n#+end_export\n{contents}#+begin_export html\n</div>\n#+end_export\n"
+///             ))
+///         } else {
+///             FilterAction::Keep
+///         }
+///     });
+///
+/// let org = Org::parse("* a\n[[file:b.org]]\n#+begin_warning\n*careful*\n#+end_warning\n* b :noexport:\nsecret");
+///
+/// let mut html = HtmlExport::default();
+/// filters.traverse(&org, &mut html);
+/// assert_eq!(
+///     html.finish(),
+///     "<main><h1>a</h1><section><p><a href=\"b.html\">b.html</a>\n</p>\
+///      <div class=\"warning\">\n<p><b>careful</b>\n</p></div>\n</section></main>"
+/// );
+/// ```
+#[derive(Default)]
+pub struct ExportFilters {
+    filters: Vec<Filter>,
+}
+
+impl ExportFilters {
+    pub fn new() -> Self {
+        ExportFilters::default()
+    }
+
+    /// Registers a filter for nodes of type `N`
+    pub fn filter<N, F>(mut self, mut f: F) -> Self
+    where
+        N: AstNode<Language = OrgLanguage>,
+        F: FnMut(&N) -> FilterAction + 'static,
+    {
+        self.filters
+            .push(Box::new(move |node| N::cast(node.clone()).map(|n| f(&n))));
+        self
+    }
+
+    /// Walks through `org` with `traverser`, applying filters
+    pub fn traverse<T: Traverser>(&mut self, org: &Org, traverser: &mut T) {
+        org.traverse(&mut Filtered {
+            filters: self,
+            config: org.config(),
+            inner: traverser,
+        });
+    }
+
+    fn action(&mut self, node: &SyntaxNode) -> FilterAction {
+        self.filters
+            .iter_mut()
+            .find_map(|f| f(node).filter(|action| *action != FilterAction::Keep))
+            .unwrap_or(FilterAction::Keep)
+    }
+}
+
+struct Filtered<'a, T> {
+    filters: &'a mut ExportFilters,
+    /// Config of the filtered document, used to parse replacements
+    config: &'a ParseConfig,
+    inner: &'a mut T,
+}
+
+impl<T: Traverser> Traverser for Filtered<'_, T> {
+    fn event(&mut self, event: Event, ctx: &mut TraversalContext) {
+        let (node, container) = match &event {
+            Event::Enter(container) => (container.syntax(), true),
+            Event::Leave(_) | Event::Text(_) => return self.inner.event(event, ctx),
+            _ => match leaf_syntax(&event) {
+                Some(node) => (node, false),
+                None => return self.inner.event(event, ctx),
+            },
+        };
+
+        match self.filters.action(node) {
+            FilterAction::Keep => self.inner.event(event, ctx),
+            FilterAction::Drop => {
+                if container {
+                    ctx.skip();
+                }
+            }
+            FilterAction::Replace(source) => {
+                replace(self.inner, node, &source, self.config, ctx);
+                if container {
+                    ctx.skip();
+                }
+            }
+        }
+    }
+}
+
+/// Returns syntax node of events other than `Enter`, `Leave` and `Text`
+fn leaf_syntax(event: &Event) -> Option<&SyntaxNode> {
+    let node = match event {
+        Event::Macros(n) => &n.syntax,
+        Event::Cookie(n) => &n.syntax,
+        Event::InlineCall(n) => &n.syntax,
+        Event::InlineSrc(n) => &n.syntax,
+        Event::Clock(n) => &n.syntax,
+        Event::LineBreak(n) => &n.syntax,
+        Event::Snippet(n) => &n.syntax,
+        Event::Rule(n) => &n.syntax,
+        Event::Timestamp(n) => &n.syntax,
+        Event::LatexFragment(n) => &n.syntax,
+        Event::LatexEnvironment(n) => &n.syntax,
+        Event::Entity(n) => &n.syntax,
+        _ => return None,
+    };
+    Some(node)
+}
+
+/// Exports org source in place of `node`, parsed with the config of the
+/// filtered document
+fn replace<T: Traverser>(
+    t: &mut T,
+    node: &SyntaxNode,
+    source: &str,
+    config: &ParseConfig,
+    ctx: &mut TraversalContext,
+) {
+    let document = config.clone().parse(source).document().syntax;
+
+    if node.kind().is_object() {
+        let paragraph = document
+            .descendants()
+            .find(|n| n.kind() == SyntaxKind::PARAGRAPH);
+        for child in paragraph.iter().flat_map(|p| p.children_with_tokens()) {
+            t.element(child, ctx);
+        }
+    } else {
+        for child in document.children_with_tokens() {
+            match child {
+                NodeOrToken::Node(section) if section.kind() == SyntaxKind::SECTION => {
+                    for child in section.children_with_tokens() {
+                        t.element(child, ctx);
+                    }
+                }
+                child => t.element(child, ctx),
+            }
+        }
+    }
+}
+
+#[test]
+fn filters() {
+    use super::{from_fn, Container, HtmlExport};
+    use crate::ast::{Headline, Link, QuoteBlock, Timestamp};
+
+    let org = Org::parse(
+        r#"* keep
+<2023-01-01 Sun> a [[https://example.com][link]] and [[https://example.com]]
+#+begin_quote
+quote
+#+end_quote
+** drop :noexport:
+dropped
+* replace
+text
+"#,
+    );
+
+    let mut filters = ExportFilters::new()
+        .filter(|headline: &Headline| {
+            if headline.tags().any(|t| t == "noexport") {
+                FilterAction::Drop
+            } else if headline.title_raw() == "replace" {
+                FilterAction::Replace(
+                    "* replaced\n#+begin_export html\n<hr>\n#+end_export\n".into(),
+                )
+            } else {
+                FilterAction::Keep
+            }
+        })
+        .filter(|_: &Timestamp| FilterAction::Drop)
+        .filter(|link: &Link| {
+            if link.has_description() {
+                FilterAction::Replace("@@html:<em>@@described@@html:</em>@@".into())
+            } else {
+                FilterAction::Keep
+            }
+        })
+        .filter(|_: &QuoteBlock| FilterAction::Replace(": fixed".into()));
+
+    let mut html = HtmlExport::default();
+    filters.traverse(&org, &mut html);
+    insta::assert_snapshot!(html.finish(), @r###"
+    <main><h1>keep</h1><section><p> a <em>described</em> and <a href="https://example.com">https://example.com</a>
+    </p>fixed</section><h1>replaced</h1><section><hr>
+    </section></main>
+    "###);
+
+    // same filters work with other traversers
+    let mut headlines = 0;
+    filters.traverse(
+        &org,
+        &mut from_fn(|event| {
+            if matches!(event, Event::Enter(Container::Headline(_))) {
+                headlines += 1;
+            }
+        }),
+    );
+    assert_eq!(headlines, 2);
+}
+
+#[test]
+fn replace_with_document_config() {
+    use super::{from_fn, Container};
+    use crate::ast::Headline;
+
+    let org = Org::parse("#+TODO: NEXT | DONE\n* a\n");
+
+    let mut filters = ExportFilters::new().filter(|headline: &Headline| {
+        if headline.title_raw() == "a" {
+            FilterAction::Replace("* NEXT b\n".into())
+        } else {
+            FilterAction::Keep
+        }
+    });
+
+    let mut keywords = vec![];
+    filters.traverse(
+        &org,
+        &mut from_fn(|event| {
+            if let Event::Enter(Container::Headline(headline)) = event {
+                keywords.push(headline.todo_keyword().map(|k| k.to_string()));
+            }
+        }),
+    );
+    assert_eq!(keywords, [Some("NEXT".to_string())]);
+}
@@ -0,0 +1,135 @@
+/// A syntax highlighter used by [`HtmlExport`](super::HtmlExport) for
+/// source blocks and inline source blocks
+///
+/// ```rust
+/// use orgize::{export::{Highlighter, HtmlExport, HtmlEscape}, Org};
+///
+/// /// Wraps keyword `fn` in a span
+/// struct Keywords;
+///
+/// impl Highlighter for Keywords {
+///     fn highlight(&self, language: &str, code: &str) -> Option<String> {
+///         (language == "rust").then(|| {
+///             HtmlEscape(code)
+///                 .to_string()
+///                 .replace("fn ", "<span class=\"keyword\">fn</span> ")
+///         })
+///     }
+/// }
+///
+/// let mut html = HtmlExport::default().with_highlighter(Keywords);
+/// Org::parse("#+begin_src rust\nfn main() {}\n#+end_src\nsrc_c{int main();}").traverse(&mut html);
+/// assert_eq!(
+///     html.finish(),
+///     "<main><section><pre><code class=\"language-rust\"><span class=\"keyword\">fn</span> main() {}\n</code></pre>\
+///      <p><code class=\"src src-c\">int main();</code></p></section></main>"
+/// );
+/// ```
+pub trait Highlighter {
+    /// Returns highlighted html of `code` written in `language`
+    ///
+    /// Returning `None` falls back to the escaped code, e.g. when the
+    /// language is unknown.
+    fn highlight(&self, language: &str, code: &str) -> Option<String>;
+}
+
+#[cfg(feature = "syntect")]
+pub use self::syntect::SyntectHighlighter;
+
+#[cfg(feature = "syntect")]
+mod syntect {
+    use syntect::easy::HighlightLines;
+    use syntect::highlighting::{Theme, ThemeSet};
+    use syntect::html::{
+        css_for_theme_with_class_style, styled_line_to_highlighted_html, ClassStyle,
+        ClassedHTMLGenerator, IncludeBackground,
+    };
+    use syntect::parsing::SyntaxSet;
+    use syntect::util::LinesWithEndings;
+
+    use super::Highlighter;
+
+    const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "syn-" };
+
+    /// A [`Highlighter`] backed by syntect with its bundled grammars and themes
+    ///
+    /// Languages are looked up by name or file extension, e.g. `rust`, `rs` or `sh`.
+    ///
+    /// ```rust
+    /// use orgize::{export::{HtmlExport, SyntectHighlighter}, Org};
+    ///
+    /// let org = Org::parse("#+begin_src rust\nfn main() {}\n#+end_src");
+    ///
+    /// // class-based spans, styled by `SyntectHighlighter::css`
+    /// let mut html = HtmlExport::default().with_highlighter(SyntectHighlighter::classed());
+    /// org.traverse(&mut html);
+    /// assert!(html.finish().contains(r#"<span class="syn-storage syn-type syn-function syn-rust">fn</span>"#));
+    ///
+    /// // inline styles from a bundled theme
+    /// let highlighter = SyntectHighlighter::inline("InspiredGitHub").unwrap();
+    /// let mut html = HtmlExport::default().with_highlighter(highlighter);
+    /// org.traverse(&mut html);
+    /// assert!(html.finish().contains(r#"<span style="font-weight:bold;color:#a71d5d;">fn </span>"#));
+    ///
+    /// assert!(SyntectHighlighter::css("InspiredGitHub").unwrap().contains(".syn-comment"));
+    /// ```
+    pub struct SyntectHighlighter {
+        syntaxes: SyntaxSet,
+        theme: Option<Theme>,
+    }
+
+    impl SyntectHighlighter {
+        /// Creates a highlighter emitting spans with `syn-` prefixed classes
+        pub fn classed() -> Self {
+            SyntectHighlighter {
+                syntaxes: SyntaxSet::load_defaults_newlines(),
+                theme: None,
+            }
+        }
+
+        /// Creates a highlighter emitting inline-styled spans using given
+        /// bundled theme, e.g. `InspiredGitHub` or `base16-ocean.dark`
+        ///
+        /// Returns `None` if the theme doesn't exist.
+        pub fn inline(theme: &str) -> Option<Self> {
+            let theme = ThemeSet::load_defaults().themes.remove(theme)?;
+            Some(SyntectHighlighter {
+                syntaxes: SyntaxSet::load_defaults_newlines(),
+                theme: Some(theme),
+            })
+        }
+
+        /// Returns stylesheet for spans of [`SyntectHighlighter::classed`]
+        /// using given bundled theme
+        pub fn css(theme: &str) -> Option<String> {
+            let theme = ThemeSet::load_defaults().themes.remove(theme)?;
+            css_for_theme_with_class_style(&theme, CLASS_STYLE).ok()
+        }
+    }
+
+    impl Highlighter for SyntectHighlighter {
+        fn highlight(&self, language: &str, code: &str) -> Option<String> {
+            let syntax = self.syntaxes.find_syntax_by_token(language)?;
+
+            if let Some(theme) = &self.theme {
+                let mut lines = HighlightLines::new(syntax, theme);
+                let mut output = String::new();
+                for line in LinesWithEndings::from(code) {
+                    let regions = lines.highlight_line(line, &self.syntaxes).ok()?;
+                    output +=
+                        &styled_line_to_highlighted_html(&regions, IncludeBackground::No).ok()?;
+                }
+                Some(output)
+            } else {
+                let mut generator =
+                    ClassedHTMLGenerator::new_with_class_style(syntax, &self.syntaxes, CLASS_STYLE);
+                for line in LinesWithEndings::from(code) {
+                    generator
+                        .parse_html_for_line_which_includes_newline(line)
+                        .ok()?;
+                }
+                Some(generator.finalize())
+            }
+        }
+    }
+}
@@ -1,12 +1,17 @@
-use rowan::NodeOrToken;
+use rowan::{ast::AstNode, Direction, NodeOrToken};
 use std::cmp::min;
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Write as _;
 
-use super::event::{Container, Event};
+use super::event::{Container, Event, Visit};
+use super::highlight::Highlighter;
+use super::mathml::latex_to_mathml;
+use super::numbering::{standalone_image, Numbered, Numbering};
 use super::TraversalContext;
 use super::Traverser;
-use crate::SyntaxKind;
+use crate::ast::*;
+use crate::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
 
 /// A wrapper for escaping sensitive characters in html.
 ///
@@ -49,11 +54,35 @@ impl<S: AsRef<str>> fmt::Display for HtmlEscape<S> {
 
 #[derive(Default)]
 pub struct HtmlExport {
-    output: String,
+    pub(crate) output: String,
 
     in_descriptive_list: Vec<bool>,
 
     table_row: TableRow,
+
+    /// Footnotes in order of first reference
+    pub(crate) footnotes: Vec<Footnote>,
+
+    /// Adds `id` attribute to headlines, see [`headline_id`]
+    pub(crate) headline_ids: bool,
+
+    /// Numbering of exported document, computed when first needed
+    numbering: Option<Numbering>,
+
+    /// Coderef labels of exported document, computed when first needed
+    coderefs: Option<HashMap<String, String>>,
+
+    highlighter: Option<Box<dyn Highlighter>>,
+
+    /// Converts LaTeX fragments and environments into MathML
+    mathml: bool,
+}
+
+pub(crate) struct Footnote {
+    /// Footnote label, empty for anonymous footnote
+    pub(crate) label: String,
+    /// Footnote reference containing the inline definition
+    pub(crate) inline: Option<FnRef>,
 }
 
 #[derive(Default, PartialEq, Eq)]
@@ -73,246 +102,1002 @@ impl HtmlExport {
     pub fn finish(self) -> String {
         self.output
     }
-}
 
-impl Traverser for HtmlExport {
-    fn event(&mut self, event: Event, ctx: &mut TraversalContext) {
-        match event {
-            Event::Enter(Container::Document(_)) => self.output += "<main>",
-            Event::Leave(Container::Document(_)) => self.output += "</main>",
-
-            Event::Enter(Container::Headline(headline)) => {
-                let level = min(headline.level(), 6);
-                let _ = write!(&mut self.output, "<h{level}>");
-                for elem in headline.title() {
-                    self.element(elem, ctx);
-                }
-                let _ = write!(&mut self.output, "</h{level}>");
-            }
-            Event::Leave(Container::Headline(_)) => {}
+    /// Highlights source blocks and inline source blocks using given highlighter
+    pub fn with_highlighter(mut self, highlighter: impl Highlighter + 'static) -> Self {
+        self.highlighter = Some(Box::new(highlighter));
+        self
+    }
 
-            Event::Enter(Container::Paragraph(_)) => self.output += "<p>",
-            Event::Leave(Container::Paragraph(_)) => self.output += "</p>",
+    /// Converts LaTeX fragments and environments into MathML, see [`latex_to_mathml`]
+    ///
+    /// Unsupported input is exported as LaTeX source, as if it's disabled.
+    pub fn with_mathml(mut self, mathml: bool) -> Self {
+        self.mathml = mathml;
+        self
+    }
+}
 
-            Event::Enter(Container::Section(_)) => self.output += "<section>",
-            Event::Leave(Container::Section(_)) => self.output += "</section>",
+impl HtmlExport {
+    /// Returns numbering of the document containing `node`
+    fn numbering(&mut self, node: &SyntaxNode) -> &Numbering {
+        self.numbering.get_or_insert_with(|| {
+            let root = node.ancestors().last().unwrap_or_else(|| node.clone());
+            Numbering::new(&root)
+        })
+    }
+
+    /// Returns text displayed by link to coderef `label` in the document containing `node`
+    fn coderef(&mut self, node: &SyntaxNode, label: &str) -> Option<&String> {
+        self.coderefs
+            .get_or_insert_with(|| {
+                let root = node.ancestors().last().unwrap_or_else(|| node.clone());
+                coderefs(&root)
+            })
+            .get(label)
+    }
 
-            Event::Enter(Container::Italic(_)) => self.output += "<i>",
-            Event::Leave(Container::Italic(_)) => self.output += "</i>",
+    fn numbered(&mut self, node: &SyntaxNode) -> Option<Numbered> {
+        self.numbering(node).get(node).cloned()
+    }
 
-            Event::Enter(Container::Bold(_)) => self.output += "<b>",
-            Event::Leave(Container::Bold(_)) => self.output += "</b>",
+    /// Writes LaTeX source, or MathML if enabled and supported
+    fn write_latex(&mut self, latex: &str) {
+        match self.mathml.then(|| latex_to_mathml(latex)).flatten() {
+            Some(mathml) => {
+                self.output += &mathml;
+                self.output += &latex[latex.trim_end().len()..];
+            }
+            None => self.output += latex,
+        }
+    }
 
-            Event::Enter(Container::Strike(_)) => self.output += "<s>",
-            Event::Leave(Container::Strike(_)) => self.output += "</s>",
+    fn highlight(&self, language: &str, code: &str) -> Option<String> {
+        if language.is_empty() {
+            return None;
+        }
+        self.highlighter.as_ref()?.highlight(language, code)
+    }
 
-            Event::Enter(Container::Underline(_)) => self.output += "<u>",
-            Event::Leave(Container::Underline(_)) => self.output += "</u>",
+    /// Returns number of footnote, and whether it's referenced for the first time
+    fn footnote(&mut self, label: &str, inline: Option<FnRef>) -> (usize, bool) {
+        if !label.is_empty() {
+            if let Some(index) = self.footnotes.iter().position(|f| f.label == label) {
+                return (index + 1, false);
+            }
+        }
+        self.footnotes.push(Footnote {
+            label: label.to_string(),
+            inline,
+        });
+        (self.footnotes.len(), true)
+    }
 
-            Event::Enter(Container::Verbatim(_)) => self.output += "<code>",
-            Event::Leave(Container::Verbatim(_)) => self.output += "</code>",
+    /// Writes source lines with line numbers and coderef labels
+    ///
+    /// Contents of all lines are highlighted at once, so multi-line comments
+    /// and strings are recognized, then split into lines.
+    fn source_lines(&mut self, language: &str, lines: &[SourceLine], retain_labels: bool) {
+        let width = lines
+            .iter()
+            .filter_map(|line| line.number)
+            .max()
+            .map_or(0, |n| n.to_string().len());
 
-            Event::Enter(Container::Code(_)) => self.output += "<code>",
-            Event::Leave(Container::Code(_)) => self.output += "</code>",
+        let code: String = lines
+            .iter()
+            .map(|line| line.content.clone() + "\n")
+            .collect();
+        let highlighted = self
+            .highlight(language, &code)
+            .map(|html| split_html_lines(&html))
+            .filter(|html| html.len() == lines.len());
 
-            Event::Enter(Container::SourceBlock(block)) => {
+        for (i, line) in lines.iter().enumerate() {
+            if let Some(label) = &line.label {
                 let _ = write!(
                     &mut self.output,
-                    r#"<pre><code class="language-{}">"#,
-                    HtmlEscape(&block.language().unwrap_or_default())
+                    r#"<span id="coderef-{}" class="coderef-off">"#,
+                    HtmlEscape(label)
                 );
             }
-            Event::Leave(Container::SourceBlock(_)) => self.output += "</code></pre>",
-
-            Event::Enter(Container::QuoteBlock(_)) => self.output += "<blockquote>",
-            Event::Leave(Container::QuoteBlock(_)) => self.output += "</blockquote>",
+            if let Some(number) = line.number {
+                let _ = write!(
+                    &mut self.output,
+                    r#"<span class="linenr">{number:>width$}: </span>"#
+                );
+            }
+            match &highlighted {
+                Some(html) => self.output += &html[i],
+                None => {
+                    let _ = write!(&mut self.output, "{}", HtmlEscape(&line.content));
+                }
+            }
+            if let Some(label) = &line.label {
+                if retain_labels {
+                    let _ = write!(&mut self.output, " ({})", HtmlEscape(label));
+                }
+                self.output += "</span>";
+            }
+            self.output += "\n";
+        }
+    }
+}
 
-            Event::Enter(Container::VerseBlock(_)) => self.output += "<p class=\"verse\">",
-            Event::Leave(Container::VerseBlock(_)) => self.output += "</p>",
+/// Splits highlighted html into lines, without line endings
+///
+/// Elements spanning multiple lines are closed at the end of each line and
+/// reopened at the beginning of the next one. Trailing markup without any text
+/// after the last line ending is dropped.
+fn split_html_lines(html: &str) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    let mut has_text = false;
+    // opening tags of elements which are not closed yet
+    let mut open: Vec<&str> = vec![];
 
-            Event::Enter(Container::ExampleBlock(_)) => self.output += "<pre class=\"example\">",
-            Event::Leave(Container::ExampleBlock(_)) => self.output += "</pre>",
+    let mut rest = html;
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            let len = rest.find('>').map_or(rest.len(), |i| i + 1);
+            let tag = &rest[..len];
+            if tag.starts_with("</") {
+                open.pop();
+            } else if !tag.ends_with("/>") {
+                open.push(tag);
+            }
+            line += tag;
+            rest = &rest[len..];
+        } else if c == '\n' {
+            for tag in open.iter().rev() {
+                let name = tag[1..]
+                    .split(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .next()
+                    .unwrap_or_default();
+                let _ = write!(&mut line, "</{name}>");
+            }
+            lines.push(std::mem::replace(&mut line, open.concat()));
+            has_text = false;
+            rest = &rest[1..];
+        } else {
+            line.push(c);
+            has_text = true;
+            rest = &rest[c.len_utf8()..];
+        }
+    }
 
-            Event::Enter(Container::CenterBlock(_)) => self.output += "<div class=\"center\">",
-            Event::Leave(Container::CenterBlock(_)) => self.output += "</div>",
+    if has_text {
+        lines.push(line);
+    }
 
-            Event::Enter(Container::CommentBlock(_)) => self.output += "<!--",
-            Event::Leave(Container::CommentBlock(_)) => self.output += "-->",
+    lines
+}
 
-            Event::Enter(Container::Comment(_)) => self.output += "<!--",
-            Event::Leave(Container::Comment(_)) => self.output += "-->",
+/// Returns `CUSTOM_ID` property of headline, or an id based on its position
+/// like `sec-1-2`
+pub(crate) fn headline_id(headline: &Headline) -> String {
+    if let Some(id) = headline
+        .properties()
+        .and_then(|drawer| drawer.get("CUSTOM_ID"))
+    {
+        return id.to_string();
+    }
 
-            Event::Enter(Container::Subscript(_)) => self.output += "<sub>",
-            Event::Leave(Container::Subscript(_)) => self.output += "</sub>",
+    let mut numbers: Vec<_> = headline
+        .syntax
+        .ancestors()
+        .filter(|n| n.kind() == SyntaxKind::HEADLINE)
+        .map(|n| {
+            n.siblings(Direction::Prev)
+                .filter(|n| n.kind() == SyntaxKind::HEADLINE)
+                .count()
+                .to_string()
+        })
+        .collect();
+    numbers.reverse();
 
-            Event::Enter(Container::Superscript(_)) => self.output += "<sup>",
-            Event::Leave(Container::Superscript(_)) => self.output += "</sup>",
+    format!("sec-{}", numbers.join("-"))
+}
 
-            Event::Enter(Container::List(list)) => {
-                self.output += if list.is_ordered() {
-                    self.in_descriptive_list.push(false);
-                    "<ol>"
-                } else if list.is_descriptive() {
-                    self.in_descriptive_list.push(true);
-                    "<dl>"
-                } else {
-                    self.in_descriptive_list.push(false);
-                    "<ul>"
-                };
+/// Writes attributes from `#+ATTR_HTML` keywords of node, merging `class`
+/// attribute into given class, and using given id unless there's an `id` attribute
+fn write_attributes(output: &mut String, node: &SyntaxNode, class: &str, id: Option<&str>) {
+    let mut class = class.to_string();
+    let mut rest = String::new();
+    let attributes = attributes(node, "HTML");
+    if let Some(id) = id.filter(|_| attributes.iter().all(|(k, _)| k != "id")) {
+        let _ = write!(&mut rest, r#" id="{}""#, HtmlEscape(id));
+    }
+    for (key, value) in attributes {
+        if key == "class" {
+            if !class.is_empty() {
+                class.push(' ');
             }
-            Event::Leave(Container::List(list)) => {
-                self.output += if list.is_ordered() {
-                    "</ol>"
-                } else if let Some(true) = self.in_descriptive_list.last() {
-                    "</dl>"
-                } else {
-                    "</ul>"
-                };
-                self.in_descriptive_list.pop();
-            }
-            Event::Enter(Container::ListItem(list_item)) => {
-                if let Some(&true) = self.in_descriptive_list.last() {
-                    self.output += "<dt>";
-                    for elem in list_item.tag() {
-                        self.element(elem, ctx);
-                    }
-                    self.output += "</dt><dd>";
-                } else {
-                    self.output += "<li>";
+            class.push_str(&value);
+        } else {
+            let _ = write!(&mut rest, r#" {}="{}""#, HtmlEscape(key), HtmlEscape(value));
+        }
+    }
+    if !class.is_empty() {
+        let _ = write!(output, r#" class="{}""#, HtmlEscape(class));
+    }
+    *output += &rest;
+}
+
+/// Writes caption prefixed with number of element, e.g. `Table 1: caption`
+fn write_caption(output: &mut String, numbered: &Numbered, caption: &str) {
+    let category = numbered.category.label();
+    let _ = write!(
+        output,
+        r#"<span class="{}-number">{category} {}:</span> {}"#,
+        category.to_ascii_lowercase(),
+        numbered.number,
+        HtmlEscape(caption.trim())
+    );
+}
+
+/// Exports footnote definition with number `n` and given contents
+pub(crate) fn footnote_definition<H: HtmlHandler + ?Sized>(
+    handler: &mut H,
+    n: usize,
+    contents: impl Iterator<Item = SyntaxElement>,
+    ctx: &mut TraversalContext,
+) {
+    let _ = write!(
+        &mut handler.html().output,
+        r##"<div class="footdef"><sup><a id="fn.{n}" class="footnum" href="#fnr.{n}" role="doc-backlink">{n}</a></sup> <div class="footpara">"##
+    );
+    for elem in contents {
+        match elem {
+            NodeOrToken::Token(token) => handler.text(token, ctx),
+            node => HtmlTraverser(&mut *handler).element(node, ctx),
+        }
+    }
+    handler.html().output += "</div></div>";
+}
+
+/// Returns coderef labels of all source blocks under `root`, mapped to the
+/// text displayed by links like `[[(label)]]`
+fn coderefs(root: &SyntaxNode) -> HashMap<String, String> {
+    let mut coderefs = HashMap::new();
+
+    for block in root.descendants().filter_map(SourceBlock::cast) {
+        let use_labels = block.use_labels();
+        for (index, line) in block.lines().into_iter().enumerate() {
+            let Some(label) = line.label else {
+                continue;
+            };
+            let text = if use_labels {
+                label.clone()
+            } else {
+                line.number.unwrap_or(index as u32 + 1).to_string()
+            };
+            coderefs.entry(label).or_insert(text);
+        }
+    }
+
+    coderefs
+}
+
+/// Renders org elements to html, one method per container and leaf
+///
+/// Every method has a default implementation, so implementors only override
+/// the ones they care about. [`HtmlExport`] is the default implementation.
+/// Wrap a handler in [`HtmlTraverser`] to pass it to
+/// [`Org::traverse`](crate::Org::traverse).
+///
+/// Container methods are called twice, with [`Visit::Enter`] before its
+/// children and [`Visit::Leave`] after them.
+///
+/// ```rust
+/// use orgize::{
+///     ast::{Headline, SourceBlock},
+///     export::{HtmlExport, HtmlHandler, HtmlTraverser, TraversalContext, Visit},
+///     Org,
+/// };
+///
+/// #[derive(Default)]
+/// struct MyHtml(HtmlExport);
+///
+/// impl HtmlHandler for MyHtml {
+///     fn html(&mut self) -> &mut HtmlExport {
+///         &mut self.0
+///     }
+///
+///     fn src_block(&mut self, block: SourceBlock, visit: Visit, ctx: &mut TraversalContext) {
+///         if visit == Visit::Enter {
+///             self.0.push_str("<pre class=\"src\">");
+///             self.0.push_str(block.value());
+///             self.0.push_str("</pre>");
+///             ctx.skip();
+///         }
+///     }
+/// }
+///
+/// let mut html = MyHtml::default();
+/// Org::parse("*bold*\n#+begin_src c\n1 < 2\n#+end_src").traverse(&mut HtmlTraverser(&mut html));
+/// assert_eq!(
+///     html.0.finish(),
+///     "<main><section><p><b>bold</b>\n</p><pre class=\"src\">1 < 2\n</pre></section></main>"
+/// );
+/// ```
+#[allow(unused_variables)]
+pub trait HtmlHandler {
+    /// Returns the html export that default methods write into
+    fn html(&mut self) -> &mut HtmlExport;
+
+    fn document(&mut self, document: Document, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<main>",
+            Visit::Leave => "</main>",
+        };
+    }
+
+    fn section(&mut self, section: Section, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<section>",
+            Visit::Leave => "</section>",
+        };
+    }
+
+    fn paragraph(&mut self, paragraph: Paragraph, visit: Visit, ctx: &mut TraversalContext) {
+        let html = self.html();
+        // only paragraphs containing a standalone image are numbered
+        match (visit, html.numbered(&paragraph.syntax)) {
+            (Visit::Enter, Some(figure)) => {
+                html.output += "<figure";
+                if let Some(name) = &figure.name {
+                    let _ = write!(&mut html.output, r#" id="{}""#, HtmlEscape(name));
                 }
+                html.output += ">";
             }
-            Event::Leave(Container::ListItem(_)) => {
-                if let Some(&true) = self.in_descriptive_list.last() {
-                    self.output += "</dd>";
-                } else {
-                    self.output += "</li>";
+            (Visit::Enter, None) => html.output += "<p>",
+            (Visit::Leave, Some(figure)) => {
+                if let Some(caption) = paragraph.caption().and_then(|c| c.value()) {
+                    html.output += "<figcaption>";
+                    write_caption(&mut html.output, &figure, &caption);
+                    html.output += "</figcaption>";
                 }
+                html.output += "</figure>";
+            }
+            (Visit::Leave, None) => html.output += "</p>",
+        }
+    }
+
+    fn headline(&mut self, headline: Headline, visit: Visit, ctx: &mut TraversalContext) {
+        if visit == Visit::Enter {
+            let level = min(headline.level(), 6);
+            let html = self.html();
+            if html.headline_ids {
+                let id = headline_id(&headline);
+                let _ = write!(&mut html.output, "<h{level} id=\"{}\">", HtmlEscape(id));
+            } else {
+                let _ = write!(&mut html.output, "<h{level}>");
+            }
+            for elem in headline.title() {
+                HtmlTraverser(&mut *self).element(elem, ctx);
             }
+            let _ = write!(&mut self.html().output, "</h{level}>");
+        }
+    }
 
-            Event::Enter(Container::OrgTable(table)) => {
-                self.output += "<table>";
-                self.table_row = if table.has_header() {
+    fn table(&mut self, table: OrgTable, visit: Visit, ctx: &mut TraversalContext) {
+        let html = self.html();
+        match visit {
+            Visit::Enter => {
+                let numbered = html.numbered(&table.syntax);
+                let name = numbered.as_ref().and_then(|n| n.name.as_deref());
+                html.output += "<table";
+                write_attributes(&mut html.output, &table.syntax, "", name);
+                html.output += ">";
+                if let (Some(numbered), Some(caption)) =
+                    (&numbered, table.caption().and_then(|c| c.value()))
+                {
+                    html.output += "<caption>";
+                    write_caption(&mut html.output, numbered, &caption);
+                    html.output += "</caption>";
+                }
+                html.table_row = if table.has_header() {
                     TableRow::HeaderRule
                 } else {
                     TableRow::BodyRule
                 }
             }
-            Event::Leave(Container::OrgTable(_)) => {
-                match self.table_row {
-                    TableRow::Body => self.output += "</tbody>",
-                    TableRow::Header => self.output += "</thead>",
+            Visit::Leave => {
+                match html.table_row {
+                    TableRow::Body => html.output += "</tbody>",
+                    TableRow::Header => html.output += "</thead>",
                     _ => {}
                 }
-                self.output += "</table>";
-            }
-            Event::Enter(Container::OrgTableRow(row)) => {
-                if row.is_rule() {
-                    match self.table_row {
-                        TableRow::Body => {
-                            self.output += "</tbody>";
-                            self.table_row = TableRow::BodyRule;
-                        }
-                        TableRow::Header => {
-                            self.output += "</thead>";
-                            self.table_row = TableRow::BodyRule;
-                        }
-                        _ => {}
-                    }
-                    ctx.skip();
-                } else {
-                    match self.table_row {
-                        TableRow::HeaderRule => {
-                            self.table_row = TableRow::Header;
-                            self.output += "<thead>";
-                        }
-                        TableRow::BodyRule => {
-                            self.table_row = TableRow::Body;
-                            self.output += "<tbody>";
-                        }
-                        _ => {}
-                    }
-                    self.output += "<tr>";
+                html.output += "</table>";
+            }
+        }
+    }
+
+    fn table_row(&mut self, row: OrgTableRow, visit: Visit, ctx: &mut TraversalContext) {
+        let html = self.html();
+        if row.is_rule() {
+            match html.table_row {
+                TableRow::Body => {
+                    html.output += "</tbody>";
+                    html.table_row = TableRow::BodyRule;
                 }
+                TableRow::Header => {
+                    html.output += "</thead>";
+                    html.table_row = TableRow::BodyRule;
+                }
+                _ => {}
             }
-            Event::Leave(Container::OrgTableRow(row)) => {
-                if row.is_rule() {
-                    match self.table_row {
-                        TableRow::Body => {
-                            self.output += "</tbody>";
-                            self.table_row = TableRow::BodyRule;
-                        }
-                        TableRow::Header => {
-                            self.output += "</thead>";
-                            self.table_row = TableRow::BodyRule;
-                        }
-                        _ => {}
-                    }
-                    ctx.skip();
-                } else {
-                    self.output += "</tr>";
+            ctx.skip();
+        } else if visit == Visit::Enter {
+            match html.table_row {
+                TableRow::HeaderRule => {
+                    html.table_row = TableRow::Header;
+                    html.output += "<thead>";
                 }
+                TableRow::BodyRule => {
+                    html.table_row = TableRow::Body;
+                    html.output += "<tbody>";
+                }
+                _ => {}
             }
-            Event::Enter(Container::OrgTableCell(_)) => self.output += "<td>",
-            Event::Leave(Container::OrgTableCell(_)) => self.output += "</td>",
+            html.output += "<tr>";
+        } else {
+            html.output += "</tr>";
+        }
+    }
 
-            Event::Enter(Container::Link(link)) => {
-                let path = link.path();
-                let path = path.trim_start_matches("file:");
+    fn table_cell(&mut self, cell: OrgTableCell, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<td>",
+            Visit::Leave => "</td>",
+        };
+    }
 
-                if link.is_image() {
-                    let _ = write!(&mut self.output, r#"<img src="{}">"#, HtmlEscape(&path));
-                    return ctx.skip();
-                }
+    fn table_el(&mut self, table: TableEl, visit: Visit, ctx: &mut TraversalContext) {}
 
-                let _ = write!(&mut self.output, r#"<a href="{}">"#, HtmlEscape(&path));
+    fn list(&mut self, list: List, visit: Visit, ctx: &mut TraversalContext) {
+        let html = self.html();
+        match visit {
+            Visit::Enter => {
+                html.output += if list.is_ordered() {
+                    html.in_descriptive_list.push(false);
+                    "<ol"
+                } else if list.is_descriptive() {
+                    html.in_descriptive_list.push(true);
+                    "<dl"
+                } else {
+                    html.in_descriptive_list.push(false);
+                    "<ul"
+                };
+                write_attributes(&mut html.output, &list.syntax, "", None);
+                html.output += ">";
+            }
+            Visit::Leave => {
+                html.output += if list.is_ordered() {
+                    "</ol>"
+                } else if let Some(true) = html.in_descriptive_list.last() {
+                    "</dl>"
+                } else {
+                    "</ul>"
+                };
+                html.in_descriptive_list.pop();
+            }
+        }
+    }
 
-                if !link.has_description() {
-                    let _ = write!(&mut self.output, "{}</a>", HtmlEscape(&path));
-                    ctx.skip();
+    fn list_item(&mut self, list_item: ListItem, visit: Visit, ctx: &mut TraversalContext) {
+        let descriptive = self.html().in_descriptive_list.last() == Some(&true);
+        match visit {
+            Visit::Enter if descriptive => {
+                self.html().output += "<dt>";
+                for elem in list_item.tag() {
+                    HtmlTraverser(&mut *self).element(elem, ctx);
                 }
+                self.html().output += "</dt><dd>";
+            }
+            Visit::Enter => self.html().output += "<li>",
+            Visit::Leave if descriptive => self.html().output += "</dd>",
+            Visit::Leave => self.html().output += "</li>",
+        }
+    }
+
+    fn drawer(&mut self, drawer: Drawer, visit: Visit, ctx: &mut TraversalContext) {}
+
+    fn dyn_block(&mut self, block: DynBlock, visit: Visit, ctx: &mut TraversalContext) {}
+
+    fn fn_def(&mut self, fn_def: FnDef, visit: Visit, ctx: &mut TraversalContext) {
+        if visit == Visit::Enter {
+            let (n, _) = self.html().footnote(&fn_def.label(), None);
+            let contents = fn_def
+                .syntax
+                .children_with_tokens()
+                .skip_while(|e| e.kind() != SyntaxKind::R_BRACKET)
+                .skip(1);
+            footnote_definition(self, n, contents, ctx);
+            ctx.skip();
+        }
+    }
+
+    fn comment(&mut self, comment: Comment, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<!--",
+            Visit::Leave => "-->",
+        };
+    }
+
+    fn fixed_width(&mut self, fixed_width: FixedWidth, visit: Visit, ctx: &mut TraversalContext) {}
+
+    fn special_block(&mut self, block: SpecialBlock, visit: Visit, ctx: &mut TraversalContext) {}
+
+    fn quote_block(&mut self, block: QuoteBlock, visit: Visit, ctx: &mut TraversalContext) {
+        let html = self.html();
+        match visit {
+            Visit::Enter => {
+                html.output += "<blockquote";
+                write_attributes(&mut html.output, &block.syntax, "", None);
+                html.output += ">";
             }
-            Event::Leave(Container::Link(_)) => self.output += "</a>",
+            Visit::Leave => html.output += "</blockquote>",
+        }
+    }
 
-            Event::Text(text) => {
-                let _ = write!(&mut self.output, "{}", HtmlEscape(text.text()));
+    fn center_block(&mut self, block: CenterBlock, visit: Visit, ctx: &mut TraversalContext) {
+        let html = self.html();
+        match visit {
+            Visit::Enter => {
+                html.output += "<div";
+                write_attributes(&mut html.output, &block.syntax, "center", None);
+                html.output += ">";
             }
+            Visit::Leave => html.output += "</div>",
+        }
+    }
 
-            Event::LineBreak(_) => self.output += "<br/>",
+    fn verse_block(&mut self, block: VerseBlock, visit: Visit, ctx: &mut TraversalContext) {
+        let html = self.html();
+        match visit {
+            Visit::Enter => {
+                html.output += "<p";
+                write_attributes(&mut html.output, &block.syntax, "verse", None);
+                html.output += ">";
+            }
+            Visit::Leave => html.output += "</p>",
+        }
+    }
 
-            Event::Snippet(snippet) => {
-                if snippet.backend().eq_ignore_ascii_case("html") {
-                    self.output += &snippet.value();
-                }
+    fn comment_block(&mut self, block: CommentBlock, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<!--",
+            Visit::Leave => "-->",
+        };
+    }
+
+    fn example_block(&mut self, block: ExampleBlock, visit: Visit, ctx: &mut TraversalContext) {
+        let html = self.html();
+        match visit {
+            Visit::Enter => {
+                html.output += "<pre";
+                write_attributes(&mut html.output, &block.syntax, "example", None);
+                html.output += ">";
+            }
+            Visit::Leave => html.output += "</pre>",
+        }
+    }
+
+    fn export_block(&mut self, block: ExportBlock, visit: Visit, ctx: &mut TraversalContext) {
+        if visit == Visit::Enter {
+            if block.ty().is_some_and(|ty| ty.eq_ignore_ascii_case("html")) {
+                self.html().output += &block.value();
             }
+            ctx.skip();
+        }
+    }
+
+    fn src_block(&mut self, block: SourceBlock, visit: Visit, ctx: &mut TraversalContext) {
+        let html = self.html();
+        match visit {
+            Visit::Enter => {
+                let numbered = html.numbered(&block.syntax);
+                if let (Some(numbered), Some(caption)) =
+                    (&numbered, block.caption().and_then(|c| c.value()))
+                {
+                    html.output += r#"<label class="org-src-name">"#;
+                    write_caption(&mut html.output, numbered, &caption);
+                    html.output += "</label>";
+                }
+                let name = numbered.as_ref().and_then(|n| n.name.as_deref());
+                html.output += "<pre";
+                write_attributes(&mut html.output, &block.syntax, "", name);
+                let language = block.language().unwrap_or_default();
+                let _ = write!(
+                    &mut html.output,
+                    r#"><code class="language-{}">"#,
+                    HtmlEscape(&language)
+                );
 
-            Event::Rule(_) => self.output += "<hr/>",
-
-            Event::Timestamp(timestamp) => {
-                self.output += r#"<span class="timestamp-wrapper"><span class="timestamp">"#;
-                for e in timestamp.syntax.children_with_tokens() {
-                    match e {
-                        NodeOrToken::Token(t) if t.kind() == SyntaxKind::MINUS2 => {
-                            self.output += "&#x2013;";
-                        }
-                        NodeOrToken::Token(t) => {
-                            self.output += t.text();
-                        }
-                        _ => {}
-                    }
+                let lines = block.lines();
+                if lines
+                    .iter()
+                    .any(|line| line.number.is_some() || line.label.is_some())
+                {
+                    html.source_lines(&language, &lines, block.retain_labels());
+                    html.output += "</code></pre>";
+                    ctx.skip();
+                } else if let Some(code) = html.highlight(&language, &block.value()) {
+                    html.output += &code;
+                    html.output += "</code></pre>";
+                    ctx.skip();
                 }
-                self.output += r#"</span></span>"#;
             }
+            Visit::Leave => html.output += "</code></pre>",
+        }
+    }
+
+    fn link(&mut self, link: Link, visit: Visit, ctx: &mut TraversalContext) {
+        let html = self.html();
+
+        if visit == Visit::Leave {
+            html.output += "</a>";
+            return;
+        }
+
+        let path = link.path();
+
+        if let Some(target) = html.numbering(&link.syntax).find(&path).cloned() {
+            let _ = write!(&mut html.output, r##"<a href="#{}">"##, HtmlEscape(&path));
+            if !link.has_description() {
+                let _ = write!(
+                    &mut html.output,
+                    "{} {}</a>",
+                    target.category.label(),
+                    target.number
+                );
+                ctx.skip();
+            }
+            return;
+        }
+
+        let label = path.strip_prefix('(').and_then(|p| p.strip_suffix(')'));
+        if let Some((label, text)) =
+            label.and_then(|label| Some((label, html.coderef(&link.syntax, label)?.clone())))
+        {
+            let _ = write!(
+                &mut html.output,
+                r##"<a href="#coderef-{}" class="coderef">"##,
+                HtmlEscape(&label)
+            );
+            if !link.has_description() {
+                let _ = write!(&mut html.output, "{}</a>", HtmlEscape(&text));
+                ctx.skip();
+            }
+            return;
+        }
+
+        let path = path.trim_start_matches("file:");
+
+        if link.is_image() {
+            let _ = write!(&mut html.output, r#"<img src="{}""#, HtmlEscape(&path));
+            if let Some(paragraph) = link
+                .syntax
+                .parent()
+                .and_then(Paragraph::cast)
+                .filter(|p| standalone_image(p).is_some())
+            {
+                write_attributes(&mut html.output, &paragraph.syntax, "", None);
+            }
+            html.output += ">";
+            return ctx.skip();
+        }
+
+        let _ = write!(&mut html.output, r#"<a href="{}">"#, HtmlEscape(&path));
+
+        if !link.has_description() {
+            let _ = write!(&mut html.output, "{}</a>", HtmlEscape(&path));
+            ctx.skip();
+        }
+    }
+
+    fn radio_target(&mut self, target: RadioTarget, visit: Visit, ctx: &mut TraversalContext) {}
+
+    fn fn_ref(&mut self, fn_ref: FnRef, visit: Visit, ctx: &mut TraversalContext) {
+        if visit == Visit::Enter {
+            let inline = fn_ref.is_inline().then(|| fn_ref.clone());
+            let html = self.html();
+            let (n, first) = html.footnote(&fn_ref.label(), inline);
+            let id = if first {
+                format!(r#" id="fnr.{n}""#)
+            } else {
+                String::new()
+            };
+            let _ = write!(
+                &mut html.output,
+                r##"<sup><a{id} class="footref" href="#fn.{n}" role="doc-noteref">{n}</a></sup>"##
+            );
+            ctx.skip();
+        }
+    }
+
+    fn target(&mut self, target: Target, visit: Visit, ctx: &mut TraversalContext) {}
+
+    fn bold(&mut self, bold: Bold, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<b>",
+            Visit::Leave => "</b>",
+        };
+    }
+
+    fn strike(&mut self, strike: Strike, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<s>",
+            Visit::Leave => "</s>",
+        };
+    }
+
+    fn italic(&mut self, italic: Italic, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<i>",
+            Visit::Leave => "</i>",
+        };
+    }
+
+    fn underline(&mut self, underline: Underline, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<u>",
+            Visit::Leave => "</u>",
+        };
+    }
+
+    fn verbatim(&mut self, verbatim: Verbatim, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<code>",
+            Visit::Leave => "</code>",
+        };
+    }
+
+    fn code(&mut self, code: Code, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<code>",
+            Visit::Leave => "</code>",
+        };
+    }
+
+    fn superscript(&mut self, superscript: Superscript, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<sup>",
+            Visit::Leave => "</sup>",
+        };
+    }
 
-            Event::LatexFragment(latex) => {
-                let _ = write!(&mut self.output, "{}", &latex.syntax);
+    fn subscript(&mut self, subscript: Subscript, visit: Visit, ctx: &mut TraversalContext) {
+        self.html().output += match visit {
+            Visit::Enter => "<sub>",
+            Visit::Leave => "</sub>",
+        };
+    }
+
+    fn babel_call(&mut self, call: BabelCall, visit: Visit, ctx: &mut TraversalContext) {}
+
+    fn property_drawer(
+        &mut self,
+        drawer: PropertyDrawer,
+        visit: Visit,
+        ctx: &mut TraversalContext,
+    ) {
+    }
+
+    fn affiliated_keyword(
+        &mut self,
+        keyword: AffiliatedKeyword,
+        visit: Visit,
+        ctx: &mut TraversalContext,
+    ) {
+    }
+
+    fn keyword(&mut self, keyword: Keyword, visit: Visit, ctx: &mut TraversalContext) {
+        ctx.skip();
+    }
+
+    fn text(&mut self, text: SyntaxToken, ctx: &mut TraversalContext) {
+        let _ = write!(&mut self.html().output, "{}", HtmlEscape(text.text()));
+    }
+
+    fn macros(&mut self, macros: Macros, ctx: &mut TraversalContext) {}
+
+    fn cookie(&mut self, cookie: Cookie, ctx: &mut TraversalContext) {}
+
+    fn inline_call(&mut self, call: InlineCall, ctx: &mut TraversalContext) {}
+
+    fn inline_src(&mut self, src: InlineSrc, ctx: &mut TraversalContext) {
+        let html = self.html();
+        let language = src.language();
+        let value = src.value();
+        let _ = write!(
+            &mut html.output,
+            r#"<code class="src src-{}">"#,
+            HtmlEscape(&language)
+        );
+        match html.highlight(&language, &value) {
+            Some(code) => html.output += &code,
+            None => {
+                let _ = write!(&mut html.output, "{}", HtmlEscape(&value));
             }
-            Event::LatexEnvironment(latex) => {
-                let _ = write!(&mut self.output, "{}", &latex.syntax);
+        }
+        html.output += "</code>";
+    }
+
+    fn clock(&mut self, clock: Clock, ctx: &mut TraversalContext) {}
+
+    fn line_break(&mut self, line_break: LineBreak, ctx: &mut TraversalContext) {
+        self.html().output += "<br/>";
+    }
+
+    fn snippet(&mut self, snippet: Snippet, ctx: &mut TraversalContext) {
+        if snippet.backend().eq_ignore_ascii_case("html") {
+            self.html().output += &snippet.value();
+        }
+    }
+
+    fn rule(&mut self, rule: Rule, ctx: &mut TraversalContext) {
+        self.html().output += "<hr/>";
+    }
+
+    fn timestamp(&mut self, timestamp: Timestamp, ctx: &mut TraversalContext) {
+        let html = self.html();
+        html.output += r#"<span class="timestamp-wrapper"><span class="timestamp">"#;
+        for e in timestamp.syntax.children_with_tokens() {
+            match e {
+                NodeOrToken::Token(t) if t.kind() == SyntaxKind::MINUS2 => {
+                    html.output += "&#x2013;";
+                }
+                NodeOrToken::Token(t) => {
+                    html.output += t.text();
+                }
+                _ => {}
             }
+        }
+        html.output += r#"</span></span>"#;
+    }
+
+    fn latex_fragment(&mut self, latex: LatexFragment, ctx: &mut TraversalContext) {
+        self.html().write_latex(&latex.syntax.to_string());
+    }
+
+    fn latex_environment(&mut self, latex: LatexEnvironment, ctx: &mut TraversalContext) {
+        let html = self.html();
+        let numbered = html.numbered(&latex.syntax);
+        if let Some(Numbered {
+            name: Some(name), ..
+        }) = &numbered
+        {
+            let _ = write!(
+                &mut html.output,
+                r#"<div id="{}" class="equation-container"><span class="equation">"#,
+                HtmlEscape(name)
+            );
+        }
+        let source: String = latex
+            .syntax
+            .children_with_tokens()
+            .filter_map(|elem| elem.into_token())
+            .map(|token| token.text().to_string())
+            .collect();
+        html.write_latex(&source);
+        if let Some(numbered) = numbered {
+            let _ = write!(
+                &mut html.output,
+                r#"</span><span class="equation-label">({})</span></div>"#,
+                numbered.number
+            );
+        }
+    }
+
+    fn entity(&mut self, entity: Entity, ctx: &mut TraversalContext) {
+        self.html().output += entity.html();
+    }
+}
+
+impl HtmlHandler for HtmlExport {
+    fn html(&mut self) -> &mut HtmlExport {
+        self
+    }
+}
+
+/// Adapts an [`HtmlHandler`] into a [`Traverser`], calling the handler method
+/// of each container and leaf
+///
+/// ```rust
+/// use orgize::{
+///     ast::Link,
+///     export::{HtmlExport, HtmlHandler, HtmlTraverser, TraversalContext, Visit},
+///     Org,
+/// };
+///
+/// #[derive(Default)]
+/// struct NoLinks(HtmlExport);
+///
+/// impl HtmlHandler for NoLinks {
+///     fn html(&mut self) -> &mut HtmlExport {
+///         &mut self.0
+///     }
+///
+///     fn link(&mut self, link: Link, visit: Visit, ctx: &mut TraversalContext) {}
+/// }
+///
+/// let mut html = NoLinks::default();
+/// Org::parse("[[https://example.com][example]]").traverse(&mut HtmlTraverser(&mut html));
+/// assert_eq!(html.0.finish(), "<main><section><p>example</p></section></main>");
+/// ```
+pub struct HtmlTraverser<'a, H: ?Sized>(pub &'a mut H);
+
+impl Traverser for HtmlExport {
+    fn event(&mut self, event: Event, ctx: &mut TraversalContext) {
+        HtmlTraverser(self).event(event, ctx)
+    }
+}
+
+impl<H: HtmlHandler + ?Sized> Traverser for HtmlTraverser<'_, H> {
+    fn event(&mut self, event: Event, ctx: &mut TraversalContext) {
+        let this = &mut *self.0;
+        let (container, visit) = match event {
+            Event::Enter(container) => (container, Visit::Enter),
+            Event::Leave(container) => (container, Visit::Leave),
 
-            Event::Entity(entity) => self.output += entity.html(),
+            Event::Text(text) => return this.text(text, ctx),
+            Event::Macros(macros) => return this.macros(macros, ctx),
+            Event::Cookie(cookie) => return this.cookie(cookie, ctx),
+            Event::InlineCall(call) => return this.inline_call(call, ctx),
+            Event::InlineSrc(src) => return this.inline_src(src, ctx),
+            Event::Clock(clock) => return this.clock(clock, ctx),
+            Event::LineBreak(line_break) => return this.line_break(line_break, ctx),
+            Event::Snippet(snippet) => return this.snippet(snippet, ctx),
+            Event::Rule(rule) => return this.rule(rule, ctx),
+            Event::Timestamp(timestamp) => return this.timestamp(timestamp, ctx),
+            Event::LatexFragment(latex) => return this.latex_fragment(latex, ctx),
+            Event::LatexEnvironment(latex) => return this.latex_environment(latex, ctx),
+            Event::Entity(entity) => return this.entity(entity, ctx),
+        };
 
-            _ => {}
+        match container {
+            Container::Document(n) => this.document(n, visit, ctx),
+            Container::Section(n) => this.section(n, visit, ctx),
+            Container::Paragraph(n) => this.paragraph(n, visit, ctx),
+            Container::Headline(n) => this.headline(n, visit, ctx),
+            Container::OrgTable(n) => this.table(n, visit, ctx),
+            Container::OrgTableRow(n) => this.table_row(n, visit, ctx),
+            Container::OrgTableCell(n) => this.table_cell(n, visit, ctx),
+            Container::TableEl(n) => this.table_el(n, visit, ctx),
+            Container::List(n) => this.list(n, visit, ctx),
+            Container::ListItem(n) => this.list_item(n, visit, ctx),
+            Container::Drawer(n) => this.drawer(n, visit, ctx),
+            Container::DynBlock(n) => this.dyn_block(n, visit, ctx),
+            Container::FnDef(n) => this.fn_def(n, visit, ctx),
+            Container::Comment(n) => this.comment(n, visit, ctx),
+            Container::FixedWidth(n) => this.fixed_width(n, visit, ctx),
+            Container::SpecialBlock(n) => this.special_block(n, visit, ctx),
+            Container::QuoteBlock(n) => this.quote_block(n, visit, ctx),
+            Container::CenterBlock(n) => this.center_block(n, visit, ctx),
+            Container::VerseBlock(n) => this.verse_block(n, visit, ctx),
+            Container::CommentBlock(n) => this.comment_block(n, visit, ctx),
+            Container::ExampleBlock(n) => this.example_block(n, visit, ctx),
+            Container::ExportBlock(n) => this.export_block(n, visit, ctx),
+            Container::SourceBlock(n) => this.src_block(n, visit, ctx),
+            Container::Link(n) => this.link(n, visit, ctx),
+            Container::RadioTarget(n) => this.radio_target(n, visit, ctx),
+            Container::FnRef(n) => this.fn_ref(n, visit, ctx),
+            Container::Target(n) => this.target(n, visit, ctx),
+            Container::Bold(n) => this.bold(n, visit, ctx),
+            Container::Strike(n) => this.strike(n, visit, ctx),
+            Container::Italic(n) => this.italic(n, visit, ctx),
+            Container::Underline(n) => this.underline(n, visit, ctx),
+            Container::Verbatim(n) => this.verbatim(n, visit, ctx),
+            Container::Code(n) => this.code(n, visit, ctx),
+            Container::Superscript(n) => this.superscript(n, visit, ctx),
+            Container::Subscript(n) => this.subscript(n, visit, ctx),
+            Container::BabelCall(n) => this.babel_call(n, visit, ctx),
+            Container::PropertyDrawer(n) => this.property_drawer(n, visit, ctx),
+            Container::AffiliatedKeyword(n) => this.affiliated_keyword(n, visit, ctx),
+            Container::Keyword(n) => this.keyword(n, visit, ctx),
         }
     }
 }
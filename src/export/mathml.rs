@@ -0,0 +1,588 @@
+use std::fmt::Write as _;
+
+use super::HtmlEscape;
+use crate::entities::ENTITIES;
+
+/// Converts LaTeX math into MathML
+///
+/// Accepts inline math (`$...$` and `\(...\)`), display math (`$$...$$` and
+/// `\[...\]`) and LaTeX environments (`\begin{...}...\end{...}`). Supported
+/// subset includes fractions, roots, sub/superscripts, accents, fences,
+/// symbols from [`Entity`](crate::ast::Entity) table, matrices, `cases`,
+/// `equation`, `align` and `gather`.
+///
+/// Returns `None` if input contains anything outside that subset.
+///
+/// ```rust
+/// use orgize::export::latex_to_mathml;
+///
+/// assert_eq!(
+///     latex_to_mathml(r"$\alpha^2 + \frac{1}{n}$").unwrap(),
+///     "<math><msup><mi>α</mi><mn>2</mn></msup><mo>+</mo><mfrac><mrow><mn>1</mn></mrow><mrow><mi>n</mi></mrow></mfrac></math>"
+/// );
+/// assert_eq!(
+///     latex_to_mathml(r"\[x_{i}\]").unwrap(),
+///     r#"<math display="block"><msub><mi>x</mi><mrow><mi>i</mi></mrow></msub></math>"#
+/// );
+/// assert_eq!(latex_to_mathml(r"$\unknown{x}$"), None);
+/// assert_eq!(latex_to_mathml(r"\enlargethispage{2cm}"), None);
+/// ```
+pub fn latex_to_mathml(latex: &str) -> Option<String> {
+    let latex = latex.trim();
+
+    let (display, body) = if let Some(body) = strip(latex, "$$", "$$") {
+        (true, body)
+    } else if let Some(body) = strip(latex, "$", "$") {
+        (false, body)
+    } else if let Some(body) = strip(latex, "\\(", "\\)") {
+        (false, body)
+    } else if let Some(body) = strip(latex, "\\[", "\\]") {
+        (true, body)
+    } else if latex.starts_with("\\begin") {
+        (true, latex)
+    } else {
+        return None;
+    };
+
+    let mut parser = Parser {
+        input: body,
+        pos: 0,
+    };
+    let mut output = String::new();
+    parser.row(&mut output)?;
+    if parser.peek().is_some() {
+        return None;
+    }
+
+    Some(if display {
+        format!(r#"<math display="block">{output}</math>"#)
+    } else {
+        format!("<math>{output}</math>")
+    })
+}
+
+fn strip<'a>(s: &'a str, prefix: &str, suffix: &str) -> Option<&'a str> {
+    s.strip_prefix(prefix)?.strip_suffix(suffix)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    /// `\name` or `\` followed by a non-letter, e.g. `\,`
+    Command(&'a str),
+    Char(char),
+    /// `{`
+    Open,
+    /// `}`
+    Close,
+    /// `^`
+    Sup,
+    /// `_`
+    Sub,
+    /// `&`
+    Align,
+    /// `\\`
+    Newline,
+    /// `\` at end of input
+    Error,
+}
+
+/// How sub/superscripts are attached to an atom
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Limits {
+    /// `msub`, `msup` or `msubsup`
+    Side,
+    /// `munder`, `mover` or `munderover`, e.g. `\sum` and `\lim`
+    UnderOver,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Returns next token and its end position, skipping whitespace and comments
+    fn lex(&self) -> Option<(Token<'a>, usize)> {
+        let mut pos = self.pos;
+        loop {
+            let rest = &self.input[pos..];
+            let trimmed = rest.trim_start();
+            pos += rest.len() - trimmed.len();
+            if trimmed.starts_with('%') {
+                pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                break;
+            }
+        }
+
+        let rest = &self.input[pos..];
+        let c = rest.chars().next()?;
+        let token = match c {
+            '{' => Token::Open,
+            '}' => Token::Close,
+            '^' => Token::Sup,
+            '_' => Token::Sub,
+            '&' => Token::Align,
+            '\\' => {
+                let name = &rest[1..];
+                let len = name
+                    .find(|c: char| !c.is_ascii_alphabetic())
+                    .unwrap_or(name.len());
+                if len > 0 {
+                    return Some((Token::Command(&name[..len]), pos + 1 + len));
+                }
+                let Some(c) = name.chars().next() else {
+                    return Some((Token::Error, pos + 1));
+                };
+                if c == '\\' {
+                    return Some((Token::Newline, pos + 2));
+                }
+                return Some((
+                    Token::Command(&name[..c.len_utf8()]),
+                    pos + 1 + c.len_utf8(),
+                ));
+            }
+            c => Token::Char(c),
+        };
+        Some((token, pos + c.len_utf8()))
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.lex().map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let (token, end) = self.lex()?;
+        self.pos = end;
+        Some(token)
+    }
+
+    fn expect(&mut self, token: Token) -> Option<()> {
+        (self.next()? == token).then_some(())
+    }
+
+    /// Returns raw contents of next `{...}` group
+    fn raw_group(&mut self) -> Option<&'a str> {
+        self.expect(Token::Open)?;
+        let start = self.pos;
+        let mut depth = 0;
+        for (i, c) in self.input[start..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    self.pos = start + i + 1;
+                    return Some(&self.input[start..start + i]);
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Parses a sequence of atoms, until end of input, `}`, `&`, `\\`, `\end` or `\right`
+    fn row(&mut self, output: &mut String) -> Option<()> {
+        loop {
+            match self.peek() {
+                None
+                | Some(
+                    Token::Close
+                    | Token::Align
+                    | Token::Newline
+                    | Token::Command("end")
+                    | Token::Command("right"),
+                ) => return Some(()),
+                Some(Token::Command("nonumber" | "notag")) => {
+                    self.next();
+                }
+                Some(Token::Command("label")) => {
+                    self.next();
+                    self.raw_group()?;
+                }
+                Some(_) => self.scripted(output)?,
+            }
+        }
+    }
+
+    /// Parses an atom with optional sub/superscripts
+    fn scripted(&mut self, output: &mut String) -> Option<()> {
+        let mut base = String::new();
+        let limits = self.atom(&mut base)?;
+
+        let mut sub = None;
+        let mut sup = None::<String>;
+        loop {
+            match self.peek() {
+                Some(Token::Sub) if sub.is_none() => {
+                    self.next();
+                    sub = Some(self.argument()?);
+                }
+                Some(Token::Sup) if sup.is_none() => {
+                    self.next();
+                    sup = Some(self.argument()?);
+                }
+                Some(Token::Char('\'')) if sup.is_none() => {
+                    self.next();
+                    let mut primes = "′".to_string();
+                    while self.peek() == Some(Token::Char('\'')) {
+                        self.next();
+                        primes += "′";
+                    }
+                    sup = Some(format!("<mo>{primes}</mo>"));
+                }
+                Some(Token::Sub | Token::Sup) => return None,
+                _ => break,
+            }
+        }
+
+        let (under, over, under_over) = match limits {
+            Limits::Side => ("msub", "msup", "msubsup"),
+            Limits::UnderOver => ("munder", "mover", "munderover"),
+        };
+        let _ = match (sub, sup) {
+            (None, None) => write!(output, "{base}"),
+            (Some(sub), None) => write!(output, "<{under}>{base}{sub}</{under}>"),
+            (None, Some(sup)) => write!(output, "<{over}>{base}{sup}</{over}>"),
+            (Some(sub), Some(sup)) => {
+                write!(output, "<{under_over}>{base}{sub}{sup}</{under_over}>")
+            }
+        };
+        Some(())
+    }
+
+    /// Parses argument of a command or script, i.e. a group or a single token
+    fn argument(&mut self) -> Option<String> {
+        let mut output = String::new();
+        match self.peek()? {
+            // only the first digit belongs to argument, e.g. `x^23`
+            Token::Char(c) if c.is_ascii_digit() => {
+                self.next();
+                let _ = write!(output, "<mn>{c}</mn>");
+            }
+            _ => {
+                self.atom(&mut output)?;
+            }
+        }
+        Some(output)
+    }
+
+    /// Parses a single atom, which is exported as exactly one MathML element
+    fn atom(&mut self, output: &mut String) -> Option<Limits> {
+        match self.next()? {
+            Token::Open => {
+                *output += "<mrow>";
+                self.row(output)?;
+                self.expect(Token::Close)?;
+                *output += "</mrow>";
+            }
+            Token::Char(c) if c.is_alphabetic() => {
+                let _ = write!(output, "<mi>{c}</mi>");
+            }
+            Token::Char(c) if c.is_ascii_digit() || c == '.' => {
+                let start = self.pos - 1;
+                let len = self.input[self.pos..]
+                    .find(|c: char| !c.is_ascii_digit() && c != '.')
+                    .unwrap_or(self.input.len() - self.pos);
+                self.pos += len;
+                let _ = write!(output, "<mn>{}</mn>", &self.input[start..self.pos]);
+            }
+            Token::Char(c) if "+-=<>,;:!?()[]/|*".contains(c) => {
+                let op = match c {
+                    '-' => "−".to_string(),
+                    '*' => "∗".to_string(),
+                    c => HtmlEscape(c.to_string()).to_string(),
+                };
+                let _ = write!(output, "<mo>{op}</mo>");
+            }
+            Token::Command(name) => return self.command(name, output),
+            _ => return None,
+        }
+        Some(Limits::Side)
+    }
+
+    fn command(&mut self, name: &'a str, output: &mut String) -> Option<Limits> {
+        match name {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.argument()?;
+                let denominator = self.argument()?;
+                let _ = write!(output, "<mfrac>{numerator}{denominator}</mfrac>");
+            }
+            "sqrt" => {
+                if self.peek() == Some(Token::Char('[')) {
+                    self.next();
+                    let start = self.pos;
+                    let len = self.input[start..].find(']')?;
+                    self.pos = start + len + 1;
+
+                    let mut index = String::new();
+                    let mut parser = Parser {
+                        input: &self.input[start..start + len],
+                        pos: 0,
+                    };
+                    parser.row(&mut index)?;
+                    if parser.peek().is_some() {
+                        return None;
+                    }
+                    let radicand = self.argument()?;
+                    let _ = write!(output, "<mroot>{radicand}<mrow>{index}</mrow></mroot>");
+                } else {
+                    let radicand = self.argument()?;
+                    let _ = write!(output, "<msqrt>{radicand}</msqrt>");
+                }
+            }
+            "left" => {
+                let open = self.delimiter()?;
+                let _ = write!(output, "<mrow><mo>{open}</mo>");
+                self.row(output)?;
+                self.expect(Token::Command("right"))?;
+                let close = self.delimiter()?;
+                let _ = write!(output, "<mo>{close}</mo></mrow>");
+            }
+            "text" | "textrm" | "mbox" => {
+                let text = self.raw_group()?;
+                let _ = write!(output, "<mtext>{}</mtext>", HtmlEscape(text));
+            }
+            "mathrm" | "operatorname" | "mathbf" | "mathit" | "mathbb" | "mathcal" | "mathsf"
+            | "mathtt" | "mathfrak" => {
+                let variant = match name {
+                    "mathrm" | "operatorname" => "normal",
+                    "mathbf" => "bold",
+                    "mathit" => "italic",
+                    "mathbb" => "double-struck",
+                    "mathcal" => "script",
+                    "mathsf" => "sans-serif",
+                    "mathtt" => "monospace",
+                    _ => "fraktur",
+                };
+                let text = self.raw_group()?.trim();
+                if text.is_empty() || !text.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return None;
+                }
+                let _ = write!(output, r#"<mi mathvariant="{variant}">{text}</mi>"#);
+            }
+            "hat" | "widehat" | "bar" | "overline" | "vec" | "dot" | "ddot" | "tilde"
+            | "widetilde" => {
+                let accent = match name {
+                    "hat" | "widehat" => "^",
+                    "bar" | "overline" => "¯",
+                    "vec" => "→",
+                    "dot" => "˙",
+                    "ddot" => "¨",
+                    _ => "~",
+                };
+                let base = self.argument()?;
+                let _ = write!(
+                    output,
+                    r#"<mover accent="true">{base}<mo>{accent}</mo></mover>"#
+                );
+            }
+            "begin" => {
+                let env = self.raw_group()?;
+                self.environment(env, output)?;
+                self.expect(Token::Command("end"))?;
+                if self.raw_group()? != env {
+                    return None;
+                }
+            }
+            "," | ":" | ">" | ";" | " " | "quad" | "qquad" => {
+                let width = match name {
+                    "," => "0.1667em",
+                    ":" | ">" => "0.2222em",
+                    ";" => "0.2778em",
+                    " " => "0.25em",
+                    "quad" => "1em",
+                    _ => "2em",
+                };
+                let _ = write!(output, r#"<mspace width="{width}"/>"#);
+            }
+            "{" | "}" | "|" | "%" | "$" | "#" | "_" | "&" => {
+                let op = if name == "|" { "‖" } else { name };
+                let _ = write!(output, "<mo>{}</mo>", HtmlEscape(op));
+            }
+            "sin" | "cos" | "tan" | "cot" | "sec" | "csc" | "arcsin" | "arccos" | "arctan"
+            | "sinh" | "cosh" | "tanh" | "coth" | "log" | "ln" | "lg" | "exp" | "det" | "dim"
+            | "ker" | "deg" | "gcd" | "arg" | "hom" | "Pr" => {
+                let _ = write!(output, "<mi>{name}</mi>");
+            }
+            "lim" | "liminf" | "limsup" | "max" | "min" | "sup" | "inf" => {
+                let _ = write!(output, "<mi>{name}</mi>");
+                return Some(Limits::UnderOver);
+            }
+            _ => {
+                let symbol = symbol(name)?;
+                let limits = match name {
+                    "sum" | "prod" | "coprod" | "bigcup" | "bigcap" | "bigoplus" | "bigotimes" => {
+                        Limits::UnderOver
+                    }
+                    _ => Limits::Side,
+                };
+                if symbol.chars().all(char::is_alphabetic) || "∞∂∇∅".contains(symbol) {
+                    let _ = write!(output, "<mi>{symbol}</mi>");
+                } else {
+                    let _ = write!(output, "<mo>{}</mo>", HtmlEscape(symbol));
+                }
+                return Some(limits);
+            }
+        }
+        Some(Limits::Side)
+    }
+
+    /// Parses delimiter after `\left` or `\right`
+    fn delimiter(&mut self) -> Option<&'static str> {
+        let delimiter = match self.next()? {
+            Token::Char('(') => "(",
+            Token::Char(')') => ")",
+            Token::Char('[') => "[",
+            Token::Char(']') => "]",
+            Token::Char('|') => "|",
+            Token::Char('/') => "/",
+            Token::Char('.') => "",
+            Token::Command("{") | Token::Command("lbrace") => "{",
+            Token::Command("}") | Token::Command("rbrace") => "}",
+            Token::Command("|") | Token::Command("Vert") => "‖",
+            Token::Command("langle") => "⟨",
+            Token::Command("rangle") => "⟩",
+            _ => return None,
+        };
+        Some(delimiter)
+    }
+
+    /// Parses contents of environment `env`, until `\end`
+    fn environment(&mut self, env: &str, output: &mut String) -> Option<()> {
+        let (open, close, align) = match env.trim_end_matches('*') {
+            "equation" | "displaymath" | "math" => {
+                *output += "<mrow>";
+                self.row(output)?;
+                *output += "</mrow>";
+                return Some(());
+            }
+            "align" | "aligned" | "eqnarray" | "split" => ("", "", "right left"),
+            "gather" | "gathered" => ("", "", "center"),
+            "matrix" => ("", "", "center"),
+            "pmatrix" => ("(", ")", "center"),
+            "bmatrix" => ("[", "]", "center"),
+            "Bmatrix" => ("{", "}", "center"),
+            "vmatrix" => ("|", "|", "center"),
+            "Vmatrix" => ("‖", "‖", "center"),
+            "cases" => ("{", "", "left"),
+            _ => return None,
+        };
+
+        let mut rows = vec![];
+        loop {
+            let mut cells = vec![];
+            loop {
+                let mut cell = String::new();
+                self.row(&mut cell)?;
+                cells.push(cell);
+                if self.peek()? != Token::Align {
+                    break;
+                }
+                self.next();
+            }
+            rows.push(cells);
+            match self.peek()? {
+                Token::Newline => {
+                    self.next();
+                    if self.peek()? == Token::Command("end") {
+                        break;
+                    }
+                }
+                Token::Command("end") => break,
+                _ => return None,
+            }
+        }
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let columnalign = align
+            .split(' ')
+            .cycle()
+            .take(columns)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if !open.is_empty() || !close.is_empty() {
+            *output += "<mrow>";
+        }
+        if !open.is_empty() {
+            let _ = write!(output, "<mo>{open}</mo>");
+        }
+        if align == "right left" {
+            let _ = write!(
+                output,
+                r#"<mtable displaystyle="true" columnalign="{columnalign}">"#
+            );
+        } else {
+            let _ = write!(output, r#"<mtable columnalign="{columnalign}">"#);
+        }
+        for cells in rows {
+            *output += "<mtr>";
+            for cell in cells {
+                let _ = write!(output, "<mtd>{cell}</mtd>");
+            }
+            *output += "</mtr>";
+        }
+        *output += "</mtable>";
+        if !close.is_empty() {
+            let _ = write!(output, "<mo>{close}</mo>");
+        }
+        if !open.is_empty() || !close.is_empty() {
+            *output += "</mrow>";
+        }
+
+        Some(())
+    }
+}
+
+/// Returns unicode of symbol command, e.g. `α` for `\alpha`
+fn symbol(name: &str) -> Option<&'static str> {
+    match name {
+        "ldots" | "dots" => return Some("…"),
+        "vdots" => return Some("⋮"),
+        "ddots" => return Some("⋱"),
+        _ => {}
+    }
+
+    ENTITIES
+        .iter()
+        .find(|e| e.0 == name)
+        .or_else(|| {
+            ENTITIES.iter().find(|e| {
+                e.1.strip_prefix('\\')
+                    .map(|latex| latex.trim_end_matches("{}"))
+                    == Some(name)
+            })
+        })
+        .map(|e| e.6)
+}
+
+#[test]
+fn convert() {
+    let t = |s: &str| latex_to_mathml(s).unwrap();
+
+    insta::assert_snapshot!(t(r"$a_1^2 + b' = \sqrt{c} \cdot \sqrt[3]{d}$"), @r###"<math><msubsup><mi>a</mi><mn>1</mn><mn>2</mn></msubsup><mo>+</mo><msup><mi>b</mi><mo>′</mo></msup><mo>=</mo><msqrt><mrow><mi>c</mi></mrow></msqrt><mo>⋅</mo><mroot><mrow><mi>d</mi></mrow><mrow><mn>3</mn></mrow></mroot></math>"###);
+    insta::assert_snapshot!(t(r"$$\sum_{i=0}^{n} \Gamma(i) \leq \infty$$"), @r###"<math display="block"><munderover><mo>∑</mo><mrow><mi>i</mi><mo>=</mo><mn>0</mn></mrow><mrow><mi>n</mi></mrow></munderover><mi>Γ</mi><mo>(</mo><mi>i</mi><mo>)</mo><mo>≤</mo><mi>∞</mi></math>"###);
+    insta::assert_snapshot!(t(r"\(\left( \frac{x}{2} \right) \text{if } \mathbb{R}\)"), @r###"<math><mrow><mo>(</mo><mfrac><mrow><mi>x</mi></mrow><mrow><mn>2</mn></mrow></mfrac><mo>)</mo></mrow><mtext>if </mtext><mi mathvariant="double-struck">R</mi></math>"###);
+    insta::assert_snapshot!(t(r"$\lim_{x \to 0} \sin x = 0.5$"), @r###"<math><munder><mi>lim</mi><mrow><mi>x</mi><mo>→</mo><mn>0</mn></mrow></munder><mi>sin</mi><mi>x</mi><mo>=</mo><mn>0.5</mn></math>"###);
+    insta::assert_snapshot!(t(r"$A = \begin{pmatrix} 1 & 0 \\ 0 & 1 \end{pmatrix}$"), @r###"<math><mi>A</mi><mo>=</mo><mrow><mo>(</mo><mtable columnalign="center center"><mtr><mtd><mn>1</mn></mtd><mtd><mn>0</mn></mtd></mtr><mtr><mtd><mn>0</mn></mtd><mtd><mn>1</mn></mtd></mtr></mtable><mo>)</mo></mrow></math>"###);
+    insta::assert_snapshot!(t(r"\begin{align*}
+a &= b + c \\
+  &= d \label{eq:1}
+\end{align*}"), @r###"<math display="block"><mtable displaystyle="true" columnalign="right left"><mtr><mtd><mi>a</mi></mtd><mtd><mo>=</mo><mi>b</mi><mo>+</mo><mi>c</mi></mtd></mtr><mtr><mtd></mtd><mtd><mo>=</mo><mi>d</mi></mtd></mtr></mtable></math>"###);
+    insta::assert_snapshot!(t(r"\begin{equation}
+\vec{v} = x^23 % comment
+\end{equation}"), @r###"<math display="block"><mrow><mover accent="true"><mrow><mi>v</mi></mrow><mo>→</mo></mover><mo>=</mo><msup><mi>x</mi><mn>2</mn></msup><mn>3</mn></mrow></math>"###);
+
+    // unsupported input
+    assert_eq!(latex_to_mathml(r"\enlargethispage{2cm}"), None);
+    assert_eq!(latex_to_mathml(r"$\foo$"), None);
+    assert_eq!(latex_to_mathml(r"$x^2^3$"), None);
+    assert_eq!(latex_to_mathml(r"$a & b$"), None);
+    assert_eq!(latex_to_mathml(r"$\frac{1}$"), None);
+    assert_eq!(latex_to_mathml(r"$\$"), None);
+    assert_eq!(
+        latex_to_mathml(r"\begin{tikzpicture}\end{tikzpicture}"),
+        None
+    );
+    assert_eq!(latex_to_mathml(r"\begin{align}a\end{gather}"), None);
+}
@@ -1,9 +1,23 @@
 //! Export `Org` struct to various formats.
 
+mod document;
 mod event;
+mod filter;
+mod highlight;
 mod html;
+mod mathml;
+mod numbering;
 mod traverse;
 
-pub use event::{Container, Event};
-pub use html::{HtmlEscape, HtmlExport};
-pub use traverse::{from_fn, from_fn_with_ctx, FromFn, FromFnWithCtx, TraversalContext, Traverser};
+pub use document::{HtmlDocument, DEFAULT_HTML_TEMPLATE};
+pub use event::{Container, Event, Visit};
+pub use filter::{ExportFilters, FilterAction};
+pub use highlight::Highlighter;
+#[cfg(feature = "syntect")]
+pub use highlight::SyntectHighlighter;
+pub use html::{HtmlEscape, HtmlExport, HtmlHandler, HtmlTraverser};
+pub use mathml::latex_to_mathml;
+pub use numbering::{Category, Numbered, Numbering};
+pub use traverse::{
+    from_fn, from_fn_with_ctx, Events, FromFn, FromFnWithCtx, TraversalContext, Traverser,
+};
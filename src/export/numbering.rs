@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use rowan::{ast::AstNode, NodeOrToken, TextRange};
+
+use crate::ast::{LatexEnvironment, Link, OrgTable, Paragraph, SourceBlock};
+use crate::{SyntaxKind, SyntaxNode};
+
+/// Category of numbered elements, each category is numbered separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// Tables
+    Table,
+    /// Paragraphs containing only an image link
+    Figure,
+    /// Source blocks
+    Listing,
+    /// LaTeX environments
+    Equation,
+}
+
+impl Category {
+    /// Returns label used in captions and references, e.g. `Table`
+    pub fn label(self) -> &'static str {
+        match self {
+            Category::Table => "Table",
+            Category::Figure => "Figure",
+            Category::Listing => "Listing",
+            Category::Equation => "Equation",
+        }
+    }
+}
+
+/// A numbered element
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Numbered {
+    pub category: Category,
+    /// Number of element in its category, starting from 1
+    pub number: usize,
+    /// Name of element, from `#+NAME` or `\label{}` of LaTeX environments
+    pub name: Option<String>,
+    pub node: SyntaxNode,
+}
+
+/// Numbers of tables, figures, listings and equations in a document
+///
+/// Tables, standalone images and source blocks are numbered if they have a
+/// `#+CAPTION` or `#+NAME`, LaTeX environments are numbered if they have a
+/// `#+NAME` or `\label{}`. Numbering follows document order.
+///
+/// ```rust
+/// use orgize::{export::Category, Org};
+///
+/// let org = Org::parse(r#"#+CAPTION: Prices
+/// | pen | 3 |
+///
+/// | not numbered |
+///
+/// #+NAME: hello
+/// #+begin_src c
+/// puts("hello");
+/// #+end_src
+///
+/// #+NAME: sizes
+/// | small | large |
+/// "#);
+///
+/// let numbering = org.numbering();
+/// let sizes = numbering.find("sizes").unwrap();
+/// assert_eq!((sizes.category, sizes.number), (Category::Table, 2));
+/// let hello = numbering.find("hello").unwrap();
+/// assert_eq!((hello.category, hello.number), (Category::Listing, 1));
+/// assert_eq!(numbering.iter().count(), 3);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Numbering {
+    elements: Vec<Numbered>,
+    /// Index of elements by their text range
+    ranges: HashMap<TextRange, usize>,
+}
+
+impl Numbering {
+    /// Numbers elements under `root`
+    pub fn new(root: &SyntaxNode) -> Self {
+        let mut elements: Vec<Numbered> = vec![];
+        let mut ranges = HashMap::new();
+
+        for node in root.descendants() {
+            let Some((category, name)) = numbered(&node) else {
+                continue;
+            };
+            let number = elements.iter().filter(|e| e.category == category).count() + 1;
+            ranges.insert(node.text_range(), elements.len());
+            elements.push(Numbered {
+                category,
+                number,
+                name,
+                node,
+            });
+        }
+
+        Numbering { elements, ranges }
+    }
+
+    /// Returns number of given element
+    pub fn get(&self, node: &SyntaxNode) -> Option<&Numbered> {
+        self.ranges
+            .get(&node.text_range())
+            .map(|&idx| &self.elements[idx])
+            .filter(|e| e.node == *node)
+    }
+
+    /// Returns numbered element with given name
+    pub fn find(&self, name: &str) -> Option<&Numbered> {
+        self.elements
+            .iter()
+            .find(|e| e.name.as_deref() == Some(name))
+    }
+
+    /// Returns an iterator over numbered elements in document order
+    pub fn iter(&self) -> impl Iterator<Item = &Numbered> {
+        self.elements.iter()
+    }
+}
+
+/// Returns category and name of node, if it should be numbered
+fn numbered(node: &SyntaxNode) -> Option<(Category, Option<String>)> {
+    let (category, caption, name) = match node.kind() {
+        SyntaxKind::ORG_TABLE => {
+            let table = OrgTable::cast(node.clone())?;
+            (Category::Table, table.caption(), table.name())
+        }
+        SyntaxKind::PARAGRAPH => {
+            let paragraph = Paragraph::cast(node.clone())?;
+            standalone_image(&paragraph)?;
+            (Category::Figure, paragraph.caption(), paragraph.name())
+        }
+        SyntaxKind::SOURCE_BLOCK => {
+            let block = SourceBlock::cast(node.clone())?;
+            (Category::Listing, block.caption(), block.name())
+        }
+        SyntaxKind::LATEX_ENVIRONMENT => {
+            let latex = LatexEnvironment::cast(node.clone())?;
+            let name = latex
+                .name()
+                .and_then(|k| k.value())
+                .map(|v| v.trim().to_string())
+                .or_else(|| {
+                    // skips affiliated keywords, e.g. a `\label{}` in caption
+                    let environment: String = node
+                        .children_with_tokens()
+                        .filter_map(NodeOrToken::into_token)
+                        .map(|t| t.text().to_string())
+                        .collect();
+                    latex_label(&environment)
+                });
+            return name.map(|name| (Category::Equation, Some(name)));
+        }
+        _ => return None,
+    };
+
+    let name = name.and_then(|k| k.value()).map(|v| v.trim().to_string());
+    (caption.is_some() || name.is_some()).then_some((category, name))
+}
+
+/// Returns argument of the first `\label{}` command
+fn latex_label(s: &str) -> Option<String> {
+    let (_, rest) = s.split_once("\\label{")?;
+    let (label, _) = rest.split_once('}')?;
+    Some(label.trim().to_string())
+}
+
+/// Returns the image link if paragraph contains nothing else
+pub(crate) fn standalone_image(paragraph: &Paragraph) -> Option<Link> {
+    let mut link = None;
+    for elem in paragraph.syntax.children_with_tokens() {
+        match elem {
+            NodeOrToken::Node(n) if n.kind() == SyntaxKind::AFFILIATED_KEYWORD => {}
+            NodeOrToken::Node(n) if link.is_none() => link = Some(Link::cast(n)?),
+            NodeOrToken::Token(t) if t.text().trim().is_empty() => {}
+            _ => return None,
+        }
+    }
+    link.filter(|link| link.is_image())
+}
+
+#[test]
+fn numbering() {
+    use crate::Org;
+
+    let org = Org::parse(
+        "#+CAPTION: see \\label{caption}\n\\begin{equation}\nx\n\\end{equation}\n\n\\begin{equation}\n\\label{y}\ny\n\\end{equation}\n",
+    );
+    let numbering = org.numbering();
+    assert!(numbering.find("caption").is_none());
+    assert_eq!(numbering.find("y").map(|n| n.number), Some(1));
+
+    let root = org.document().syntax;
+    let numbered: Vec<_> = root
+        .descendants()
+        .filter_map(|node| numbering.get(&node))
+        .map(|n| n.name.as_deref())
+        .collect();
+    assert_eq!(numbered, [Some("y")]);
+}
@@ -1,5 +1,7 @@
+use std::iter::FusedIterator;
+
 use crate::ast::*;
-use crate::syntax::{SyntaxElement, SyntaxKind};
+use crate::syntax::{SyntaxElement, SyntaxElementChildren, SyntaxKind, SyntaxNode};
 use rowan::ast::AstNode;
 use SyntaxKind::*;
 
@@ -127,93 +129,29 @@ pub trait Traverser {
         }
 
         match element {
-            SyntaxElement::Node(node) => {
-                macro_rules! walk {
-                    ($ast:ident) => {{
-                        debug_assert!($ast::can_cast(node.kind()));
-                        let node = $ast { syntax: node };
-                        self.event(Event::Enter(Container::$ast(node.clone())), ctx);
-                        take_control!();
-                        for child in node.syntax.children_with_tokens() {
-                            self.element(child, ctx);
-                            take_control!();
-                        }
-                        self.event(Event::Leave(Container::$ast(node.clone())), ctx);
-                        take_control!();
-                    }};
-                    (@$ast:ident) => {{
-                        debug_assert!($ast::can_cast(node.kind()));
-                        let node = $ast { syntax: node };
-                        self.event(Event::$ast(node), ctx);
+            SyntaxElement::Node(node) => match walk(node) {
+                Walk::Container(container, node) => {
+                    self.event(Event::Enter(container.clone()), ctx);
+                    take_control!();
+                    for child in node.children_with_tokens() {
+                        self.element(child, ctx);
                         take_control!();
-                    }};
+                    }
+                    self.event(Event::Leave(container), ctx);
+                    take_control!();
                 }
-
-                match node.kind() {
-                    DOCUMENT => walk!(Document),
-                    HEADLINE => walk!(Headline),
-                    SECTION => walk!(Section),
-                    PARAGRAPH => walk!(Paragraph),
-                    BOLD => walk!(Bold),
-                    ITALIC => walk!(Italic),
-                    STRIKE => walk!(Strike),
-                    UNDERLINE => walk!(Underline),
-                    LIST => walk!(List),
-                    LIST_ITEM => walk!(ListItem),
-                    CODE => walk!(Code),
-                    INLINE_CALL => walk!(@InlineCall),
-                    INLINE_SRC => walk!(@InlineSrc),
-                    RULE => walk!(@Rule),
-                    VERBATIM => walk!(Verbatim),
-                    SPECIAL_BLOCK => walk!(SpecialBlock),
-                    QUOTE_BLOCK => walk!(QuoteBlock),
-                    CENTER_BLOCK => walk!(CenterBlock),
-                    VERSE_BLOCK => walk!(VerseBlock),
-                    COMMENT_BLOCK => walk!(CommentBlock),
-                    EXAMPLE_BLOCK => walk!(ExampleBlock),
-                    EXPORT_BLOCK => walk!(ExportBlock),
-                    SOURCE_BLOCK => walk!(SourceBlock),
-                    BABEL_CALL => walk!(BabelCall),
-                    CLOCK => walk!(@Clock),
-                    COOKIE => walk!(@Cookie),
-                    RADIO_TARGET => walk!(RadioTarget),
-                    DRAWER => walk!(Drawer),
-                    DYN_BLOCK => walk!(DynBlock),
-                    FN_DEF => walk!(FnDef),
-                    FN_REF => walk!(FnRef),
-                    MACROS => walk!(@Macros),
-                    SNIPPET => walk!(@Snippet),
-                    TIMESTAMP_ACTIVE | TIMESTAMP_INACTIVE | TIMESTAMP_DIARY => walk!(@Timestamp),
-                    TARGET => walk!(Target),
-                    COMMENT => walk!(Comment),
-                    FIXED_WIDTH => walk!(FixedWidth),
-                    ORG_TABLE => walk!(OrgTable),
-                    ORG_TABLE_RULE_ROW | ORG_TABLE_STANDARD_ROW => walk!(OrgTableRow),
-                    ORG_TABLE_CELL => walk!(OrgTableCell),
-                    LINK => walk!(Link),
-                    LATEX_FRAGMENT => walk!(@LatexFragment),
-                    LATEX_ENVIRONMENT => walk!(@LatexEnvironment),
-                    ENTITY => walk!(@Entity),
-                    LINE_BREAK => walk!(@LineBreak),
-                    SUPERSCRIPT => walk!(Superscript),
-                    SUBSCRIPT => walk!(Subscript),
-                    KEYWORD => walk!(Keyword),
-                    PROPERTY_DRAWER => walk!(PropertyDrawer),
-                    BLOCK_CONTENT | LIST_ITEM_CONTENT => {
-                        for child in node.children_with_tokens() {
-                            self.element(child, ctx);
-                            take_control!();
-                        }
+                Walk::Leaf(event) => {
+                    self.event(event, ctx);
+                    take_control!();
+                }
+                Walk::Transparent(node) => {
+                    for child in node.children_with_tokens() {
+                        self.element(child, ctx);
+                        take_control!();
                     }
-                    NODE_PROPERTY | AFFILIATED_KEYWORD => {}
-
-                    kind => debug_assert!(
-                        !kind.is_element() && !kind.is_object(),
-                        "{:?} is not handled",
-                        kind
-                    ),
                 }
-            }
+                Walk::Ignored => {}
+            },
             SyntaxElement::Token(token) => {
                 if token.kind() == TEXT {
                     self.event(Event::Text(token), ctx);
@@ -224,6 +162,216 @@ pub trait Traverser {
     }
 }
 
+/// How a node is visited during traversal
+enum Walk {
+    /// Emits `Enter` and `Leave` events around its children
+    Container(Container, SyntaxNode),
+    /// Emits a single event
+    Leaf(Event),
+    /// Visits its children only
+    Transparent(SyntaxNode),
+    /// Emits nothing
+    Ignored,
+}
+
+fn walk(node: SyntaxNode) -> Walk {
+    macro_rules! walk {
+        ($ast:ident) => {{
+            debug_assert!($ast::can_cast(node.kind()));
+            Walk::Container(
+                Container::$ast($ast {
+                    syntax: node.clone(),
+                }),
+                node,
+            )
+        }};
+        (@$ast:ident) => {{
+            debug_assert!($ast::can_cast(node.kind()));
+            Walk::Leaf(Event::$ast($ast { syntax: node }))
+        }};
+    }
+
+    match node.kind() {
+        DOCUMENT => walk!(Document),
+        HEADLINE => walk!(Headline),
+        SECTION => walk!(Section),
+        PARAGRAPH => walk!(Paragraph),
+        BOLD => walk!(Bold),
+        ITALIC => walk!(Italic),
+        STRIKE => walk!(Strike),
+        UNDERLINE => walk!(Underline),
+        LIST => walk!(List),
+        LIST_ITEM => walk!(ListItem),
+        CODE => walk!(Code),
+        INLINE_CALL => walk!(@InlineCall),
+        INLINE_SRC => walk!(@InlineSrc),
+        RULE => walk!(@Rule),
+        VERBATIM => walk!(Verbatim),
+        SPECIAL_BLOCK => walk!(SpecialBlock),
+        QUOTE_BLOCK => walk!(QuoteBlock),
+        CENTER_BLOCK => walk!(CenterBlock),
+        VERSE_BLOCK => walk!(VerseBlock),
+        COMMENT_BLOCK => walk!(CommentBlock),
+        EXAMPLE_BLOCK => walk!(ExampleBlock),
+        EXPORT_BLOCK => walk!(ExportBlock),
+        SOURCE_BLOCK => walk!(SourceBlock),
+        BABEL_CALL => walk!(BabelCall),
+        CLOCK => walk!(@Clock),
+        COOKIE => walk!(@Cookie),
+        RADIO_TARGET => walk!(RadioTarget),
+        DRAWER => walk!(Drawer),
+        DYN_BLOCK => walk!(DynBlock),
+        FN_DEF => walk!(FnDef),
+        FN_REF => walk!(FnRef),
+        MACROS => walk!(@Macros),
+        SNIPPET => walk!(@Snippet),
+        TIMESTAMP_ACTIVE | TIMESTAMP_INACTIVE | TIMESTAMP_DIARY => walk!(@Timestamp),
+        TARGET => walk!(Target),
+        COMMENT => walk!(Comment),
+        FIXED_WIDTH => walk!(FixedWidth),
+        ORG_TABLE => walk!(OrgTable),
+        ORG_TABLE_RULE_ROW | ORG_TABLE_STANDARD_ROW => walk!(OrgTableRow),
+        ORG_TABLE_CELL => walk!(OrgTableCell),
+        LINK => walk!(Link),
+        LATEX_FRAGMENT => walk!(@LatexFragment),
+        LATEX_ENVIRONMENT => walk!(@LatexEnvironment),
+        ENTITY => walk!(@Entity),
+        LINE_BREAK => walk!(@LineBreak),
+        SUPERSCRIPT => walk!(Superscript),
+        SUBSCRIPT => walk!(Subscript),
+        KEYWORD => walk!(Keyword),
+        PROPERTY_DRAWER => walk!(PropertyDrawer),
+        BLOCK_CONTENT | LIST_ITEM_CONTENT => Walk::Transparent(node),
+        NODE_PROPERTY | AFFILIATED_KEYWORD | PLANNING => Walk::Ignored,
+
+        kind => {
+            debug_assert!(
+                !kind.is_element() && !kind.is_object(),
+                "{:?} is not handled",
+                kind
+            );
+            Walk::Ignored
+        }
+    }
+}
+
+/// An iterator over traversal events, returned by [`Org::events`](crate::Org::events)
+///
+/// It yields the same events as [`Traverser`] in the same order, so exporters
+/// can be written as ordinary loops or iterator combinators. Call
+/// [`Events::skip_subtree`] right after an `Event::Enter` to skip the
+/// descendants of that container, along with its `Event::Leave`.
+///
+/// ```rust
+/// use orgize::{
+///     export::{Container, Event},
+///     Org,
+/// };
+///
+/// let org = Org::parse("* a\n*bold* text\n* b\n** c");
+///
+/// let mut titles = vec![];
+/// let mut events = org.events();
+/// while let Some(event) = events.next() {
+///     match event {
+///         Event::Enter(Container::Headline(headline)) => titles.push(headline.title_raw()),
+///         Event::Enter(Container::Section(_)) => events.skip_subtree(),
+///         Event::Text(text) => panic!("unexpected text {text}"),
+///         _ => {}
+///     }
+/// }
+/// assert_eq!(titles, ["a", "b", "c"]);
+///
+/// let texts: Vec<_> = org
+///     .events()
+///     .filter_map(|event| match event {
+///         Event::Text(text) => Some(text.to_string()),
+///         _ => None,
+///     })
+///     .collect();
+/// assert_eq!(texts, ["bold", " text\n"]);
+/// ```
+pub struct Events {
+    stack: Vec<(Option<Container>, SyntaxElementChildren)>,
+    pending: Option<Event>,
+    entered: bool,
+}
+
+impl Events {
+    pub(crate) fn new(node: SyntaxNode) -> Self {
+        let mut events = Events {
+            stack: vec![],
+            pending: None,
+            entered: false,
+        };
+        match walk(node) {
+            Walk::Container(container, node) => {
+                events.pending = Some(Event::Enter(container.clone()));
+                events
+                    .stack
+                    .push((Some(container), node.children_with_tokens()));
+            }
+            Walk::Leaf(event) => events.pending = Some(event),
+            Walk::Transparent(node) => events.stack.push((None, node.children_with_tokens())),
+            Walk::Ignored => {}
+        }
+        events
+    }
+
+    /// Skips the descendants of the container entered by the last `Event::Enter`
+    ///
+    /// Its `Event::Leave` is skipped as well. Does nothing if the last event
+    /// is not an `Event::Enter`.
+    pub fn skip_subtree(&mut self) {
+        if self.entered {
+            self.entered = false;
+            self.stack.pop();
+        }
+    }
+}
+
+impl Iterator for Events {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        self.entered = false;
+
+        if let Some(event) = self.pending.take() {
+            self.entered = matches!(event, Event::Enter(_));
+            return Some(event);
+        }
+
+        loop {
+            let (_, children) = self.stack.last_mut()?;
+            match children.next() {
+                Some(SyntaxElement::Node(node)) => match walk(node) {
+                    Walk::Container(container, node) => {
+                        self.stack
+                            .push((Some(container.clone()), node.children_with_tokens()));
+                        self.entered = true;
+                        return Some(Event::Enter(container));
+                    }
+                    Walk::Leaf(event) => return Some(event),
+                    Walk::Transparent(node) => self.stack.push((None, node.children_with_tokens())),
+                    Walk::Ignored => {}
+                },
+                Some(SyntaxElement::Token(token)) => {
+                    if token.kind() == TEXT {
+                        return Some(Event::Text(token));
+                    }
+                }
+                None => {
+                    if let Some((Some(container), _)) = self.stack.pop() {
+                        return Some(Event::Leave(container));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl FusedIterator for Events {}
+
 pub struct FromFn<F: FnMut(Event)>(F);
 
 impl<F: FnMut(Event)> Traverser for FromFn<F> {
@@ -284,3 +432,70 @@ pub fn from_fn<F: FnMut(Event)>(f: F) -> FromFn<F> {
 pub fn from_fn_with_ctx<F: FnMut(Event, &mut TraversalContext)>(f: F) -> FromFnWithCtx<F> {
     FromFnWithCtx(f)
 }
+
+#[test]
+fn events() {
+    use crate::Org;
+
+    let org = Org::parse(
+        r#"#+TITLE: events
+* TODO heading :tag:
+SCHEDULED: <2023-01-01 Sun>
+:PROPERTIES:
+:ID: 1
+:END:
+- item *bold* [[https://example.com][link]]
+  #+begin_src rust
+  fn main() {}
+  #+end_src
+| a | b |
+|---+---|
+"#,
+    );
+
+    let mut expected = vec![];
+    org.traverse(&mut from_fn(|event| expected.push(event)));
+    assert_eq!(org.events().collect::<Vec<_>>(), expected);
+
+    // skipping headlines leaves only the zeroth section
+    let name = |container: &Container| {
+        format!("{container:?}")
+            .split('(')
+            .next()
+            .unwrap()
+            .to_string()
+    };
+    let mut events = org.events();
+    let mut kinds = vec![];
+    while let Some(event) = events.next() {
+        match event {
+            Event::Enter(Container::Headline(_)) => events.skip_subtree(),
+            Event::Enter(container) => kinds.push(format!("enter {}", name(&container))),
+            Event::Leave(container) => kinds.push(format!("leave {}", name(&container))),
+            Event::Text(text) => kinds.push(format!("text {:?}", text.text())),
+            _ => kinds.push("leaf".into()),
+        }
+    }
+    insta::assert_debug_snapshot!(kinds, @r###"
+    [
+        "enter Document",
+        "enter Section",
+        "enter Keyword",
+        "text \"TITLE\"",
+        "text \" events\"",
+        "leave Keyword",
+        "leave Section",
+        "leave Document",
+    ]
+    "###);
+
+    // skipping the root yields nothing else
+    let mut events = org.events();
+    assert!(matches!(
+        events.next(),
+        Some(Event::Enter(Container::Document(_)))
+    ));
+    events.skip_subtree();
+    assert!(events.next().is_none());
+    assert!(events.next().is_none());
+}
@@ -0,0 +1,732 @@
+//! Org-mode formatter
+//!
+//! Rewrites a parsed document into a normalized form:
+//!
+//! - aligns table columns,
+//! - aligns headline tags to [`FormatConfig::tags_column`],
+//! - normalizes the indentation of list items and their contents,
+//! - normalizes blank lines before headlines,
+//! - re-indents planning lines and property drawers, and aligns property values,
+//! - removes trailing whitespace, except in the contents of verbatim blocks.
+//!
+//! The formatted output is always reparsed and compared with the original
+//! tree. If the two trees are not equivalent, the original text is returned
+//! unchanged.
+//!
+//! ```rust
+//! use orgize::Org;
+//!
+//! let org = Org::parse("* TODO title :tag:  \n|a|bb|\n|-\n|ccc|\n");
+//! assert_eq!(
+//!     org.format(),
+//!     r#"* TODO title                                                            :tag:
+//! | a   | bb |
+//! |-----+----|
+//! | ccc |    |
+//! "#
+//! );
+//! ```
+
+use rowan::NodeOrToken;
+
+use crate::{Org, SyntaxElement, SyntaxKind, SyntaxKind::*, SyntaxNode};
+
+/// Format configuration
+#[derive(Clone, Debug)]
+pub struct FormatConfig {
+    /// Column to align headline tags to
+    ///
+    /// A negative value right-aligns tags so that they end at this column, a
+    /// positive value left-aligns them so that they start at this column,
+    /// equivalent to [`org-tags-column`](https://orgmode.org/manual/Setting-Tags.html).
+    /// Tags are always separated from the title by at least one space.
+    pub tags_column: isize,
+
+    /// Number of blank lines before each headline, except the first line of document
+    pub headline_blank_lines: usize,
+
+    /// Indents planning lines and property drawers to the content of headline,
+    /// equivalent to `org-adapt-indentation`
+    pub adapt_indentation: bool,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig {
+            tags_column: -77,
+            headline_blank_lines: 1,
+            adapt_indentation: false,
+        }
+    }
+}
+
+impl FormatConfig {
+    /// Returns formatted org-mode string of given document
+    pub fn format(&self, org: &Org) -> String {
+        let root = org.document().syntax;
+        let output = self.format_unchecked(&root);
+
+        let reparsed = org.config.clone().parse(&output);
+        if signature(&root) == signature(&reparsed.document().syntax) {
+            output
+        } else {
+            root.to_string()
+        }
+    }
+
+    fn format_unchecked(&self, root: &SyntaxNode) -> String {
+        let mut f = Formatter {
+            config: self,
+            output: String::with_capacity(root.text().len().into()),
+            protect: 0,
+            at_line_start: true,
+            indent: None,
+        };
+        f.node(root);
+        f.finish();
+        f.output
+    }
+}
+
+struct Formatter<'a> {
+    config: &'a FormatConfig,
+    output: String,
+    /// bytes before this offset must be kept as-is
+    protect: usize,
+    at_line_start: bool,
+    /// column of current list item content, `None` preserves original indentation
+    indent: Option<usize>,
+}
+
+impl Formatter<'_> {
+    /// Appends text, removing trailing whitespace and re-indenting lines if needed
+    fn push_text(&mut self, s: &str) {
+        for c in s.chars() {
+            match c {
+                '\n' => {
+                    let cr = self.output.len() > self.protect && self.output.ends_with('\r');
+                    if cr {
+                        self.output.pop();
+                    }
+                    self.trim_end(&[' ', '\t']);
+                    self.output.push_str(if cr { "\r\n" } else { "\n" });
+                    self.at_line_start = true;
+                }
+                ' ' | '\t' | '\r' if self.at_line_start && self.indent.is_some() => {}
+                _ => {
+                    if self.at_line_start {
+                        if let Some(indent) = self.indent {
+                            self.output.extend(std::iter::repeat_n(' ', indent));
+                        }
+                        self.at_line_start = false;
+                    }
+                    self.output.push(c);
+                }
+            }
+        }
+    }
+
+    /// Appends text as-is
+    fn push_verbatim(&mut self, s: &str) {
+        if s.is_empty() {
+            return;
+        }
+        self.output.push_str(s);
+        self.protect = self.output.len();
+        self.at_line_start = s.ends_with('\n');
+    }
+
+    fn trim_end(&mut self, chars: &[char]) {
+        let len = self.output[self.protect..].trim_end_matches(chars).len();
+        self.output.truncate(self.protect + len);
+    }
+
+    fn finish(&mut self) {
+        self.trim_end(&[' ', '\t', '\r', '\n']);
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+    }
+
+    fn element(&mut self, element: SyntaxElement) {
+        match element {
+            NodeOrToken::Node(node) => self.node(&node),
+            NodeOrToken::Token(token) => self.push_text(token.text()),
+        }
+    }
+
+    fn node(&mut self, node: &SyntaxNode) {
+        match node.kind() {
+            HEADLINE => self.headline(node),
+            ORG_TABLE => self.table(node),
+            LIST => self.list(node),
+            BLOCK_CONTENT
+                if node
+                    .parent()
+                    .map(|p| !p.kind().is_greater_element())
+                    .unwrap_or_default() =>
+            {
+                self.push_verbatim(&node.to_string())
+            }
+            _ => {
+                for child in node.children_with_tokens() {
+                    self.element(child);
+                }
+            }
+        }
+    }
+
+    fn headline(&mut self, node: &SyntaxNode) {
+        if !self.output.trim().is_empty() {
+            self.trim_end(&[' ', '\t', '\r', '\n']);
+            self.output.push('\n');
+            for _ in 0..self.config.headline_blank_lines {
+                self.output.push('\n');
+            }
+        }
+        self.indent = None;
+        self.at_line_start = false;
+
+        let mut children = node.children_with_tokens();
+        let mut level = 0;
+        let mut line = String::new();
+        let mut tags = None;
+        let mut newline = String::new();
+
+        for child in children.by_ref() {
+            match child.kind() {
+                HEADLINE_STARS => {
+                    let stars = child.to_string();
+                    level = stars.len();
+                    line.push_str(&stars);
+                }
+                HEADLINE_KEYWORD_TODO
+                | HEADLINE_KEYWORD_DONE
+                | HEADLINE_PRIORITY
+                | HEADLINE_TITLE => {
+                    let text = child.to_string();
+                    let text = text.trim_end();
+                    if !text.is_empty() {
+                        line.push(' ');
+                        line.push_str(text);
+                    }
+                }
+                HEADLINE_TAGS => tags = Some(child.to_string()),
+                NEW_LINE => {
+                    newline = child.to_string();
+                    break;
+                }
+                _ => {}
+            }
+        }
+        self.push_headline_line(line, tags);
+        self.push_text(&newline);
+
+        for child in children {
+            match child {
+                NodeOrToken::Node(n) if n.kind() == PLANNING => {
+                    let indent = self.headline_indent(level);
+                    self.push_text(&format!("{indent}{}", n.to_string().trim_start()));
+                }
+                NodeOrToken::Node(n) if n.kind() == PROPERTY_DRAWER => {
+                    self.property_drawer(&n, level)
+                }
+                _ => self.element(child),
+            }
+        }
+    }
+
+    fn push_headline_line(&mut self, mut line: String, tags: Option<String>) {
+        if let Some(tags) = tags {
+            let width = line.chars().count();
+            let tags_width = tags.chars().count();
+            let column = self.config.tags_column;
+            let padding = if column < 0 {
+                column.unsigned_abs().saturating_sub(width + tags_width)
+            } else {
+                (column as usize).saturating_sub(width)
+            };
+            line.extend(std::iter::repeat_n(' ', padding.max(1)));
+            line.push_str(&tags);
+            self.push_text(&line);
+        } else if !line.contains(' ') {
+            // headline stars must be followed by a space
+            line.push(' ');
+            self.push_verbatim(&line);
+        } else {
+            self.push_text(&line);
+        }
+    }
+
+    fn headline_indent(&self, level: usize) -> String {
+        if self.config.adapt_indentation {
+            " ".repeat(level + 1)
+        } else {
+            String::new()
+        }
+    }
+
+    fn property_drawer(&mut self, node: &SyntaxNode, level: usize) {
+        let indent = self.headline_indent(level);
+
+        let properties: Vec<(String, String)> = node
+            .children()
+            .filter(|n| n.kind() == NODE_PROPERTY)
+            .map(|n| {
+                let mut key = String::new();
+                let mut value = String::new();
+                let mut colons = 0;
+                for token in n.children_with_tokens().filter_map(|e| e.into_token()) {
+                    match token.kind() {
+                        COLON if colons < 2 => {
+                            colons += 1;
+                            key.push(':');
+                        }
+                        WHITESPACE | NEW_LINE => {}
+                        _ if colons < 2 => key.push_str(token.text()),
+                        _ => value.push_str(token.text()),
+                    }
+                }
+                (key, value)
+            })
+            .collect();
+
+        let width = properties
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or_default();
+
+        let mut properties = properties.into_iter();
+        for child in node.children_with_tokens() {
+            match child.kind() {
+                DRAWER_BEGIN | DRAWER_END => {
+                    let text = child.to_string();
+                    self.push_text(&format!("{indent}{}", text.trim()));
+                    if text.ends_with('\n') {
+                        self.push_text("\n");
+                    }
+                }
+                NODE_PROPERTY => {
+                    let Some((key, value)) = properties.next() else {
+                        continue;
+                    };
+                    let padding = " ".repeat(width - key.chars().count());
+                    if value.is_empty() {
+                        // property name must be followed by whitespace
+                        self.push_verbatim(&format!("{indent}{key} "));
+                    } else {
+                        self.push_text(&format!("{indent}{key}{padding} {value}"));
+                    }
+                    self.push_text("\n");
+                }
+                _ => self.element(child),
+            }
+        }
+    }
+
+    fn table(&mut self, node: &SyntaxNode) {
+        let rows: Vec<Option<Vec<String>>> = node
+            .children()
+            .filter_map(|row| match row.kind() {
+                ORG_TABLE_STANDARD_ROW => Some(Some(table_cells(&row.to_string()))),
+                ORG_TABLE_RULE_ROW => Some(None),
+                _ => None,
+            })
+            .collect();
+
+        if rows.iter().flatten().all(Vec::is_empty) {
+            for child in node.children_with_tokens() {
+                self.element(child);
+            }
+            return;
+        }
+
+        let indent: String = node
+            .text()
+            .to_string()
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+
+        let mut lines = align_table(&rows, |idx| {
+            column_align(rows.iter().flatten().filter_map(|row| row.get(idx)))
+        })
+        .into_iter();
+
+        for child in node.children_with_tokens() {
+            match child.kind() {
+                ORG_TABLE_RULE_ROW | ORG_TABLE_STANDARD_ROW => {
+                    let text = child.to_string();
+                    let line_end = if text.ends_with("\r\n") {
+                        "\r\n"
+                    } else if text.ends_with('\n') {
+                        "\n"
+                    } else {
+                        ""
+                    };
+                    let line = lines.next().unwrap_or_default();
+                    self.push_text(&format!("{indent}{line}{line_end}"));
+                }
+                _ => self.element(child),
+            }
+        }
+    }
+
+    fn list(&mut self, node: &SyntaxNode) {
+        let indent = match self.indent {
+            Some(indent) => " ".repeat(indent),
+            None => node
+                .children()
+                .find(|n| n.kind() == LIST_ITEM)
+                .and_then(|item| item.first_token())
+                .filter(|token| token.kind() == LIST_ITEM_INDENT)
+                .map(|token| token.text().to_string())
+                .unwrap_or_default(),
+        };
+
+        for child in node.children_with_tokens() {
+            match child {
+                NodeOrToken::Node(n) if n.kind() == LIST_ITEM => self.list_item(&n, &indent),
+                _ => self.element(child),
+            }
+        }
+    }
+
+    fn list_item(&mut self, node: &SyntaxNode, indent: &str) {
+        let parent_indent = self.indent;
+
+        for child in node.children_with_tokens() {
+            match child.kind() {
+                LIST_ITEM_INDENT => {
+                    self.output.push_str(indent);
+                    self.at_line_start = false;
+                }
+                LIST_ITEM_BULLET => {
+                    let bullet = child.to_string();
+                    let trimmed = bullet.trim_end();
+                    self.push_text(trimmed);
+                    if bullet.ends_with('\n') {
+                        self.push_text(&bullet[trimmed.len()..]);
+                    } else {
+                        // keeps the space even if item is empty, since `-\n`
+                        // and `- \n` are parsed differently
+                        self.push_verbatim(" ");
+                    }
+                    self.indent = Some(indent.chars().count() + trimmed.chars().count() + 1);
+                }
+                _ => self.element(child),
+            }
+        }
+
+        self.indent = parent_indent;
+    }
+}
+
+/// Splits table row into trimmed cells, including empty ones
+fn table_cells(row: &str) -> Vec<String> {
+    let mut cells: Vec<String> = row
+        .split('|')
+        .skip(1)
+        .map(|cell| cell.trim().to_string())
+        .collect();
+    // text after the last vertical bar is a cell only if it's not empty
+    if cells.last().is_some_and(String::is_empty) {
+        cells.pop();
+    }
+    cells
+}
+
+/// Aligns table rows, where `None` is a rule row, and returns one line per
+/// row without line endings
+///
+/// Every column is at least one character wide and padded to its widest cell.
+pub(crate) fn align_table(
+    rows: &[Option<Vec<String>>],
+    align: impl Fn(usize) -> Align,
+) -> Vec<String> {
+    let columns = rows
+        .iter()
+        .flatten()
+        .map(Vec::len)
+        .max()
+        .unwrap_or_default();
+
+    let mut widths = vec![1; columns];
+    for row in rows.iter().flatten() {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let aligns: Vec<Align> = (0..columns).map(align).collect();
+
+    rows.iter()
+        .map(|row| {
+            let mut line = String::from("|");
+            match row {
+                Some(cells) => {
+                    for (idx, width) in widths.iter().enumerate() {
+                        let cell = cells.get(idx).map(String::as_str).unwrap_or_default();
+                        let padding = width - cell.chars().count();
+                        let (left, right) = match aligns[idx] {
+                            Align::Left => (0, padding),
+                            Align::Right => (padding, 0),
+                            Align::Center => (padding / 2, padding - padding / 2),
+                        };
+                        line.push(' ');
+                        line.extend(std::iter::repeat_n(' ', left));
+                        line.push_str(cell);
+                        line.extend(std::iter::repeat_n(' ', right));
+                        line.push_str(" |");
+                    }
+                }
+                None => {
+                    for (idx, width) in widths.iter().enumerate() {
+                        if idx > 0 {
+                            line.push('+');
+                        }
+                        line.extend(std::iter::repeat_n('-', width + 2));
+                    }
+                    line.push('|');
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// Returns column alignment, either specified by a cookie like `<r>`, or
+/// right-aligned if most non-empty cells are numbers
+fn column_align<'a>(cells: impl Iterator<Item = &'a String>) -> Align {
+    let mut numbers = 0;
+    let mut non_empty = 0;
+
+    for cell in cells {
+        if let Some(cookie) = cell
+            .strip_prefix('<')
+            .and_then(|c| c.strip_suffix('>'))
+            .map(|c| c.trim_end_matches(|c: char| c.is_ascii_digit()))
+        {
+            match cookie {
+                "l" => return Align::Left,
+                "r" => return Align::Right,
+                "c" => return Align::Center,
+                _ => {}
+            }
+        }
+        if !cell.is_empty() {
+            non_empty += 1;
+            if is_number(cell) {
+                numbers += 1;
+            }
+        }
+    }
+
+    if non_empty > 0 && numbers * 2 > non_empty {
+        Align::Right
+    } else {
+        Align::Left
+    }
+}
+
+fn is_number(s: &str) -> bool {
+    let s = s.trim_end_matches('%');
+    !s.is_empty() && s.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Signature {
+    Enter(SyntaxKind),
+    Leave(SyntaxKind),
+    Text(String),
+}
+
+/// Flattens tree into a sequence that ignores whitespace differences, empty
+/// nodes and trailing empty table cells
+fn signature(node: &SyntaxNode) -> Vec<Signature> {
+    fn flush(buf: &mut String, output: &mut Vec<Signature>) {
+        let text = buf.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            output.push(Signature::Text(text));
+        }
+        buf.clear();
+    }
+
+    fn walk(node: &SyntaxNode, buf: &mut String, output: &mut Vec<Signature>) {
+        let is_row = matches!(node.kind(), ORG_TABLE_STANDARD_ROW | ORG_TABLE_RULE_ROW);
+
+        if node.kind() == ORG_TABLE_STANDARD_ROW {
+            let mut cells = table_cells(&node.to_string());
+            while cells.last().is_some_and(|cell| cell.is_empty()) {
+                cells.pop();
+            }
+            output.push(Signature::Text(cells.join("|")));
+        }
+
+        for child in node.children_with_tokens() {
+            match child {
+                // vertical bars and dashes in table rows are not meaningful
+                NodeOrToken::Token(_) if is_row => {}
+                NodeOrToken::Token(token) => buf.push_str(token.text()),
+                NodeOrToken::Node(n) => {
+                    if n.to_string().trim().is_empty() {
+                        continue;
+                    }
+                    flush(buf, output);
+                    output.push(Signature::Enter(n.kind()));
+                    walk(&n, buf, output);
+                    flush(buf, output);
+                    output.push(Signature::Leave(n.kind()));
+                }
+            }
+        }
+    }
+
+    let mut output = vec![];
+    let mut buf = String::new();
+    walk(node, &mut buf, &mut output);
+    flush(&mut buf, &mut output);
+    output
+}
+
+#[test]
+fn format() {
+    let format = |s: &str| {
+        let org = Org::parse(s);
+        let config = FormatConfig {
+            tags_column: -30,
+            ..Default::default()
+        };
+        let output = config.format(&org);
+        assert_eq!(
+            config.format(&Org::parse(&output)),
+            output,
+            "formatting should be idempotent"
+        );
+        output
+    };
+
+    insta::assert_snapshot!(
+        format("* TODO [#A] title   :a:b:  \n** DONE  sub\n   DEADLINE: <2023-01-01 Sun>  \n:PROPERTIES:\n   :ID: 1\n :CUSTOM_ID:   foo  \n  :EMPTY: \n:END:\n\n\n\n* \n*** :tag:"),
+        @r###"
+    * TODO [#A] title        :a:b:
+
+    ** DONE sub
+    DEADLINE: <2023-01-01 Sun>
+    :PROPERTIES:
+    :ID:        1
+    :CUSTOM_ID: foo
+    :EMPTY: 
+    :END:
+
+    * 
+
+    ***                      :tag:
+    "###
+    );
+
+    insta::assert_snapshot!(
+        format("|a|b|c|  \n|-\n| 1 | 10 |\n| 100 | | x\n|  | <c>|\n#+TBLFM: $2=$1  \n"),
+        @r###"
+    |   a |  b  | c |
+    |-----+-----+---|
+    |   1 | 10  |   |
+    | 100 |     | x |
+    |     | <c> |   |
+    #+TBLFM: $2=$1
+    "###
+    );
+
+    insta::assert_snapshot!(
+        format("  + item  \n        continued\n           + nested\n\n       second paragraph\n  + 10. [X] tag :: item\n          |a|bb|\n\n\n- a\n-\n\n- b"),
+        @r###"
+      + item
+        continued
+        + nested
+
+        second paragraph
+      + 10. [X] tag :: item
+        | a | bb |
+
+
+    - a
+    -
+
+    - b
+    "###
+    );
+
+    insta::assert_snapshot!(
+        format("text  \n#+begin_src sh   \n  echo 1   \n#+end_src  \n- item\n     #+begin_example\n  verbatim   \n     #+end_example\n"),
+        @r###"
+    text
+    #+begin_src sh
+      echo 1   
+    #+end_src
+    - item
+      #+begin_example
+      verbatim   
+      #+end_example
+    "###
+    );
+}
+
+#[test]
+fn equivalent() {
+    for input in [
+        "",
+        "\n\n\n",
+        "* ",
+        "*  \n",
+        " * ",
+        "\t* ",
+        "- ",
+        "0. ",
+        "* * ** :a:",
+        "- \n- hello",
+        "|\n\u{b}|",
+        "\r-",
+        "6\r\n",
+        "a  \r\nb\r\n",
+        "* a\r\n** b  \r\n",
+        "  - a\n  * b\n",
+        "- a\n  - b\n  #+begin_src\n,* c\n  #+end_src\n",
+        "[fn:1] a\n\n  [fn:2] b\n",
+        "|a|b|  \n|-\n| 1 | 10 |\n| 100 |\n|  | <c>|\n#+TBLFM: $2=$1  \n",
+    ] {
+        let org = Org::parse(input);
+        let output = FormatConfig::default().format_unchecked(&org.document().syntax);
+        assert_eq!(
+            signature(&org.document().syntax),
+            signature(&Org::parse(&output).document().syntax),
+            "{input:?} => {output:?}"
+        );
+    }
+}
+
+#[test]
+fn adapt_indentation() {
+    let config = FormatConfig {
+        adapt_indentation: true,
+        ..Default::default()
+    };
+
+    insta::assert_snapshot!(
+        config.format(&Org::parse("** a\nCLOSED: [2023-01-01 Sun]\n:PROPERTIES:\n:A: 1\n:END:\ntext")),
+        @r###"
+    ** a
+       CLOSED: [2023-01-01 Sun]
+       :PROPERTIES:
+       :A: 1
+       :END:
+    text
+    "###
+    );
+}
@@ -0,0 +1,541 @@
+//! Resolve `#+SETUPFILE:` and `#+INCLUDE:` keywords
+//!
+//! Files are loaded through a [`FileLoader`], so documents can be assembled
+//! from the filesystem using [`FsLoader`], or from memory using [`MemoryLoader`].
+//!
+//! ```rust
+//! use orgize::{include::MemoryLoader, ParseConfig};
+//!
+//! let loader = MemoryLoader::new()
+//!     .with_file("setup.org", "#+TODO: NEXT | DONE\n")
+//!     .with_file("chapter.org", "* Chapter\n** Section\ntext\n");
+//!
+//! let org = ParseConfig::default()
+//!     .parse_with_loader(
+//!         "#+SETUPFILE: setup.org\n* NEXT Book\n#+INCLUDE: \"chapter.org::*Section\" :minlevel 2\n",
+//!         &loader,
+//!     )
+//!     .unwrap();
+//!
+//! assert_eq!(
+//!     org.to_org(),
+//!     "#+SETUPFILE: setup.org\n* NEXT Book\n** Section\ntext\n"
+//! );
+//! assert_eq!(org.config().todo_keywords.0, vec!["NEXT".to_string()]);
+//! ```
+//!
+//! Only todo keywords (`#+TODO:`, `#+SEQ_TODO:` and `#+TYP_TODO:`) are taken
+//! from setup files. Other settings, like `#+PROPERTY:` or `#+MACRO:`, are
+//! not merged into the document, so [`SourceBlock::header_args`] and macro
+//! expansion only see keywords in the document itself. Use `#+INCLUDE:` to
+//! pull such keywords into the document instead.
+//!
+//! [`SourceBlock::header_args`]: crate::ast::SourceBlock::header_args
+
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use rowan::ast::AstNode;
+
+use crate::{
+    ast::{Headline, Keyword},
+    builder::escape_code,
+    syntax::combinator::line_starts_iter,
+    Org,
+};
+
+/// Loads content of included files
+pub trait FileLoader {
+    /// Returns content of file at `path`
+    ///
+    /// Relative paths in nested files are already joined with the directory
+    /// of the including file.
+    fn load(&self, path: &str) -> io::Result<String>;
+}
+
+/// Loads files from the filesystem, relative paths are resolved against `root`
+#[derive(Clone, Debug)]
+pub struct FsLoader {
+    root: PathBuf,
+}
+
+impl FsLoader {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FsLoader { root: root.into() }
+    }
+}
+
+impl FileLoader for FsLoader {
+    fn load(&self, path: &str) -> io::Result<String> {
+        fs::read_to_string(self.root.join(path))
+    }
+}
+
+/// Loads files from memory, mainly for testing
+#[derive(Clone, Debug, Default)]
+pub struct MemoryLoader {
+    files: HashMap<String, String>,
+}
+
+impl MemoryLoader {
+    pub fn new() -> Self {
+        MemoryLoader::default()
+    }
+
+    /// Adds a file with given path and content
+    pub fn with_file(mut self, path: impl Into<String>, content: impl Into<String>) -> Self {
+        self.files.insert(path.into(), content.into());
+        self
+    }
+}
+
+impl FileLoader for MemoryLoader {
+    fn load(&self, path: &str) -> io::Result<String> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{path} not found")))
+    }
+}
+
+/// Errors returned when resolving `#+SETUPFILE:` or `#+INCLUDE:`
+#[derive(Debug)]
+pub enum IncludeError {
+    /// File can't be loaded
+    Io { path: String, error: io::Error },
+    /// File includes itself, directly or indirectly
+    Recursive { path: String },
+    /// Selector like `::*heading` or `::#custom-id` matches nothing
+    NotFound { path: String, selector: String },
+}
+
+impl fmt::Display for IncludeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IncludeError::Io { path, error } => write!(f, "failed to load {path}: {error}"),
+            IncludeError::Recursive { path } => write!(f, "{path} includes itself"),
+            IncludeError::NotFound { path, selector } => {
+                write!(f, "{selector} not found in {path}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IncludeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IncludeError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Replaces each `#+INCLUDE:` keyword with the content it refers to
+///
+/// Supported options are block wrapping (`src <lang>`, `example`,
+/// `export <backend>`), `:lines "5-20"` (end line excluded), `:minlevel N`,
+/// and `::*heading` or `::#custom-id` selectors after the file name.
+///
+/// ```rust
+/// use orgize::include::{expand_includes, MemoryLoader};
+///
+/// let loader = MemoryLoader::new().with_file("main.rs", "// 1\nfn main() {}\n// 3\n");
+///
+/// assert_eq!(
+///     expand_includes("#+INCLUDE: main.rs src rust :lines \"2-3\"", &loader).unwrap(),
+///     "#+BEGIN_SRC rust\nfn main() {}\n#+END_SRC\n"
+/// );
+/// ```
+pub fn expand_includes(input: &str, loader: &dyn FileLoader) -> Result<String, IncludeError> {
+    expand(input, None, loader, &mut vec![])
+}
+
+/// Collects content of all `#+SETUPFILE:`, including nested ones
+///
+/// The content is only scanned for todo keywords, see module documentation.
+pub(crate) fn setup_files(input: &str, loader: &dyn FileLoader) -> Result<String, IncludeError> {
+    fn collect(
+        input: &str,
+        current: Option<&str>,
+        loader: &dyn FileLoader,
+        stack: &mut Vec<String>,
+        output: &mut String,
+    ) -> Result<(), IncludeError> {
+        for (keyword, _) in keywords(input, "SETUPFILE") {
+            let value = keyword.value();
+            let path = join(current, unquote(value.trim()));
+            let content = load(&path, loader, stack)?;
+            collect(&content, Some(&path), loader, stack, output)?;
+            output.push_str(&content);
+            output.push('\n');
+            stack.pop();
+        }
+        Ok(())
+    }
+
+    let mut output = String::new();
+    collect(input, None, loader, &mut vec![], &mut output)?;
+    Ok(output)
+}
+
+fn expand(
+    input: &str,
+    current: Option<&str>,
+    loader: &dyn FileLoader,
+    stack: &mut Vec<String>,
+) -> Result<String, IncludeError> {
+    let mut output = String::with_capacity(input.len());
+    let mut pos = 0;
+
+    for (keyword, indent) in keywords(input, "INCLUDE") {
+        let start: usize = keyword.start().into();
+        // only replace keyword line, blank lines after it are kept
+        let end = line_starts_iter(&input[start..])
+            .nth(1)
+            .map_or(input.len(), |i| start + i);
+
+        output.push_str(&input[pos..start]);
+        output.push_str(&include(&keyword.value(), &indent, current, loader, stack)?);
+        pos = end;
+    }
+
+    output.push_str(&input[pos..]);
+    Ok(output)
+}
+
+/// Returns keywords with given key and their indentation
+fn keywords(input: &str, key: &str) -> Vec<(Keyword, String)> {
+    Org::parse(input)
+        .document()
+        .syntax()
+        .descendants()
+        .filter_map(Keyword::cast)
+        .filter(|k| k.key().eq_ignore_ascii_case(key))
+        .map(|k| {
+            let text = k.syntax().to_string();
+            let indent = text[..text.len() - text.trim_start().len()].to_string();
+            (k, indent)
+        })
+        .collect()
+}
+
+fn load(
+    path: &str,
+    loader: &dyn FileLoader,
+    stack: &mut Vec<String>,
+) -> Result<String, IncludeError> {
+    if stack.iter().any(|p| p == path) {
+        return Err(IncludeError::Recursive { path: path.into() });
+    }
+    let content = loader.load(path).map_err(|error| IncludeError::Io {
+        path: path.into(),
+        error,
+    })?;
+    stack.push(path.into());
+    Ok(content)
+}
+
+fn include(
+    value: &str,
+    indent: &str,
+    current: Option<&str>,
+    loader: &dyn FileLoader,
+    stack: &mut Vec<String>,
+) -> Result<String, IncludeError> {
+    let mut args = Args::new(value);
+
+    let Some(file) = args.next() else {
+        return Ok(String::new());
+    };
+    let (file, selector) = match file.split_once("::") {
+        Some((file, selector)) => (file, Some(selector)),
+        None => (file, None),
+    };
+    let path = join(current, file);
+
+    // block type and its argument, e.g. `src rust`
+    let mut block = vec![];
+    let mut lines = None;
+    let mut minlevel = None;
+    while let Some(arg) = args.next() {
+        match arg {
+            ":lines" => lines = args.next(),
+            ":minlevel" => minlevel = args.next().and_then(|s| s.parse::<usize>().ok()),
+            _ if arg.starts_with(':') => {
+                args.next();
+            }
+            _ if lines.is_none() && minlevel.is_none() => block.push(arg),
+            _ => {}
+        }
+    }
+
+    let content = load(&path, loader, stack)?;
+
+    let mut content = match selector {
+        Some(selector) => select(&content, selector).ok_or_else(|| IncludeError::NotFound {
+            path: path.clone(),
+            selector: format!("::{selector}"),
+        })?,
+        None => content,
+    };
+
+    if let Some(lines) = lines {
+        content = slice_lines(&content, lines);
+    }
+
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+
+    let result = match block.first() {
+        Some(ty) => {
+            let ty = ty.to_ascii_uppercase();
+            let mut s = format!("{indent}#+BEGIN_{ty}");
+            for arg in &block[1..] {
+                s.push(' ');
+                s.push_str(arg);
+            }
+            s.push('\n');
+            if ty == "EXPORT" {
+                s.push_str(&content);
+            } else {
+                s.push_str(&escape_code(&content));
+            }
+            s.push_str(&format!("{indent}#+END_{ty}\n"));
+            s
+        }
+        None => {
+            if let Some(level) = minlevel {
+                content = shift_levels(&content, level);
+            }
+            expand(&content, Some(&path), loader, stack)?
+        }
+    };
+
+    stack.pop();
+    Ok(result)
+}
+
+/// Splits keyword value into arguments, double quotes are removed
+struct Args<'a> {
+    s: &'a str,
+}
+
+impl<'a> Args<'a> {
+    fn new(s: &'a str) -> Self {
+        Args { s }
+    }
+}
+
+impl<'a> Iterator for Args<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let s = self.s.trim_start();
+        if s.is_empty() {
+            return None;
+        }
+        let (arg, rest) = match s.strip_prefix('"').and_then(|s| s.split_once('"')) {
+            Some((arg, rest)) => (arg, rest),
+            None => s.split_at(s.find(char::is_whitespace).unwrap_or(s.len())),
+        };
+        self.s = rest;
+        Some(arg)
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+/// Resolves `path` relative to the directory of `current` file
+fn join(current: Option<&str>, path: &str) -> String {
+    match current.and_then(|c| Path::new(c).parent()) {
+        Some(parent) if Path::new(path).is_relative() => {
+            parent.join(path).to_string_lossy().into_owned()
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Returns the subtree matching `*heading` or `#custom-id`
+fn select(content: &str, selector: &str) -> Option<String> {
+    let org = Org::parse(content);
+    let mut headlines = org
+        .document()
+        .syntax()
+        .descendants()
+        .filter_map(Headline::cast);
+
+    let headline = if let Some(title) = selector.strip_prefix('*') {
+        headlines.find(|h| h.title_raw().trim() == title.trim())
+    } else if let Some(id) = selector.strip_prefix('#') {
+        headlines.find(|h| {
+            h.properties()
+                .and_then(|p| p.get("CUSTOM_ID"))
+                .is_some_and(|v| v == id)
+        })
+    } else {
+        None
+    }?;
+
+    Some(headline.syntax().to_string())
+}
+
+/// Keeps lines in range like `5-20`, `5-` or `-20`, starting from 1 and end excluded
+fn slice_lines(content: &str, range: &str) -> String {
+    let (start, end) = range.split_once('-').unwrap_or((range, ""));
+    let start = start.trim().parse::<usize>().unwrap_or(1).max(1);
+    let end = end.trim().parse::<usize>().unwrap_or(usize::MAX);
+
+    content
+        .split_inclusive('\n')
+        .enumerate()
+        .filter(|(i, _)| (start..end).contains(&(i + 1)))
+        .map(|(_, line)| line)
+        .collect()
+}
+
+/// Shifts headline levels so that the top-most headline has `level` stars
+fn shift_levels(content: &str, level: usize) -> String {
+    let org = Org::parse(content);
+    let headlines: Vec<_> = org
+        .document()
+        .syntax()
+        .descendants()
+        .filter_map(Headline::cast)
+        .collect();
+
+    let Some(min) = headlines.iter().map(|h| h.level()).min() else {
+        return content.to_string();
+    };
+
+    let mut output = String::with_capacity(content.len());
+    let mut pos = 0;
+    for headline in headlines {
+        let start: usize = headline.start().into();
+        output.push_str(&content[pos..start]);
+        output.push_str(&"*".repeat(headline.level() + level - min));
+        pos = start + headline.level();
+    }
+    output.push_str(&content[pos..]);
+    output
+}
+
+#[test]
+fn expand_include() {
+    let loader = MemoryLoader::new()
+        .with_file("book.org", "#+INCLUDE: chapters/one.org :minlevel 2\n")
+        .with_file(
+            "chapters/one.org",
+            "* One\n:PROPERTIES:\n:CUSTOM_ID: one\n:END:\n** Detail\n#+INCLUDE: \"code.sh\" example\n",
+        )
+        .with_file("chapters/code.sh", "#+comment\necho 1\n")
+        .with_file("loop.org", "#+INCLUDE: loop.org");
+
+    assert_eq!(
+        expand_includes("* Book\n#+INCLUDE: \"book.org\"\n\ntext", &loader).unwrap(),
+        "* Book\n** One\n:PROPERTIES:\n:CUSTOM_ID: one\n:END:\n*** Detail\n#+BEGIN_EXAMPLE\n,#+comment\necho 1\n#+END_EXAMPLE\n\ntext"
+    );
+
+    assert_eq!(
+        expand_includes(
+            "  #+include: \"chapters/one.org::#one\" :lines \"1-2\"",
+            &loader
+        )
+        .unwrap(),
+        "* One\n"
+    );
+
+    assert_eq!(
+        expand_includes("#+INCLUDE: \"chapters/code.sh\" export html", &loader).unwrap(),
+        "#+BEGIN_EXPORT html\n#+comment\necho 1\n#+END_EXPORT\n"
+    );
+
+    assert_eq!(
+        expand_includes("#+INCLUDE: chapters/code.sh src sh :lines \"2-\"", &loader).unwrap(),
+        "#+BEGIN_SRC sh\necho 1\n#+END_SRC\n"
+    );
+
+    // keywords inside blocks are kept as is
+    assert_eq!(
+        expand_includes(
+            "#+begin_example\n#+INCLUDE: missing.org\n#+end_example\n",
+            &loader
+        )
+        .unwrap(),
+        "#+begin_example\n#+INCLUDE: missing.org\n#+end_example\n"
+    );
+
+    assert!(matches!(
+        expand_includes("#+INCLUDE: loop.org", &loader),
+        Err(IncludeError::Recursive { .. })
+    ));
+    assert!(matches!(
+        expand_includes("#+INCLUDE: missing.org", &loader),
+        Err(IncludeError::Io { .. })
+    ));
+    assert!(matches!(
+        expand_includes("#+INCLUDE: \"chapters/one.org::*Two\"", &loader),
+        Err(IncludeError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn setup_file() {
+    let loader = MemoryLoader::new()
+        .with_file(
+            "setup.org",
+            "#+SETUPFILE: nested/todo.org\n#+TYP_TODO: Fred | Mike\n",
+        )
+        .with_file("nested/todo.org", "#+TODO: NEXT WAIT(w@) | DONE\n");
+
+    let org = crate::ParseConfig::default()
+        .parse_with_loader(
+            "#+SETUPFILE: \"setup.org\"\n#+TODO: BUG | FIXED\n* WAIT a\n* BUG b\n* Fred c",
+            &loader,
+        )
+        .unwrap();
+
+    let keywords: Vec<_> = org
+        .document()
+        .headlines()
+        .map(|h| h.todo_keyword().map(|k| k.to_string()))
+        .collect();
+    assert_eq!(
+        keywords,
+        vec![Some("WAIT".into()), Some("BUG".into()), Some("Fred".into())]
+    );
+    assert_eq!(org.todo_sequences().len(), 3);
+    assert_eq!(org.todo_keyword("WAIT").unwrap().key, Some('w'));
+}
+
+#[test]
+fn setup_file_only_todo_keywords() {
+    use crate::ast::SourceBlock;
+
+    let loader = MemoryLoader::new().with_file(
+        "setup.org",
+        "#+TODO: NEXT | DONE\n#+PROPERTY: header-args :results silent\n#+MACRO: name value\n",
+    );
+
+    let org = crate::ParseConfig::default()
+        .parse_with_loader(
+            "#+SETUPFILE: setup.org\n* NEXT a\n#+begin_src sh\n#+end_src\n",
+            &loader,
+        )
+        .unwrap();
+
+    assert!(org.todo_keyword("NEXT").is_some());
+    let block = org.first_node::<SourceBlock>().unwrap();
+    assert_eq!(block.header_args().get("results"), None);
+    assert_eq!(
+        org.to_org(),
+        "#+SETUPFILE: setup.org\n* NEXT a\n#+begin_src sh\n#+end_src\n"
+    );
+}
@@ -1,10 +1,17 @@
 #![doc = include_str!("../README.md")]
 
 pub mod ast;
+pub mod babel;
+pub mod builder;
 mod config;
 mod entities;
 pub mod export;
+pub mod format;
+pub mod include;
+pub mod lint;
 mod org;
+mod org_element;
+pub mod stream;
 mod syntax;
 #[cfg(test)]
 mod tests;
@@ -12,9 +19,10 @@ mod tests;
 // Re-export of the rowan crate.
 pub use rowan;
 
-pub use config::ParseConfig;
+pub use config::{ParseConfig, TodoKeyword, TodoLog, TodoSequence, TodoSequenceKind};
 pub use org::Org;
 pub use syntax::{
+    diagnostic::{ParseDiagnostic, ParseDiagnosticKind},
     SyntaxElement, SyntaxElementChildren, SyntaxKind, SyntaxNode, SyntaxNodeChildren, SyntaxToken,
 };
 
@@ -0,0 +1,817 @@
+//! Lint org-mode documents
+//!
+//! Checks are modeled on `org-lint` from org-mode, each of them can be
+//! toggled using [`LintConfig::rules`].
+//!
+//! ```rust
+//! use orgize::{lint::LintRule, Org};
+//!
+//! let org = Org::parse("* a\n:PROPERTIES:\n:CUSTOM_ID: a\n:END:\n* b\n:PROPERTIES:\n:CUSTOM_ID: a\n:END:\n[[#b]]");
+//! let diagnostics = org.lint();
+//!
+//! assert_eq!(diagnostics.len(), 2);
+//! assert_eq!(diagnostics[0].rule, LintRule::DuplicateId);
+//! assert_eq!(diagnostics[0].message, "Duplicate CUSTOM_ID property \"a\"");
+//! assert_eq!(diagnostics[1].rule, LintRule::UnknownLinkTarget);
+//! assert_eq!(&org.to_org()[diagnostics[1].range], "[[#b]]");
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use rowan::{ast::AstNode, TextRange, TextSize};
+
+use crate::{
+    ast::{AffiliatedKeyword, Drawer, FnDef, FnRef, Headline, Link, SourceBlock, Timestamp},
+    Org, SyntaxKind, SyntaxNode,
+};
+
+/// Lint rules
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    /// Footnote reference without definition
+    DanglingFootnote,
+    /// Internal link to a target, custom id or headline that doesn't exist
+    UnknownLinkTarget,
+    /// Same `CUSTOM_ID` or `ID` property in multiple headlines
+    DuplicateId,
+    /// Timestamp with invalid date or time, e.g. `<2023-13-01>`
+    InvalidTimestamp,
+    /// `#+BEGIN_` line without matching `#+END_` line, or vice versa
+    MismatchedBlock,
+    /// Planning line not right after a headline
+    MisplacedPlanning,
+    /// Property drawer which is misplaced, unterminated or contains invalid lines
+    MalformedPropertyDrawer,
+    /// Source block language not in [`LintConfig::src_languages`]
+    UnknownSrcLanguage,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 8] = [
+        LintRule::DanglingFootnote,
+        LintRule::UnknownLinkTarget,
+        LintRule::DuplicateId,
+        LintRule::InvalidTimestamp,
+        LintRule::MismatchedBlock,
+        LintRule::MisplacedPlanning,
+        LintRule::MalformedPropertyDrawer,
+        LintRule::UnknownSrcLanguage,
+    ];
+
+    /// Returns rule name, e.g. `dangling-footnote`
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintRule::DanglingFootnote => "dangling-footnote",
+            LintRule::UnknownLinkTarget => "unknown-link-target",
+            LintRule::DuplicateId => "duplicate-id",
+            LintRule::InvalidTimestamp => "invalid-timestamp",
+            LintRule::MismatchedBlock => "mismatched-block",
+            LintRule::MisplacedPlanning => "misplaced-planning",
+            LintRule::MalformedPropertyDrawer => "malformed-property-drawer",
+            LintRule::UnknownSrcLanguage => "unknown-src-language",
+        }
+    }
+}
+
+/// A problem reported by linter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule: LintRule,
+    /// Text range of the problematic node or line
+    pub range: TextRange,
+    pub message: String,
+}
+
+/// Lint configuration
+#[derive(Clone, Debug)]
+pub struct LintConfig {
+    /// Enabled rules
+    pub rules: Vec<LintRule>,
+
+    /// Known source block languages, used by [`LintRule::UnknownSrcLanguage`]
+    pub src_languages: Vec<String>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            rules: LintRule::ALL.to_vec(),
+            src_languages: [
+                "C",
+                "C++",
+                "D",
+                "R",
+                "asm",
+                "awk",
+                "bash",
+                "c",
+                "calc",
+                "clojure",
+                "cpp",
+                "css",
+                "diff",
+                "ditaa",
+                "dot",
+                "elisp",
+                "emacs-lisp",
+                "fish",
+                "fortran",
+                "go",
+                "gnuplot",
+                "haskell",
+                "html",
+                "java",
+                "javascript",
+                "js",
+                "json",
+                "julia",
+                "kotlin",
+                "latex",
+                "lilypond",
+                "lisp",
+                "lua",
+                "makefile",
+                "matlab",
+                "nix",
+                "ocaml",
+                "octave",
+                "org",
+                "perl",
+                "php",
+                "plantuml",
+                "powershell",
+                "python",
+                "ruby",
+                "rust",
+                "sass",
+                "scala",
+                "scheme",
+                "screen",
+                "sed",
+                "sh",
+                "shell",
+                "sql",
+                "sqlite",
+                "swift",
+                "text",
+                "toml",
+                "typescript",
+                "xml",
+                "yaml",
+                "zsh",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        }
+    }
+}
+
+impl LintConfig {
+    /// Returns diagnostics of given document, sorted by position
+    pub fn lint(&self, org: &Org) -> Vec<Diagnostic> {
+        let root = org.document().syntax;
+        let text = root.to_string();
+
+        let mut linter = Linter {
+            config: self,
+            text: &text,
+            diagnostics: vec![],
+        };
+
+        let enabled = |rule| self.rules.contains(&rule);
+
+        if enabled(LintRule::DanglingFootnote) {
+            linter.dangling_footnotes(&root);
+        }
+        if enabled(LintRule::UnknownLinkTarget) {
+            linter.unknown_link_targets(&root);
+        }
+        if enabled(LintRule::DuplicateId) {
+            linter.duplicate_ids(&root);
+        }
+        if enabled(LintRule::InvalidTimestamp) {
+            linter.invalid_timestamps(&root);
+        }
+        if enabled(LintRule::MalformedPropertyDrawer) {
+            linter.property_drawers(&root);
+        }
+        if enabled(LintRule::UnknownSrcLanguage) {
+            linter.src_languages(&root);
+        }
+        linter.paragraph_lines(&root);
+
+        let mut diagnostics = linter.diagnostics;
+        diagnostics.sort_by_key(|d| (d.range.start(), d.range.end()));
+        diagnostics
+    }
+}
+
+struct Linter<'a> {
+    config: &'a LintConfig,
+    text: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, rule: LintRule, range: TextRange, message: String) {
+        self.diagnostics.push(Diagnostic {
+            rule,
+            range,
+            message,
+        });
+    }
+
+    fn dangling_footnotes(&mut self, root: &SyntaxNode) {
+        let mut labels = HashSet::new();
+        let mut references = vec![];
+
+        for node in root.descendants() {
+            if let Some(fn_def) = FnDef::cast(node.clone()) {
+                labels.insert(fn_def.label().as_ref().to_string());
+            } else if let Some(fn_ref) = FnRef::cast(node) {
+                let label = fn_ref.label();
+                if label.is_empty() {
+                    continue;
+                }
+                if fn_ref.is_inline() {
+                    labels.insert(label.as_ref().to_string());
+                } else {
+                    references.push((label.as_ref().to_string(), fn_ref.syntax.text_range()));
+                }
+            }
+        }
+
+        for (label, range) in references {
+            if !labels.contains(&label) {
+                self.report(
+                    LintRule::DanglingFootnote,
+                    range,
+                    format!("Missing definition for footnote [{label}]"),
+                );
+            }
+        }
+    }
+
+    fn unknown_link_targets(&mut self, root: &SyntaxNode) {
+        let mut targets = HashSet::new();
+        let mut custom_ids = HashSet::new();
+        let mut headlines = HashSet::new();
+
+        for node in root.descendants() {
+            match node.kind() {
+                SyntaxKind::TARGET | SyntaxKind::RADIO_TARGET => {
+                    let text = node.to_string();
+                    let text = text.trim_start_matches('<').trim_end_matches('>');
+                    targets.insert(normalize(text));
+                }
+                SyntaxKind::AFFILIATED_KEYWORD => {
+                    let keyword = AffiliatedKeyword { syntax: node };
+                    if keyword.key().eq_ignore_ascii_case("NAME") {
+                        if let Some(value) = keyword.value() {
+                            targets.insert(normalize(&value));
+                        }
+                    }
+                }
+                SyntaxKind::HEADLINE => {
+                    let headline = Headline { syntax: node };
+                    headlines.insert(normalize(&headline.title_raw()));
+                    let properties = headline.properties();
+                    for (key, id) in properties.iter().flat_map(|p| p.iter()) {
+                        if key.eq_ignore_ascii_case("CUSTOM_ID") {
+                            custom_ids.insert(id.as_ref().to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for link in root.descendants().filter_map(Link::cast) {
+            let path = link.path();
+            let path = path.as_ref();
+
+            let message = if let Some(id) = path.strip_prefix('#') {
+                if custom_ids.contains(id) {
+                    continue;
+                }
+                format!("Unknown custom ID \"{id}\"")
+            } else if let Some(title) = path.strip_prefix('*') {
+                if headlines.contains(&normalize(title)) {
+                    continue;
+                }
+                format!("Unknown headline \"{title}\"")
+            } else if is_fuzzy_link(path) {
+                let path = normalize(path);
+                if targets.contains(&path) || headlines.contains(&path) {
+                    continue;
+                }
+                format!("Unknown fuzzy location \"{path}\"")
+            } else {
+                continue;
+            };
+
+            self.report(
+                LintRule::UnknownLinkTarget,
+                link.syntax.text_range(),
+                message,
+            );
+        }
+    }
+
+    fn duplicate_ids(&mut self, root: &SyntaxNode) {
+        let mut seen = HashMap::new();
+
+        for headline in root.descendants().filter_map(Headline::cast) {
+            let Some(properties) = headline.properties() else {
+                continue;
+            };
+
+            for (key, value) in properties.iter() {
+                let key = key.to_ascii_uppercase();
+                if key != "CUSTOM_ID" && key != "ID" {
+                    continue;
+                }
+                let range = TextRange::new(value.start(), value.end());
+                if seen
+                    .insert((key.clone(), value.as_ref().to_string()), range)
+                    .is_some()
+                {
+                    self.report(
+                        LintRule::DuplicateId,
+                        range,
+                        format!("Duplicate {key} property \"{}\"", value.as_ref()),
+                    );
+                }
+            }
+        }
+    }
+
+    fn invalid_timestamps(&mut self, root: &SyntaxNode) {
+        for timestamp in root.descendants().filter_map(Timestamp::cast) {
+            if timestamp.is_diary() {
+                continue;
+            }
+
+            let valid = is_valid_date(
+                timestamp.year_start().as_deref(),
+                timestamp.month_start().as_deref(),
+                timestamp.day_start().as_deref(),
+            ) && is_valid_time(
+                timestamp.hour_start().as_deref(),
+                timestamp.minute_start().as_deref(),
+            ) && (timestamp.year_end().is_none()
+                || is_valid_date(
+                    timestamp.year_end().as_deref(),
+                    timestamp.month_end().as_deref(),
+                    timestamp.day_end().as_deref(),
+                ))
+                && is_valid_time(
+                    timestamp.hour_end().as_deref(),
+                    timestamp.minute_end().as_deref(),
+                );
+
+            if !valid {
+                self.report(
+                    LintRule::InvalidTimestamp,
+                    timestamp.syntax.text_range(),
+                    format!("Invalid timestamp \"{}\"", timestamp.syntax),
+                );
+            }
+        }
+    }
+
+    fn property_drawers(&mut self, root: &SyntaxNode) {
+        for drawer in root.descendants().filter_map(Drawer::cast) {
+            if !drawer.name().eq_ignore_ascii_case("PROPERTIES") {
+                continue;
+            }
+
+            let section = drawer
+                .syntax
+                .parent()
+                .filter(|p| p.kind() == SyntaxKind::SECTION);
+            let is_first = section
+                .as_ref()
+                .and_then(|s| s.first_child())
+                .is_some_and(|n| n == drawer.syntax);
+            let headline = section
+                .as_ref()
+                .and_then(|s| s.parent())
+                .filter(|p| p.kind() == SyntaxKind::HEADLINE);
+
+            let message = match headline {
+                // file-level property drawer
+                None if is_first => continue,
+                // right after headline and planning, unless headline has one already
+                Some(headline)
+                    if is_first
+                        && !headline
+                            .children()
+                            .any(|n| n.kind() == SyntaxKind::PROPERTY_DRAWER) =>
+                {
+                    if drawer.syntax.to_string().lines().skip(1).all(|line| {
+                        let line = line.trim();
+                        line.eq_ignore_ascii_case(":END:") || is_node_property(line)
+                    }) {
+                        "Property drawer name should be upper case"
+                    } else {
+                        "Property drawer contains invalid lines"
+                    }
+                }
+                _ => "Misplaced property drawer",
+            };
+
+            self.report(
+                LintRule::MalformedPropertyDrawer,
+                drawer.syntax.text_range(),
+                message.to_string(),
+            );
+        }
+    }
+
+    fn src_languages(&mut self, root: &SyntaxNode) {
+        for block in root.descendants().filter_map(SourceBlock::cast) {
+            let Some(language) = block.language() else {
+                continue;
+            };
+
+            if !self
+                .config
+                .src_languages
+                .iter()
+                .any(|l| l == language.as_ref())
+            {
+                let range = TextRange::new(language.start(), language.end());
+                self.report(
+                    LintRule::UnknownSrcLanguage,
+                    range,
+                    format!("Unknown source block language \"{}\"", language.as_ref()),
+                );
+            }
+        }
+    }
+
+    /// Checks lines that are supposed to be parsed as other elements but end
+    /// up in paragraphs
+    fn paragraph_lines(&mut self, root: &SyntaxNode) {
+        let enabled = |rule| self.config.rules.contains(&rule);
+        let mismatched_block = enabled(LintRule::MismatchedBlock);
+        let misplaced_planning = enabled(LintRule::MisplacedPlanning);
+        let malformed_drawer = enabled(LintRule::MalformedPropertyDrawer);
+
+        for paragraph in root
+            .descendants()
+            .filter(|n| n.kind() == SyntaxKind::PARAGRAPH)
+        {
+            let range = paragraph.text_range();
+            let mut start = usize::from(range.start());
+            let end = usize::from(range.end());
+
+            while start < end {
+                let line_end = self.text[start..end]
+                    .find('\n')
+                    .map(|i| start + i + 1)
+                    .unwrap_or(end);
+                let line = &self.text[start..line_end];
+                let at_line_start = start == 0 || self.text.as_bytes()[start - 1] == b'\n';
+                let trimmed = line.trim();
+                if !at_line_start || trimmed.is_empty() {
+                    start = line_end;
+                    continue;
+                }
+                let range = TextRange::new(
+                    TextSize::from((start + line.len() - line.trim_start().len()) as u32),
+                    TextSize::from((start + line.trim_end().len()) as u32),
+                );
+                start = line_end;
+
+                if mismatched_block {
+                    if let Some(name) = block_line(trimmed, "#+begin_") {
+                        self.report(
+                            LintRule::MismatchedBlock,
+                            range,
+                            format!("Missing #+END_{} line", name.to_uppercase()),
+                        );
+                        continue;
+                    }
+                    if let Some(name) = block_line(trimmed, "#+end_") {
+                        self.report(
+                            LintRule::MismatchedBlock,
+                            range,
+                            format!("Missing #+BEGIN_{} line", name.to_uppercase()),
+                        );
+                        continue;
+                    }
+                }
+
+                if misplaced_planning
+                    && ["DEADLINE:", "SCHEDULED:", "CLOSED:"]
+                        .iter()
+                        .any(|k| trimmed.starts_with(k))
+                {
+                    self.report(
+                        LintRule::MisplacedPlanning,
+                        range,
+                        "Misplaced planning info line".into(),
+                    );
+                    continue;
+                }
+
+                if malformed_drawer && trimmed.eq_ignore_ascii_case(":PROPERTIES:") {
+                    self.report(
+                        LintRule::MalformedPropertyDrawer,
+                        range,
+                        "Property drawer without :END: line".into(),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Returns block name if line starts with given prefix, case-insensitively
+fn block_line<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = line.get(0..prefix.len())?;
+    if !head.eq_ignore_ascii_case(prefix) {
+        return None;
+    }
+    let name = &line[prefix.len()..];
+    let name = &name[0..name
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(name.len())];
+    (!name.is_empty()).then_some(name)
+}
+
+/// Returns `true` if link is a search option in current document, e.g. `[[target]]`
+fn is_fuzzy_link(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with(['/', '.', '~', '('])
+        && !path.split_once(':').is_some_and(|(scheme, _)| {
+            scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Returns true if line is a node property, e.g. `:KEY: VALUE` or `:KEY+:`
+fn is_node_property(line: &str) -> bool {
+    let Some((key, value)) = line.strip_prefix(':').and_then(|line| line.split_once(':')) else {
+        return false;
+    };
+    let key = key.strip_suffix('+').unwrap_or(key);
+    !key.is_empty()
+        && !key.contains(char::is_whitespace)
+        && (value.is_empty() || value.starts_with([' ', '\t']))
+}
+
+fn normalize(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn is_valid_date(year: Option<&str>, month: Option<&str>, day: Option<&str>) -> bool {
+    let (Some(year), Some(month), Some(day)) = (
+        year.and_then(|s| s.parse::<u32>().ok()),
+        month.and_then(|s| s.parse::<u32>().ok()),
+        day.and_then(|s| s.parse::<u32>().ok()),
+    ) else {
+        return false;
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=days).contains(&day)
+}
+
+fn is_valid_time(hour: Option<&str>, minute: Option<&str>) -> bool {
+    match (hour, minute) {
+        (None, None) => true,
+        (Some(hour), Some(minute)) => {
+            hour.parse::<u32>().is_ok_and(|h| h < 24) && minute.parse::<u32>().is_ok_and(|m| m < 60)
+        }
+        _ => false,
+    }
+}
+
+#[test]
+fn lint() {
+    let lint = |s: &str| {
+        let org = Org::parse(s);
+        org.lint()
+            .into_iter()
+            .map(|d| (d.rule, org.to_org()[d.range].to_string(), d.message))
+            .collect::<Vec<_>>()
+    };
+
+    insta::assert_debug_snapshot!(
+        lint("text [fn:1] [fn:2] [fn:3:inline] [fn:3] [fn::anonymous]\n\n[fn:2] def"),
+        @r###"
+    [
+        (
+            DanglingFootnote,
+            "[fn:1]",
+            "Missing definition for footnote [1]",
+        ),
+    ]
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        lint("* Heading\n:PROPERTIES:\n:CUSTOM_ID: heading\n:END:\n<<target>>\n#+NAME: named\n| a |\n\n[[#heading]] [[#nope]] [[*Heading]] [[*nope]] [[target]] [[NAMED]] [[heading]] [[nope]] [[https://example.com]] [[file:a.org]] [[./a.org]] [[(ref)]]"),
+        @r###"
+    [
+        (
+            UnknownLinkTarget,
+            "[[#nope]]",
+            "Unknown custom ID \"nope\"",
+        ),
+        (
+            UnknownLinkTarget,
+            "[[*nope]]",
+            "Unknown headline \"nope\"",
+        ),
+        (
+            UnknownLinkTarget,
+            "[[nope]]",
+            "Unknown fuzzy location \"nope\"",
+        ),
+    ]
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        lint("* a\n:PROPERTIES:\n:ID: 1\n:CUSTOM_ID: a\n:END:\n** b\n:PROPERTIES:\n:ID: 1\n:CUSTOM_ID: b\n:END:\n* c\n:PROPERTIES:\n:CUSTOM_ID: a\n:ID: 2\n:END:"),
+        @r###"
+    [
+        (
+            DuplicateId,
+            "1",
+            "Duplicate ID property \"1\"",
+        ),
+        (
+            DuplicateId,
+            "a",
+            "Duplicate CUSTOM_ID property \"a\"",
+        ),
+    ]
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        lint("<2023-02-29 Wed> <2024-02-29 Thu> <2023-13-01> [2023-04-31] <2023-01-01 24:00> <2023-01-01 10:00-10:60> <2023-01-01>--<2023-01-32> <%%(diary-float t 4 2)>"),
+        @r###"
+    [
+        (
+            InvalidTimestamp,
+            "<2023-02-29 Wed>",
+            "Invalid timestamp \"<2023-02-29 Wed>\"",
+        ),
+        (
+            InvalidTimestamp,
+            "<2023-13-01>",
+            "Invalid timestamp \"<2023-13-01>\"",
+        ),
+        (
+            InvalidTimestamp,
+            "[2023-04-31]",
+            "Invalid timestamp \"[2023-04-31]\"",
+        ),
+        (
+            InvalidTimestamp,
+            "<2023-01-01 24:00>",
+            "Invalid timestamp \"<2023-01-01 24:00>\"",
+        ),
+        (
+            InvalidTimestamp,
+            "<2023-01-01 10:00-10:60>",
+            "Invalid timestamp \"<2023-01-01 10:00-10:60>\"",
+        ),
+        (
+            InvalidTimestamp,
+            "<2023-01-01>--<2023-01-32>",
+            "Invalid timestamp \"<2023-01-01>--<2023-01-32>\"",
+        ),
+    ]
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        lint("#+BEGIN_QUOTE\ntext\n\n  #+end_example\n#+begin_src rust\n#+end_quote\n#+end_src"),
+        @r###"
+    [
+        (
+            MismatchedBlock,
+            "#+BEGIN_QUOTE",
+            "Missing #+END_QUOTE line",
+        ),
+        (
+            MismatchedBlock,
+            "#+end_example",
+            "Missing #+BEGIN_EXAMPLE line",
+        ),
+    ]
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        lint(":PROPERTIES:\n:TITLE: file level\n:END:\n* a\ntext\nDEADLINE: <2023-01-01>\n:PROPERTIES:\n:A: 1\n:END:\n* b\n:PROPERTIES:\nnot a property\n:END:\n* c\n:PROPERTIES:\n:A: 1\n"),
+        @r###"
+    [
+        (
+            MisplacedPlanning,
+            "DEADLINE: <2023-01-01>",
+            "Misplaced planning info line",
+        ),
+        (
+            MalformedPropertyDrawer,
+            ":PROPERTIES:\n:A: 1\n:END:\n",
+            "Misplaced property drawer",
+        ),
+        (
+            MalformedPropertyDrawer,
+            ":PROPERTIES:\nnot a property\n:END:\n",
+            "Property drawer contains invalid lines",
+        ),
+        (
+            MalformedPropertyDrawer,
+            ":PROPERTIES:",
+            "Property drawer without :END: line",
+        ),
+    ]
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        lint("* a\n:PROPERTIES:\n:A: 1\n:END:\n:PROPERTIES:\n:B: 2\n:END:\n* b\n:properties:\n:A: 1\n:B+:\n:end:\n"),
+        @r###"
+    [
+        (
+            MalformedPropertyDrawer,
+            ":PROPERTIES:\n:B: 2\n:END:\n",
+            "Misplaced property drawer",
+        ),
+        (
+            MalformedPropertyDrawer,
+            ":properties:\n:A: 1\n:B+:\n:end:\n",
+            "Property drawer name should be upper case",
+        ),
+    ]
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        lint("* a\n:PROPERTIES:\n:id: 1\n:custom_id: a\n:END:\n* b\n:PROPERTIES:\n:ID: 1\n:END:\n[[#a]]"),
+        @r###"
+    [
+        (
+            DuplicateId,
+            "1",
+            "Duplicate ID property \"1\"",
+        ),
+    ]
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        lint("#+begin_src rust\n#+end_src\n#+begin_src brainfuck\n#+end_src\n#+begin_src\n#+end_src"),
+        @r###"
+    [
+        (
+            UnknownSrcLanguage,
+            "brainfuck",
+            "Unknown source block language \"brainfuck\"",
+        ),
+    ]
+    "###
+    );
+}
+
+#[test]
+fn toggle() {
+    let org = Org::parse("[[nope]] <2023-13-01>");
+    assert_eq!(org.lint().len(), 2);
+
+    let config = LintConfig {
+        rules: vec![LintRule::InvalidTimestamp],
+        ..Default::default()
+    };
+    let diagnostics = config.lint(&org);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, LintRule::InvalidTimestamp);
+
+    let config = LintConfig {
+        rules: vec![],
+        ..Default::default()
+    };
+    assert!(config.lint(&org).is_empty());
+}
@@ -2,15 +2,20 @@ use rowan::ast::AstNode;
 use rowan::GreenNode;
 
 use crate::ast::Document;
-use crate::config::ParseConfig;
-use crate::export::{HtmlExport, TraversalContext, Traverser};
-use crate::syntax::{OrgLanguage, SyntaxNode};
+use crate::babel::find_named;
+use crate::config::{ParseConfig, TodoKeyword, TodoSequence};
+use crate::export::{Events, HtmlDocument, HtmlExport, Numbering, TraversalContext, Traverser};
+use crate::format::FormatConfig;
+use crate::lint::{Diagnostic, LintConfig};
+use crate::syntax::{diagnostic::ParseDiagnostic, OrgLanguage, SyntaxNode};
 use crate::SyntaxElement;
 
 #[derive(Debug)]
 pub struct Org {
     pub(crate) green: GreenNode,
     pub(crate) config: ParseConfig,
+    pub(crate) diagnostics: Vec<ParseDiagnostic>,
+    pub(crate) todo_sequences: Vec<TodoSequence>,
 }
 
 impl Org {
@@ -23,10 +28,35 @@ impl Org {
         &self.green
     }
 
+    /// Returns config used while parsing
+    ///
+    /// Its [`todo_keywords`](ParseConfig::todo_keywords) are the ones actually
+    /// used, i.e. replaced by in-buffer `#+TODO:` settings if present.
     pub fn config(&self) -> &ParseConfig {
         &self.config
     }
 
+    /// Returns diagnostics collected while parsing, sorted by range
+    pub fn diagnostics(&self) -> &[ParseDiagnostic] {
+        &self.diagnostics
+    }
+
+    /// Returns todo keyword sequences used while parsing
+    ///
+    /// They come from in-buffer `#+TODO:`, `#+SEQ_TODO:` or `#+TYP_TODO:`
+    /// settings if present, otherwise from [`ParseConfig::todo_keywords`].
+    pub fn todo_sequences(&self) -> &[TodoSequence] {
+        &self.todo_sequences
+    }
+
+    /// Returns todo keyword with given name
+    pub fn todo_keyword(&self, name: &str) -> Option<&TodoKeyword> {
+        self.todo_sequences
+            .iter()
+            .flat_map(|seq| seq.todo.iter().chain(seq.done.iter()))
+            .find(|k| k.name == name)
+    }
+
     /// Returns the document
     pub fn document(&self) -> Document {
         Document {
@@ -39,6 +69,16 @@ impl Org {
         self.green.to_string()
     }
 
+    /// Returns formatted org-mode string using default format config
+    pub fn format(&self) -> String {
+        FormatConfig::default().format(self)
+    }
+
+    /// Returns lint diagnostics using default lint config
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        LintConfig::default().lint(self)
+    }
+
     /// Convert org element tree to html-format using default html handler
     pub fn to_html(&self) -> String {
         let mut handler = HtmlExport::default();
@@ -46,6 +86,12 @@ impl Org {
         handler.finish()
     }
 
+    /// Convert org element tree to a standalone html document using default
+    /// [`HtmlDocument`] config
+    pub fn to_html_document(&self) -> String {
+        HtmlDocument::default().export(self)
+    }
+
     /// Walk through org element tree using given traverser
     pub fn traverse<T: Traverser>(&self, t: &mut T) {
         let mut ctx = TraversalContext::default();
@@ -55,6 +101,32 @@ impl Org {
         );
     }
 
+    /// Returns an iterator over traversal events
+    ///
+    /// It's the pull-based counterpart of [`Org::traverse`], see [`Events`].
+    pub fn events(&self) -> Events {
+        Events::new(SyntaxNode::new_root(self.green.clone()))
+    }
+
+    /// Returns numbers of tables, figures, listings and equations, see [`Numbering`]
+    pub fn numbering(&self) -> Numbering {
+        Numbering::new(&SyntaxNode::new_root(self.green.clone()))
+    }
+
+    /// Returns the element with `#+NAME: name` affiliated keyword
+    ///
+    /// ```rust
+    /// use orgize::{rowan::ast::AstNode, ast::OrgTable, Org, SyntaxKind};
+    ///
+    /// let org = Org::parse("#+NAME: inventory\n| pen | 3 |\n\n#+name: note\n: text");
+    /// assert!(org.named("inventory").and_then(OrgTable::cast).is_some());
+    /// assert_eq!(org.named("note").unwrap().kind(), SyntaxKind::FIXED_WIDTH);
+    /// assert!(org.named("missing").is_none());
+    /// ```
+    pub fn named(&self, name: &str) -> Option<SyntaxNode> {
+        find_named(&SyntaxNode::new_root(self.green.clone()), name)
+    }
+
     /// Returns the first node in org element tree in depth first order
     pub fn first_node<N: AstNode<Language = OrgLanguage>>(&self) -> Option<N> {
         fn find<N: AstNode<Language = OrgLanguage>>(node: SyntaxNode) -> Option<N> {
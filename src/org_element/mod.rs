@@ -0,0 +1,342 @@
+//! Shape of `org-element-parse-buffer` output
+//!
+//! Maps syntax nodes to org-element types and properties, shared by the
+//! S-expression dump and the serde implementation.
+
+#[cfg(feature = "serde")]
+mod serialize;
+mod sexp;
+
+use rowan::ast::AstNode;
+
+use crate::{
+    ast::{
+        blank_lines, filter_token, BabelCall, Comment, Drawer, Entity, ExampleBlock, ExportBlock,
+        FixedWidth, FnDef, FnRef, Headline, InlineCall, InlineSrc, Keyword, Link, List, ListItem,
+        Macros, Snippet, SourceBlock, Timestamp, TodoType, Token,
+    },
+    SyntaxKind, SyntaxNode,
+};
+
+/// Property value of an element or object
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Property {
+    Number(usize),
+    String(String),
+    Strings(Vec<String>),
+    Bool(bool),
+}
+
+impl From<Token> for Property {
+    fn from(token: Token) -> Self {
+        Property::String(token.as_ref().to_string())
+    }
+}
+
+impl From<String> for Property {
+    fn from(s: String) -> Self {
+        Property::String(s)
+    }
+}
+
+impl From<&str> for Property {
+    fn from(s: &str) -> Self {
+        Property::String(s.to_string())
+    }
+}
+
+/// Converts byte offsets into 1-based character positions, which is how
+/// org-element reports `:begin` and `:end`
+pub(crate) struct Positions(Vec<usize>);
+
+impl Positions {
+    pub(crate) fn new(text: &str) -> Self {
+        Positions(text.char_indices().map(|(i, _)| i).collect())
+    }
+
+    /// Returns character position of byte `offset`
+    pub(crate) fn get(&self, offset: usize) -> usize {
+        self.0.partition_point(|&i| i < offset) + 1
+    }
+}
+
+/// Returns org-element type of node, e.g. `src-block` for [`SyntaxKind::SOURCE_BLOCK`]
+///
+/// Returns `None` for nodes that only exist in syntax tree, e.g. headline title
+/// or block begin line.
+pub(crate) fn element_type(kind: SyntaxKind) -> Option<&'static str> {
+    use SyntaxKind::*;
+
+    let ty = match kind {
+        DOCUMENT => "org-data",
+        SECTION => "section",
+        PARAGRAPH => "paragraph",
+        HEADLINE => "headline",
+        PROPERTY_DRAWER => "property-drawer",
+        NODE_PROPERTY => "node-property",
+        PLANNING => "planning",
+        ORG_TABLE | TABLE_EL => "table",
+        ORG_TABLE_RULE_ROW | ORG_TABLE_STANDARD_ROW => "table-row",
+        ORG_TABLE_CELL => "table-cell",
+        LIST => "plain-list",
+        LIST_ITEM => "item",
+        DRAWER => "drawer",
+        KEYWORD => "keyword",
+        BABEL_CALL => "babel-call",
+        CLOCK => "clock",
+        FN_DEF => "footnote-definition",
+        COMMENT => "comment",
+        RULE => "horizontal-rule",
+        FIXED_WIDTH => "fixed-width",
+        DYN_BLOCK => "dynamic-block",
+        SPECIAL_BLOCK => "special-block",
+        QUOTE_BLOCK => "quote-block",
+        CENTER_BLOCK => "center-block",
+        VERSE_BLOCK => "verse-block",
+        COMMENT_BLOCK => "comment-block",
+        EXAMPLE_BLOCK => "example-block",
+        EXPORT_BLOCK => "export-block",
+        SOURCE_BLOCK => "src-block",
+        LATEX_ENVIRONMENT => "latex-environment",
+        INLINE_CALL => "inline-babel-call",
+        INLINE_SRC => "inline-src-block",
+        LINK => "link",
+        LINE_BREAK => "line-break",
+        COOKIE => "statistics-cookie",
+        RADIO_TARGET => "radio-target",
+        FN_REF => "footnote-reference",
+        LATEX_FRAGMENT => "latex-fragment",
+        MACROS => "macro",
+        SNIPPET => "export-snippet",
+        TARGET => "target",
+        BOLD => "bold",
+        STRIKE => "strike-through",
+        ITALIC => "italic",
+        UNDERLINE => "underline",
+        VERBATIM => "verbatim",
+        CODE => "code",
+        ENTITY => "entity",
+        SUPERSCRIPT => "superscript",
+        SUBSCRIPT => "subscript",
+        TIMESTAMP_ACTIVE | TIMESTAMP_INACTIVE | TIMESTAMP_DIARY => "timestamp",
+        _ => return None,
+    };
+
+    Some(ty)
+}
+
+/// Returns properties of node, using org-element property names without the
+/// leading colon
+///
+/// `begin` and `end` are 1-based character positions, and `post-blank` is
+/// the number of trailing blank lines of elements.
+pub(crate) fn properties(
+    node: &SyntaxNode,
+    positions: &Positions,
+) -> Vec<(&'static str, Property)> {
+    let range = node.text_range();
+    let mut props = vec![
+        (
+            "begin",
+            Property::Number(positions.get(range.start().into())),
+        ),
+        ("end", Property::Number(positions.get(range.end().into()))),
+    ];
+
+    let kind = node.kind();
+    if element_type(kind).is_some() && !kind.is_object() {
+        props.push(("post-blank", Property::Number(blank_lines(node))));
+    }
+
+    macro_rules! push {
+        ($name:expr, $value:expr) => {
+            props.push(($name, Property::from($value)))
+        };
+    }
+
+    macro_rules! push_opt {
+        ($name:expr, $value:expr) => {
+            if let Some(value) = $value {
+                props.push(($name, Property::from(value)))
+            }
+        };
+    }
+
+    match kind {
+        SyntaxKind::HEADLINE => {
+            let headline = Headline::cast(node.clone()).unwrap();
+            props.push(("level", Property::Number(headline.level())));
+            push!("raw-value", headline.title_raw().trim().to_string());
+            push_opt!("todo-keyword", headline.todo_keyword());
+            push_opt!(
+                "todo-type",
+                headline.todo_type().map(|ty| match ty {
+                    TodoType::Todo => "todo",
+                    TodoType::Done => "done",
+                })
+            );
+            push_opt!("priority", headline.priority());
+            props.push((
+                "tags",
+                Property::Strings(headline.tags().map(|t| t.as_ref().to_string()).collect()),
+            ));
+            props.push(("archivedp", Property::Bool(headline.is_archived())));
+            props.push(("commentedp", Property::Bool(headline.is_commented())));
+        }
+        SyntaxKind::NODE_PROPERTY => {
+            let mut texts = node
+                .children_with_tokens()
+                .filter_map(filter_token(SyntaxKind::TEXT));
+            push_opt!("key", texts.next());
+            push!("value", texts.next().unwrap_or_default());
+        }
+        SyntaxKind::ORG_TABLE_RULE_ROW => push!("type", "rule"),
+        SyntaxKind::ORG_TABLE_STANDARD_ROW => push!("type", "standard"),
+        SyntaxKind::LIST => {
+            let list = List::cast(node.clone()).unwrap();
+            let ty = if list.is_descriptive() {
+                "descriptive"
+            } else if list.is_ordered() {
+                "ordered"
+            } else {
+                "unordered"
+            };
+            push!("type", ty);
+        }
+        SyntaxKind::LIST_ITEM => {
+            let item = ListItem::cast(node.clone()).unwrap();
+            push!("bullet", item.bullet());
+            push_opt!(
+                "checkbox",
+                item.checkbox().map(|c| match c.as_ref() {
+                    "X" | "x" => "on",
+                    "-" => "trans",
+                    _ => "off",
+                })
+            );
+            if let Some(counter) = item.counter() {
+                props.push((
+                    "counter",
+                    match counter.as_ref().parse() {
+                        Ok(n) => Property::Number(n),
+                        Err(_) => Property::from(counter),
+                    },
+                ));
+            }
+        }
+        SyntaxKind::DRAWER => push!("drawer-name", Drawer::cast(node.clone()).unwrap().name()),
+        SyntaxKind::KEYWORD => {
+            let keyword = Keyword::cast(node.clone()).unwrap();
+            push!("key", keyword.key().as_ref().to_ascii_uppercase());
+            push!("value", keyword.value().as_ref().trim());
+        }
+        SyntaxKind::BABEL_CALL => {
+            let call = BabelCall::cast(node.clone()).unwrap();
+            push!("call", call.call());
+            push!("value", call.value());
+        }
+        SyntaxKind::FN_DEF => push!("label", FnDef::cast(node.clone()).unwrap().label()),
+        SyntaxKind::COMMENT => push!("value", Comment::cast(node.clone()).unwrap().value()),
+        SyntaxKind::FIXED_WIDTH => push!("value", FixedWidth::cast(node.clone()).unwrap().value()),
+        SyntaxKind::EXAMPLE_BLOCK => {
+            push!("value", ExampleBlock::cast(node.clone()).unwrap().value())
+        }
+        SyntaxKind::EXPORT_BLOCK => {
+            let block = ExportBlock::cast(node.clone()).unwrap();
+            push_opt!("type", block.ty().map(|t| t.as_ref().to_ascii_uppercase()));
+        }
+        SyntaxKind::SOURCE_BLOCK => {
+            let block = SourceBlock::cast(node.clone()).unwrap();
+            push_opt!("language", block.language());
+            push_opt!("switches", block.switches());
+            push_opt!("parameters", block.parameters());
+            push!("value", block.value());
+        }
+        SyntaxKind::INLINE_CALL => push!("call", InlineCall::cast(node.clone()).unwrap().call()),
+        SyntaxKind::INLINE_SRC => {
+            let src = InlineSrc::cast(node.clone()).unwrap();
+            push!("language", src.language());
+            push_opt!("parameters", src.parameters());
+            push!("value", src.value());
+        }
+        SyntaxKind::LINK => {
+            let link = Link::cast(node.clone()).unwrap();
+            let path = link.path();
+            let (ty, value) = match path.as_ref().split_once(':') {
+                Some((ty, value))
+                    if !ty.is_empty() && ty.chars().all(|c| c.is_ascii_alphanumeric()) =>
+                {
+                    (ty.to_string(), value.to_string())
+                }
+                _ => ("fuzzy".to_string(), path.as_ref().to_string()),
+            };
+            push!("type", ty);
+            push!("path", value);
+            push!("raw-link", path);
+        }
+        SyntaxKind::FN_REF => {
+            let label = FnRef::cast(node.clone()).unwrap().label();
+            if !label.as_ref().is_empty() {
+                push!("label", label);
+            }
+        }
+        SyntaxKind::MACROS => {
+            let macros = Macros::cast(node.clone()).unwrap();
+            push!("key", macros.key());
+            if let Some(args) = macros.args() {
+                props.push((
+                    "args",
+                    Property::Strings(args.as_ref().split(',').map(String::from).collect()),
+                ));
+            }
+        }
+        SyntaxKind::SNIPPET => {
+            let snippet = Snippet::cast(node.clone()).unwrap();
+            push!("back-end", snippet.backend());
+            push!("value", snippet.value());
+        }
+        SyntaxKind::ENTITY => push!("name", Entity::cast(node.clone()).unwrap().name()),
+        SyntaxKind::TIMESTAMP_ACTIVE
+        | SyntaxKind::TIMESTAMP_INACTIVE
+        | SyntaxKind::TIMESTAMP_DIARY => {
+            let ts = Timestamp::cast(node.clone()).unwrap();
+            let ty = match (kind, ts.is_range()) {
+                (SyntaxKind::TIMESTAMP_DIARY, _) => "diary",
+                (SyntaxKind::TIMESTAMP_ACTIVE, false) => "active",
+                (SyntaxKind::TIMESTAMP_ACTIVE, true) => "active-range",
+                (_, false) => "inactive",
+                (_, true) => "inactive-range",
+            };
+            push!("type", ty);
+            push!("raw-value", node.to_string().trim_end().to_string());
+            for (name, token) in [
+                ("year-start", ts.year_start()),
+                ("month-start", ts.month_start()),
+                ("day-start", ts.day_start()),
+                ("hour-start", ts.hour_start()),
+                ("minute-start", ts.minute_start()),
+                ("year-end", ts.year_end()),
+                ("month-end", ts.month_end()),
+                ("day-end", ts.day_end()),
+                ("hour-end", ts.hour_end()),
+                ("minute-end", ts.minute_end()),
+            ] {
+                if let Some(n) = token.and_then(|t| t.as_ref().parse().ok()) {
+                    props.push((name, Property::Number(n)));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(name) = node
+        .children()
+        .filter_map(crate::ast::AffiliatedKeyword::cast)
+        .find(|k| k.key().eq_ignore_ascii_case("NAME"))
+        .and_then(|k| k.value())
+    {
+        push!("name", name.as_ref().trim());
+    }
+
+    props
+}
@@ -0,0 +1,439 @@
+use std::fmt;
+
+use serde::{
+    de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use super::{element_type, properties, Positions, Property};
+use crate::{Org, SyntaxElement, SyntaxKind, SyntaxNode};
+
+/// Serializes syntax tree in the shape of `org-element-parse-buffer` output
+///
+/// Every node is serialized as an object with three fields:
+///
+/// - `type`: org-element type, e.g. `headline`, `src-block` or `bold`. Nodes
+///   without an org-element counterpart use their lowercased syntax kind, e.g.
+///   `headline-title` or `block-begin`.
+/// - `properties`: org-element properties without the leading colon, e.g.
+///   `level`, `todo-keyword` and `tags` of headlines. All nodes have `begin`
+///   and `end` as 1-based character positions, like in org-element and
+///   [`Org::to_org_element_sexp`], and elements have `post-blank`.
+/// - `children`: child nodes and strings of source text in order
+///
+/// Concatenating all strings in `children` gives the original source.
+///
+/// ```rust
+/// use orgize::Org;
+///
+/// let org = Org::parse("* TODO hello :tag:");
+/// let json = serde_json::to_value(&org).unwrap();
+///
+/// let headline = &json["children"][0];
+/// assert_eq!(headline["type"], "headline");
+/// assert_eq!(headline["properties"]["level"], 1);
+/// assert_eq!(headline["properties"]["todo-keyword"], "TODO");
+/// assert_eq!(headline["properties"]["tags"][0], "tag");
+/// assert_eq!(headline["children"][0], "*");
+/// ```
+impl Serialize for Org {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let root = self.document().syntax;
+        let positions = Positions::new(&root.to_string());
+        Node(&root, &positions).serialize(serializer)
+    }
+}
+
+/// Deserializes syntax tree serialized by [`Org::serialize`]
+///
+/// Only strings in `children` are read, they're concatenated and parsed
+/// with the default [`ParseConfig`](crate::ParseConfig). Types and properties
+/// are ignored.
+///
+/// ```rust
+/// use orgize::Org;
+///
+/// let org = Org::parse("* TODO hello :tag:\n#+begin_src rust\nfn main() {}\n#+end_src\n");
+/// let json = serde_json::to_string(&org).unwrap();
+/// let org2: Org = serde_json::from_str(&json).unwrap();
+/// assert_eq!(org2.to_org(), org.to_org());
+/// ```
+impl<'de> Deserialize<'de> for Org {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut source = String::new();
+        Text(&mut source).deserialize(deserializer)?;
+        Ok(Org::parse(source))
+    }
+}
+
+struct Node<'a>(&'a SyntaxNode, &'a Positions);
+
+impl Serialize for Node<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let kind = self.0.kind();
+        let mut map = serializer.serialize_map(Some(3))?;
+        match element_type(kind) {
+            Some(ty) => map.serialize_entry("type", ty)?,
+            None => map.serialize_entry("type", &syntax_type(kind))?,
+        }
+        map.serialize_entry("properties", &Properties(properties(self.0, self.1)))?;
+        map.serialize_entry("children", &Children(self.0, self.1))?;
+        map.end()
+    }
+}
+
+struct Properties(Vec<(&'static str, Property)>);
+
+impl Serialize for Properties {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, value) in &self.0 {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for Property {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Property::Number(n) => serializer.serialize_u64(*n as u64),
+            Property::String(s) => serializer.serialize_str(s),
+            Property::Strings(s) => s.serialize(serializer),
+            Property::Bool(b) => serializer.serialize_bool(*b),
+        }
+    }
+}
+
+struct Children<'a>(&'a SyntaxNode, &'a Positions);
+
+impl Serialize for Children<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        for child in self.0.children_with_tokens() {
+            match child {
+                SyntaxElement::Node(node) => seq.serialize_element(&Node(&node, self.1))?,
+                SyntaxElement::Token(token) => seq.serialize_element(token.text())?,
+            }
+        }
+        seq.end()
+    }
+}
+
+/// Returns type name of syntax-only node, e.g. `headline-title` for
+/// [`SyntaxKind::HEADLINE_TITLE`]
+fn syntax_type(kind: SyntaxKind) -> String {
+    format!("{kind:?}").to_ascii_lowercase().replace('_', "-")
+}
+
+/// Collects source text of a serialized node or string
+struct Text<'a>(&'a mut String);
+
+impl<'de> DeserializeSeed<'de> for Text<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Text<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an org element or a string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<(), E> {
+        self.0.push_str(v);
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "children" {
+                map.next_value_seed(TextSeq(&mut *self.0))?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct TextSeq<'a>(&'a mut String);
+
+impl<'de> DeserializeSeed<'de> for TextSeq<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for TextSeq<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of org elements or strings")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while seq.next_element_seed(Text(&mut *self.0))?.is_some() {}
+        Ok(())
+    }
+}
+
+#[test]
+fn serialize() {
+    let org = Org::parse("* DONE [#A] hello *world* :a:b:\n[2023-01-01 Sun]\n\n- [X] item\n");
+
+    insta::assert_snapshot!(serde_json::to_string_pretty(&org).unwrap(), @r###"
+    {
+      "type": "org-data",
+      "properties": {
+        "begin": 1,
+        "end": 62,
+        "post-blank": 0
+      },
+      "children": [
+        {
+          "type": "headline",
+          "properties": {
+            "begin": 1,
+            "end": 62,
+            "post-blank": 0,
+            "level": 1,
+            "raw-value": "hello *world*",
+            "todo-keyword": "DONE",
+            "todo-type": "done",
+            "priority": "A",
+            "tags": [
+              "a",
+              "b"
+            ],
+            "archivedp": false,
+            "commentedp": false
+          },
+          "children": [
+            "*",
+            " ",
+            "DONE",
+            " ",
+            {
+              "type": "headline-priority",
+              "properties": {
+                "begin": 8,
+                "end": 12
+              },
+              "children": [
+                "[",
+                "#",
+                "A",
+                "]"
+              ]
+            },
+            " ",
+            {
+              "type": "headline-title",
+              "properties": {
+                "begin": 13,
+                "end": 27
+              },
+              "children": [
+                "hello ",
+                {
+                  "type": "bold",
+                  "properties": {
+                    "begin": 19,
+                    "end": 26
+                  },
+                  "children": [
+                    "*",
+                    "world",
+                    "*"
+                  ]
+                },
+                " "
+              ]
+            },
+            {
+              "type": "headline-tags",
+              "properties": {
+                "begin": 27,
+                "end": 32
+              },
+              "children": [
+                ":",
+                "a",
+                ":",
+                "b",
+                ":"
+              ]
+            },
+            "\n",
+            {
+              "type": "section",
+              "properties": {
+                "begin": 33,
+                "end": 62,
+                "post-blank": 0
+              },
+              "children": [
+                {
+                  "type": "paragraph",
+                  "properties": {
+                    "begin": 33,
+                    "end": 51,
+                    "post-blank": 1
+                  },
+                  "children": [
+                    {
+                      "type": "timestamp",
+                      "properties": {
+                        "begin": 33,
+                        "end": 49,
+                        "type": "inactive",
+                        "raw-value": "[2023-01-01 Sun]",
+                        "year-start": 2023,
+                        "month-start": 1,
+                        "day-start": 1,
+                        "year-end": 2023,
+                        "month-end": 1,
+                        "day-end": 1
+                      },
+                      "children": [
+                        "[",
+                        "2023",
+                        "-",
+                        "01",
+                        "-",
+                        "01",
+                        " ",
+                        "Sun",
+                        "]"
+                      ]
+                    },
+                    "\n",
+                    "\n"
+                  ]
+                },
+                {
+                  "type": "plain-list",
+                  "properties": {
+                    "begin": 51,
+                    "end": 62,
+                    "post-blank": 0,
+                    "type": "unordered"
+                  },
+                  "children": [
+                    {
+                      "type": "item",
+                      "properties": {
+                        "begin": 51,
+                        "end": 62,
+                        "post-blank": 0,
+                        "bullet": "- ",
+                        "checkbox": "on"
+                      },
+                      "children": [
+                        "",
+                        "- ",
+                        {
+                          "type": "list-item-check-box",
+                          "properties": {
+                            "begin": 53,
+                            "end": 56
+                          },
+                          "children": [
+                            "[",
+                            "X",
+                            "]"
+                          ]
+                        },
+                        " ",
+                        {
+                          "type": "list-item-content",
+                          "properties": {
+                            "begin": 57,
+                            "end": 62
+                          },
+                          "children": [
+                            {
+                              "type": "paragraph",
+                              "properties": {
+                                "begin": 57,
+                                "end": 62,
+                                "post-blank": 0
+                              },
+                              "children": [
+                                "item\n"
+                              ]
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }
+      ]
+    }
+    "###);
+
+    let json = serde_json::to_string(&org).unwrap();
+    let org2: Org = serde_json::from_str(&json).unwrap();
+    assert_eq!(org2.to_org(), org.to_org());
+
+    let org = Org::parse(
+        r#"#+TITLE: round trip
+* COMMENT h :ARCHIVE:
+:PROPERTIES:
+:ID: 1
+:END:
+#+NAME: src
+#+begin_src rust -n :tangle no
+fn main() {}
+#+end_src
+#+CALL: src()
+
+| a | [[https://example.com][link]] |
+|---+------|
+| \alpha | src_sh{echo 1} call_src() |
+
+1. [-] {{{macro(x)}}} @@html:<br>@@ [fn:1] <<target>> <<<radio>>> [1/2]
+   : fixed
+#+begin_export html
+<p></p>
+#+end_export
+[fn:1] footnote
+<2023-01-01 Sun>--<2023-01-02 Mon>
+"#,
+    );
+    let json = serde_json::to_string(&org).unwrap();
+    let org2: Org = serde_json::from_str(&json).unwrap();
+    assert_eq!(org2.to_org(), org.to_org());
+
+    assert!(serde_json::from_str::<Org>("[1]").is_err());
+}
+
+#[test]
+fn positions() {
+    // positions count characters, like in org-element and the sexp dump
+    let org = Org::parse("* 你好 *世界*\n");
+    let json = serde_json::to_value(&org).unwrap();
+
+    let headline = &json["children"][0];
+    assert_eq!(headline["properties"]["begin"], 1);
+    assert_eq!(headline["properties"]["end"], 11);
+    let bold = &headline["children"][2]["children"][1];
+    assert_eq!(bold["type"], "bold");
+    assert_eq!(bold["properties"]["begin"], 6);
+    assert!(org
+        .to_org_element_sexp()
+        .contains("(headline (:begin 1 :end 11 "));
+}
@@ -0,0 +1,304 @@
+use std::fmt::Write;
+
+use super::{element_type, properties, Positions, Property};
+use crate::{Org, SyntaxElement, SyntaxKind, SyntaxNode};
+
+impl Org {
+    /// Dumps syntax tree as an S-expression in the shape of `(org-element-parse-buffer)`
+    ///
+    /// Each element or object is printed as `(TYPE PROPERTIES CONTENTS...)`,
+    /// one per line and indented by depth. Properties include `:begin` and
+    /// `:end` as 1-based character positions, `:post-blank` of elements and
+    /// the type-specific properties listed in `tests/org-element/dump.el`.
+    /// Contents are elements, objects and plain text strings, as returned by
+    /// `org-element-contents`.
+    ///
+    /// The same output can be produced in Emacs with `tests/org-element/dump.el`,
+    /// which is used to compare this parser with org-element.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("* TODO hello\nsome *bold* text");
+    /// assert_eq!(
+    ///     org.to_org_element_sexp(),
+    ///     r#"(org-data (:begin 1 :end 30 :post-blank 0)
+    ///   (headline (:begin 1 :end 30 :post-blank 0 :level 1 :raw-value "hello" :todo-keyword "TODO" :todo-type "todo")
+    ///     (section (:begin 14 :end 30 :post-blank 0)
+    ///       (paragraph (:begin 14 :end 30 :post-blank 0)
+    ///         "some "
+    ///         (bold (:begin 19 :end 25)
+    ///           "bold")
+    ///         " text"))))"#
+    /// );
+    /// ```
+    pub fn to_org_element_sexp(&self) -> String {
+        let root = self.document().syntax;
+        let mut printer = Printer {
+            positions: Positions::new(&root.to_string()),
+            output: String::new(),
+        };
+        printer.node(&root, "org-data", 0);
+        printer.output
+    }
+}
+
+struct Printer {
+    positions: Positions,
+    output: String,
+}
+
+enum Content {
+    Node(SyntaxNode, &'static str),
+    Text(String),
+}
+
+impl Printer {
+    fn node(&mut self, node: &SyntaxNode, ty: &str, depth: usize) {
+        let _ = write!(&mut self.output, "({ty} ");
+        self.properties(node);
+
+        for content in contents(node) {
+            self.output.push('\n');
+            for _ in 0..=depth {
+                self.output.push_str("  ");
+            }
+            match content {
+                Content::Node(node, ty) => self.node(&node, ty, depth + 1),
+                Content::Text(text) => quote(&mut self.output, &text),
+            }
+        }
+
+        self.output.push(')');
+    }
+
+    fn properties(&mut self, node: &SyntaxNode) {
+        let properties: Vec<_> = properties(node, &self.positions)
+            .into_iter()
+            .filter(|(_, value)| match value {
+                Property::Bool(b) => *b,
+                Property::Strings(s) => !s.is_empty(),
+                _ => true,
+            })
+            .collect();
+
+        if properties.is_empty() {
+            self.output.push_str("nil");
+            return;
+        }
+
+        self.output.push('(');
+        for (idx, (name, value)) in properties.into_iter().enumerate() {
+            if idx > 0 {
+                self.output.push(' ');
+            }
+            let _ = write!(&mut self.output, ":{name} ");
+            match value {
+                Property::Number(n) => {
+                    let _ = write!(&mut self.output, "{n}");
+                }
+                Property::String(s) => quote(&mut self.output, &s),
+                Property::Strings(s) => {
+                    self.output.push('(');
+                    for (idx, s) in s.iter().enumerate() {
+                        if idx > 0 {
+                            self.output.push(' ');
+                        }
+                        quote(&mut self.output, s);
+                    }
+                    self.output.push(')');
+                }
+                Property::Bool(_) => self.output.push('t'),
+            }
+        }
+        self.output.push(')');
+    }
+}
+
+/// Writes string in the same way as `prin1`
+fn quote(output: &mut String, s: &str) {
+    output.push('"');
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+    output.push('"');
+}
+
+/// Returns contents of element or object, i.e. `org-element-contents`
+fn contents(node: &SyntaxNode) -> Vec<Content> {
+    use SyntaxKind::*;
+
+    let children: Vec<SyntaxElement> = node.children_with_tokens().collect();
+
+    let range: &[SyntaxElement] = match node.kind() {
+        // greater elements, which contain elements only
+        DOCUMENT
+        | SECTION
+        | HEADLINE
+        | LIST
+        | LIST_ITEM
+        | DRAWER
+        | PROPERTY_DRAWER
+        | DYN_BLOCK
+        | QUOTE_BLOCK
+        | CENTER_BLOCK
+        | SPECIAL_BLOCK
+        | ORG_TABLE
+        | ORG_TABLE_STANDARD_ROW => {
+            let mut contents = vec![];
+            elements(node, &mut contents);
+            return contents;
+        }
+
+        PARAGRAPH | ORG_TABLE_CELL => &children,
+        BOLD | ITALIC | UNDERLINE | STRIKE | RADIO_TARGET if children.len() >= 2 => {
+            &children[1..children.len() - 1]
+        }
+        SUPERSCRIPT | SUBSCRIPT => {
+            if children.get(1).map(|c| c.kind()) == Some(L_CURLY) {
+                &children[2..children.len() - 1]
+            } else {
+                &children[1..]
+            }
+        }
+        LINK => match children.iter().position(|c| c.kind() == L_BRACKET) {
+            Some(start) if children.last().map(|c| c.kind()) == Some(R_BRACKET2) => {
+                &children[start + 1..children.len() - 1]
+            }
+            _ => &[],
+        },
+        FN_REF => match children
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.kind() == COLON)
+            .nth(1)
+        {
+            Some((i, _)) => &children[i + 1..children.len() - 1],
+            None => &[],
+        },
+        FN_DEF => match children.iter().position(|c| c.kind() == R_BRACKET) {
+            Some(start) => &children[start + 1..],
+            None => &[],
+        },
+        VERSE_BLOCK => {
+            let contents = node.children().find(|n| n.kind() == BLOCK_CONTENT);
+            return objects(
+                &contents
+                    .map(|n| n.children_with_tokens().collect::<Vec<_>>())
+                    .unwrap_or_default(),
+            );
+        }
+
+        // elements and objects without contents
+        _ => &[],
+    };
+
+    objects(range)
+}
+
+/// Collects child elements, descending into syntax-only content nodes
+fn elements(node: &SyntaxNode, contents: &mut Vec<Content>) {
+    for child in node.children() {
+        match element_type(child.kind()) {
+            Some(ty) => contents.push(Content::Node(child, ty)),
+            None if matches!(
+                child.kind(),
+                SyntaxKind::DRAWER_CONTENT
+                    | SyntaxKind::LIST_ITEM_CONTENT
+                    | SyntaxKind::BLOCK_CONTENT
+            ) =>
+            {
+                elements(&child, contents)
+            }
+            None => {}
+        }
+    }
+}
+
+/// Converts objects and tokens into contents, merging adjacent text
+fn objects(children: &[SyntaxElement]) -> Vec<Content> {
+    let mut contents = vec![];
+    for child in children {
+        if matches!(
+            child.kind(),
+            SyntaxKind::AFFILIATED_KEYWORD | SyntaxKind::BLANK_LINE
+        ) {
+            continue;
+        }
+
+        let ty = match child {
+            SyntaxElement::Node(node) => element_type(node.kind()).map(|ty| (node, ty)),
+            SyntaxElement::Token(_) => None,
+        };
+
+        match (ty, contents.last_mut()) {
+            (Some((node, ty)), _) => contents.push(Content::Node(node.clone(), ty)),
+            (None, Some(Content::Text(text))) => text.push_str(&child.to_string()),
+            (None, _) => contents.push(Content::Text(child.to_string())),
+        }
+    }
+    contents.retain(|c| !matches!(c, Content::Text(t) if t.is_empty()));
+    contents
+}
+
+#[test]
+fn sexp() {
+    let org = Org::parse(
+        r#"#+TITLE: sexp
+* DONE [#A] heading :a:b:
+:PROPERTIES:
+:ID: 1
+:END:
+#+NAME: quote
+#+begin_quote
+[[https://example.com][a *link*]] x^{2} \alpha "quoted" [fn::inline]
+#+end_quote
+
+3. [@3] [X] {{{kbd(C-c, C-c)}}}
+   | a | b |
+   |---+---|
+"#,
+    );
+
+    insta::assert_snapshot!(org.to_org_element_sexp(), @r###"
+    (org-data (:begin 1 :end 235 :post-blank 0)
+      (section (:begin 1 :end 15 :post-blank 0)
+        (keyword (:begin 1 :end 15 :post-blank 0 :key "TITLE" :value "sexp")))
+      (headline (:begin 15 :end 235 :post-blank 0 :level 1 :raw-value "heading" :todo-keyword "DONE" :todo-type "done" :priority "A" :tags ("a" "b"))
+        (property-drawer (:begin 41 :end 67 :post-blank 0)
+          (node-property (:begin 54 :end 61 :post-blank 0 :key "ID" :value "1")))
+        (section (:begin 67 :end 235 :post-blank 0)
+          (quote-block (:begin 67 :end 177 :post-blank 1 :name "quote")
+            (paragraph (:begin 95 :end 164 :post-blank 0)
+              (link (:begin 95 :end 128 :type "https" :path "//example.com" :raw-link "https://example.com")
+                "a "
+                (bold (:begin 120 :end 126)
+                  "link"))
+              " x"
+              (superscript (:begin 130 :end 134)
+                "2")
+              " "
+              (entity (:begin 135 :end 141 :name "alpha"))
+              " \"quoted\" "
+              (footnote-reference (:begin 151 :end 163)
+                "inline")
+              "
+    "))
+          (plain-list (:begin 177 :end 235 :post-blank 0 :type "ordered")
+            (item (:begin 177 :end 235 :post-blank 0 :bullet "3. " :checkbox "on" :counter 3)
+              (paragraph (:begin 189 :end 209 :post-blank 0)
+                (macro (:begin 189 :end 208 :key "kbd" :args ("C-c" " C-c")))
+                "
+    ")
+              (table (:begin 209 :end 235 :post-blank 0)
+                (table-row (:begin 209 :end 222 :post-blank 0 :type "standard")
+                  (table-cell (:begin 214 :end 215)
+                    "a")
+                  (table-cell (:begin 218 :end 219)
+                    "b"))
+                (table-row (:begin 222 :end 235 :post-blank 0 :type "rule"))))))))
+    "###);
+}
@@ -0,0 +1,776 @@
+//! Streaming parser, which emits events line by line without building syntax tree
+//!
+//! It's meant for tasks like indexing large archives, where only headlines,
+//! properties, keywords and timestamps are needed. Events borrow from input,
+//! and nothing but small per-line state is kept between lines.
+//!
+//! Only headlines, planning lines, property drawers, keywords and blocks are
+//! recognized, everything else is emitted as [`StreamEvent::Line`]. Timestamps
+//! and regular links are emitted as objects, see [`Stream::section_objects`].
+//!
+//! ```rust
+//! use orgize::{stream::StreamEvent, ParseConfig};
+//!
+//! let config = ParseConfig::default();
+//! let mut stream = config.stream("* TODO task :work:\nSCHEDULED: <2023-01-01 Sun>\nsee [[https://example.com][here]]");
+//!
+//! let Some(StreamEvent::Headline(headline)) = stream.next() else { unreachable!() };
+//! assert_eq!(headline.level, 1);
+//! assert_eq!(headline.keyword, Some("TODO"));
+//! assert_eq!(headline.title, "task");
+//! assert_eq!(headline.tags().collect::<Vec<_>>(), ["work"]);
+//!
+//! assert_eq!(
+//!     stream.collect::<Vec<_>>(),
+//!     [
+//!         StreamEvent::Planning { keyword: "SCHEDULED", timestamp: "<2023-01-01 Sun>" },
+//!         StreamEvent::Line("see [[https://example.com][here]]"),
+//!         StreamEvent::Link { path: "https://example.com", description: Some("here") },
+//!     ]
+//! );
+//! ```
+
+use std::{io, io::BufRead, ops::Range};
+
+use memchr::memchr2;
+
+use crate::{
+    config::TodoSequence,
+    syntax::{
+        input::Input,
+        timestamp::{timestamp_active_node, timestamp_diary_node, timestamp_inactive_node},
+    },
+    ParseConfig,
+};
+
+/// Event emitted by [`Stream`] and [`StreamReader`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StreamEvent<'a> {
+    /// Headline line
+    Headline(StreamHeadline<'a>),
+    /// Entry of planning line, e.g. `DEADLINE: <2023-01-01 Sun>`
+    Planning {
+        keyword: &'a str,
+        timestamp: &'a str,
+    },
+    /// Node property in property drawer, e.g. `:ID: 1`
+    Property { key: &'a str, value: &'a str },
+    /// Keyword, e.g. `#+TITLE: hello`
+    Keyword { key: &'a str, value: &'a str },
+    /// Any other line, without line ending
+    Line(&'a str),
+    /// Timestamp object, including ranges like `<2023-01-01>--<2023-01-02>`
+    Timestamp(&'a str),
+    /// Regular link object, e.g. `[[https://example.com][example]]`
+    Link {
+        path: &'a str,
+        description: Option<&'a str>,
+    },
+}
+
+/// Headline line emitted by [`Stream`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamHeadline<'a> {
+    /// Number of stars
+    pub level: usize,
+    /// Todo keyword, e.g. `TODO`
+    pub keyword: Option<&'a str>,
+    /// Priority cookie, e.g. `A` of `[#A]`
+    pub priority: Option<char>,
+    /// Raw title, without keyword, priority and tags
+    pub title: &'a str,
+    tags: &'a str,
+}
+
+impl<'a> StreamHeadline<'a> {
+    /// Returns tags of headline, e.g. `a` and `b` of `:a:b:`
+    pub fn tags(&self) -> impl Iterator<Item = &'a str> {
+        self.tags.split(':').filter(|tag| !tag.is_empty())
+    }
+}
+
+impl ParseConfig {
+    /// Returns a streaming parser of input, see [`stream`](crate::stream)
+    ///
+    /// Todo keywords declared by `#+TODO:`, `#+SEQ_TODO:` or `#+TYP_TODO:`
+    /// take effect from the next line, so they should come before headlines.
+    /// Like [`ParseConfig::parse`], they replace [`ParseConfig::todo_keywords`]
+    /// once the first sequence is declared.
+    pub fn stream<'a>(&self, input: &'a str) -> Stream<'a> {
+        Stream {
+            rest: input,
+            line: "",
+            scanner: Scanner::new(self.clone()),
+        }
+    }
+
+    /// Returns a streaming parser reading input from `reader`, see [`StreamReader`]
+    pub fn stream_reader<R: BufRead>(&self, reader: R) -> StreamReader<R> {
+        StreamReader {
+            reader,
+            line: String::new(),
+            scanner: Scanner::new(self.clone()),
+        }
+    }
+}
+
+/// Streaming parser of `&str`, returned by [`ParseConfig::stream`]
+pub struct Stream<'a> {
+    rest: &'a str,
+    line: &'a str,
+    scanner: Scanner,
+}
+
+impl<'a> Stream<'a> {
+    /// Sets whether objects in sections are parsed, defaults to `true`
+    ///
+    /// When it's `false`, section lines are only emitted as [`StreamEvent::Line`].
+    /// Timestamps and links in headline titles and planning lines are always emitted.
+    ///
+    /// ```rust
+    /// use orgize::{stream::StreamEvent, ParseConfig};
+    ///
+    /// let config = ParseConfig::default();
+    /// let input = "* meeting <2023-01-01 Sun>\nnotes [2023-01-02 Mon]";
+    ///
+    /// let timestamps = |objects| {
+    ///     config
+    ///         .stream(input)
+    ///         .section_objects(objects)
+    ///         .filter(|event| matches!(event, StreamEvent::Timestamp(_)))
+    ///         .count()
+    /// };
+    /// assert_eq!(timestamps(true), 2);
+    /// assert_eq!(timestamps(false), 1);
+    /// ```
+    pub fn section_objects(mut self, yes: bool) -> Self {
+        self.scanner.section_objects = yes;
+        self
+    }
+}
+
+impl<'a> Iterator for Stream<'a> {
+    type Item = StreamEvent<'a>;
+
+    fn next(&mut self) -> Option<StreamEvent<'a>> {
+        loop {
+            if let Some(raw) = self.scanner.step(self.line) {
+                return Some(raw.resolve(self.line));
+            }
+
+            if self.rest.is_empty() {
+                return None;
+            }
+
+            let (line, rest) = match self.rest.find('\n') {
+                Some(i) => (&self.rest[..i], &self.rest[i + 1..]),
+                None => (self.rest, ""),
+            };
+            self.line = line.strip_suffix('\r').unwrap_or(line);
+            self.rest = rest;
+            self.scanner.next_line();
+        }
+    }
+}
+
+/// Streaming parser reading from [`BufRead`], returned by [`ParseConfig::stream_reader`]
+///
+/// Input is read one line at a time and events borrow from the current line,
+/// so it's not an [`Iterator`]. Call [`StreamReader::next_event`] in a loop instead.
+///
+/// ```rust
+/// use orgize::{stream::StreamEvent, ParseConfig};
+///
+/// let input = "#+TODO: NEXT | DONE\n* NEXT a\n** DONE b :x:\n";
+/// let mut reader = ParseConfig::default().stream_reader(input.as_bytes());
+///
+/// let mut headlines = vec![];
+/// while let Some(event) = reader.next_event().unwrap() {
+///     if let StreamEvent::Headline(headline) = event {
+///         headlines.push((headline.level, headline.keyword.map(String::from)));
+///     }
+/// }
+/// assert_eq!(headlines, [(1, Some("NEXT".into())), (2, Some("DONE".into()))]);
+/// ```
+pub struct StreamReader<R> {
+    reader: R,
+    line: String,
+    scanner: Scanner,
+}
+
+impl<R: BufRead> StreamReader<R> {
+    /// Sets whether objects in sections are parsed, see [`Stream::section_objects`]
+    pub fn section_objects(mut self, yes: bool) -> Self {
+        self.scanner.section_objects = yes;
+        self
+    }
+
+    /// Returns next event, or `None` at the end of input
+    pub fn next_event(&mut self) -> io::Result<Option<StreamEvent<'_>>> {
+        loop {
+            if let Some(raw) = self.scanner.step(&self.line) {
+                return Ok(Some(raw.resolve(&self.line)));
+            }
+
+            self.line.clear();
+            if self.reader.read_line(&mut self.line)? == 0 {
+                return Ok(None);
+            }
+            let len = self.line.trim_end_matches(['\n', '\r']).len();
+            self.line.truncate(len);
+            self.scanner.next_line();
+        }
+    }
+}
+
+/// Where the current line is located
+#[derive(Debug, PartialEq, Eq)]
+enum Context {
+    /// Right after a headline, where planning line can appear
+    Headline,
+    /// Right after a headline or planning line, where property drawer can appear
+    Planning,
+    /// Inside property drawer
+    Properties,
+    /// Inside block, whose name is kept in `Scanner::block`
+    Block,
+    Section,
+}
+
+/// What's left to emit of the current line
+#[derive(Debug)]
+enum Scan {
+    /// Nothing has been emitted
+    Line,
+    /// Objects in given range
+    Objects {
+        pos: usize,
+        end: usize,
+    },
+    /// Entries of planning line
+    Planning {
+        pos: usize,
+    },
+    Done,
+}
+
+/// Event with ranges of the current line, resolved into [`StreamEvent`] later
+///
+/// Keeping ranges instead of slices lets [`StreamReader`] reuse its line buffer.
+enum Raw {
+    Headline {
+        level: usize,
+        keyword: Option<Range<usize>>,
+        priority: Option<char>,
+        title: Range<usize>,
+        tags: Range<usize>,
+    },
+    Planning(Range<usize>, Range<usize>),
+    Property(Range<usize>, Range<usize>),
+    Keyword(Range<usize>, Range<usize>),
+    Line,
+    Timestamp(Range<usize>),
+    Link(Range<usize>, Option<Range<usize>>),
+}
+
+impl Raw {
+    fn resolve(self, line: &str) -> StreamEvent<'_> {
+        match self {
+            Raw::Headline {
+                level,
+                keyword,
+                priority,
+                title,
+                tags,
+            } => StreamEvent::Headline(StreamHeadline {
+                level,
+                keyword: keyword.map(|r| &line[r]),
+                priority,
+                title: &line[title],
+                tags: &line[tags],
+            }),
+            Raw::Planning(keyword, timestamp) => StreamEvent::Planning {
+                keyword: &line[keyword],
+                timestamp: &line[timestamp],
+            },
+            Raw::Property(key, value) => StreamEvent::Property {
+                key: &line[key],
+                value: &line[value],
+            },
+            Raw::Keyword(key, value) => StreamEvent::Keyword {
+                key: &line[key],
+                value: &line[value],
+            },
+            Raw::Line => StreamEvent::Line(line),
+            Raw::Timestamp(range) => StreamEvent::Timestamp(&line[range]),
+            Raw::Link(path, description) => StreamEvent::Link {
+                path: &line[path],
+                description: description.map(|r| &line[r]),
+            },
+        }
+    }
+}
+
+struct Scanner {
+    config: ParseConfig,
+    /// Todo keyword sequences declared in buffer so far
+    sequences: Vec<TodoSequence>,
+    /// Name of the current block, reused across blocks
+    block: String,
+    section_objects: bool,
+    context: Context,
+    scan: Scan,
+}
+
+impl Scanner {
+    fn new(config: ParseConfig) -> Self {
+        Scanner {
+            config,
+            sequences: vec![],
+            block: String::new(),
+            section_objects: true,
+            // property drawer can appear at the beginning of document
+            context: Context::Planning,
+            scan: Scan::Done,
+        }
+    }
+
+    fn next_line(&mut self) {
+        self.scan = Scan::Line;
+    }
+
+    /// Returns next event of the current line, or `None` if there's nothing left
+    fn step(&mut self, line: &str) -> Option<Raw> {
+        loop {
+            match self.scan {
+                Scan::Line => {
+                    if let Some(raw) = self.line(line) {
+                        return Some(raw);
+                    }
+                }
+                Scan::Objects { pos, end } => {
+                    let (raw, next) = self.object(line, pos, end)?;
+                    self.scan = Scan::Objects { pos: next, end };
+                    return Some(raw);
+                }
+                Scan::Planning { pos } => {
+                    let (raw, next) = self.planning(line, pos)?;
+                    self.scan = Scan::Planning { pos: next };
+                    return Some(raw);
+                }
+                Scan::Done => return None,
+            }
+        }
+    }
+
+    /// Classifies the current line, and decides what's scanned after it
+    fn line(&mut self, line: &str) -> Option<Raw> {
+        self.scan = Scan::Done;
+
+        if let Some(raw) = self.headline(line) {
+            self.context = Context::Headline;
+            return Some(raw);
+        }
+
+        let trimmed = line.trim();
+        let offset = line.len() - line.trim_start().len();
+
+        match &self.context {
+            Context::Block => {
+                if strip_prefix_ignore_case(trimmed, "#+END_")
+                    .is_some_and(|end| end.eq_ignore_ascii_case(&self.block))
+                {
+                    self.context = Context::Section;
+                }
+                return Some(Raw::Line);
+            }
+            Context::Properties => {
+                if trimmed.eq_ignore_ascii_case(":END:") {
+                    self.context = Context::Section;
+                    return None;
+                }
+                return Some(
+                    node_property(trimmed)
+                        .map(|(key, value)| Raw::Property(shift(key, offset), shift(value, offset)))
+                        .unwrap_or(Raw::Line),
+                );
+            }
+            Context::Headline if is_planning(trimmed) => {
+                self.context = Context::Planning;
+                self.scan = Scan::Planning { pos: offset };
+                return None;
+            }
+            Context::Headline | Context::Planning
+                if trimmed.eq_ignore_ascii_case(":PROPERTIES:") =>
+            {
+                self.context = Context::Properties;
+                return None;
+            }
+            _ => {}
+        }
+
+        self.context = Context::Section;
+
+        if let Some(rest) = strip_prefix_ignore_case(trimmed, "#+BEGIN_") {
+            let name = rest.split_ascii_whitespace().next().unwrap_or_default();
+            self.block.clear();
+            self.block.push_str(name);
+            self.context = Context::Block;
+            return Some(Raw::Line);
+        }
+
+        if let Some((key, value)) = keyword(trimmed) {
+            let sequences = TodoSequence::scan(trimmed);
+            if !sequences.is_empty() {
+                self.sequences.extend(sequences);
+                self.config.todo_keywords = (
+                    self.sequences
+                        .iter()
+                        .flat_map(|seq| seq.todo.iter().map(|k| k.name.clone()))
+                        .collect(),
+                    self.sequences
+                        .iter()
+                        .flat_map(|seq| seq.done.iter().map(|k| k.name.clone()))
+                        .collect(),
+                );
+            }
+            return Some(Raw::Keyword(shift(key, offset), shift(value, offset)));
+        }
+
+        if self.section_objects {
+            self.scan = Scan::Objects {
+                pos: 0,
+                end: line.len(),
+            };
+        }
+        Some(Raw::Line)
+    }
+
+    fn headline(&mut self, line: &str) -> Option<Raw> {
+        let level = line.bytes().take_while(|&c| c == b'*').count();
+        if level == 0 || line.as_bytes().get(level) != Some(&b' ') {
+            return None;
+        }
+
+        let mut pos = level + 1;
+        let skip_ws = |pos: usize| pos + line[pos..].len() - line[pos..].trim_start().len();
+        pos = skip_ws(pos);
+
+        let word_end = line[pos..]
+            .find(|c: char| c.is_ascii_whitespace())
+            .map_or(line.len(), |i| pos + i);
+        let word = &line[pos..word_end];
+        let (todo, done) = &self.config.todo_keywords;
+        let keyword = if todo.iter().chain(done).any(|k| k == word) {
+            let range = pos..word_end;
+            pos = skip_ws(word_end);
+            Some(range)
+        } else {
+            None
+        };
+
+        let bytes = line.as_bytes();
+        let priority = if bytes.get(pos) == Some(&b'[') && bytes.get(pos + 1) == Some(&b'#') {
+            let mut chars = line[pos + 2..].chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), Some(']')) => {
+                    pos = skip_ws(pos + 3 + c.len_utf8());
+                    Some(c)
+                }
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        let end = pos + line[pos..].trim_end().len();
+        let tags = headline_tags(&line[pos..end]).map_or(end..end, |r| shift(r, pos));
+        let title = pos..pos + line[pos..tags.start].trim_end().len();
+
+        self.scan = Scan::Objects {
+            pos: title.start,
+            end: title.end,
+        };
+
+        Some(Raw::Headline {
+            level,
+            keyword,
+            priority,
+            title,
+            tags,
+        })
+    }
+
+    /// Finds next timestamp or link in `line[pos..end]`
+    fn object(&self, line: &str, mut pos: usize, end: usize) -> Option<(Raw, usize)> {
+        while pos < end {
+            let i = pos + memchr2(b'<', b'[', &line.as_bytes()[pos..end])?;
+            let s = &line[i..end];
+
+            if let Some((path, description, len)) = link(s) {
+                return Some((
+                    Raw::Link(shift(path, i), description.map(|r| shift(r, i))),
+                    i + len,
+                ));
+            }
+            if let Some(len) = self.timestamp(s) {
+                return Some((Raw::Timestamp(i..i + len), i + len));
+            }
+
+            pos = i + 1;
+        }
+        None
+    }
+
+    /// Finds next `KEYWORD: TIMESTAMP` entry of planning line
+    fn planning(&self, line: &str, pos: usize) -> Option<(Raw, usize)> {
+        let start = pos + line[pos..].len() - line[pos..].trim_start().len();
+        let colon = start + line[start..].find(':')?;
+        let keyword = start..colon;
+        if !is_planning(&line[keyword.start..]) {
+            return None;
+        }
+
+        let ts = colon + 1 + line[colon + 1..].len() - line[colon + 1..].trim_start().len();
+        let len = self.timestamp(&line[ts..])?;
+        Some((Raw::Planning(keyword, ts..ts + len), ts + len))
+    }
+
+    /// Returns length of timestamp at the beginning of input
+    fn timestamp(&self, s: &str) -> Option<usize> {
+        let input = Input {
+            s,
+            c: &self.config,
+            d: None,
+        };
+        let (rest, _) = if s.starts_with("<%%") {
+            timestamp_diary_node(input).ok()?
+        } else if s.starts_with('<') {
+            timestamp_active_node(input).ok()?
+        } else {
+            timestamp_inactive_node(input).ok()?
+        };
+        Some(s.len() - rest.len())
+    }
+}
+
+fn shift(range: Range<usize>, offset: usize) -> Range<usize> {
+    range.start + offset..range.end + offset
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    s.get(..prefix.len())
+        .filter(|p| p.eq_ignore_ascii_case(prefix))
+        .map(|_| &s[prefix.len()..])
+}
+
+fn is_planning(s: &str) -> bool {
+    ["SCHEDULED:", "DEADLINE:", "CLOSED:"]
+        .iter()
+        .any(|k| s.starts_with(k))
+}
+
+/// Returns ranges of key and value of `:KEY: VALUE`
+fn node_property(s: &str) -> Option<(Range<usize>, Range<usize>)> {
+    let rest = s.strip_prefix(':')?;
+    let colon = 1 + rest.find(':')?;
+    let key = 1..colon;
+    if key.is_empty() || s[key.clone()].contains(char::is_whitespace) {
+        return None;
+    }
+    let value = &s[colon + 1..];
+    let start = colon + 1 + value.len() - value.trim_start().len();
+    Some((key, start..s.len()))
+}
+
+/// Returns ranges of key and value of `#+KEY: VALUE`
+fn keyword(s: &str) -> Option<(Range<usize>, Range<usize>)> {
+    let rest = s.strip_prefix("#+")?;
+    let colon = 2 + rest.find(':')?;
+    let key = 2..colon;
+    if key.is_empty() || s[key.clone()].contains(char::is_whitespace) {
+        return None;
+    }
+    let value = &s[colon + 1..];
+    let start = colon + 1 + value.len() - value.trim_start().len();
+    Some((key, start..s.len()))
+}
+
+/// Returns range of trailing tags, e.g. `:a:b:`, which must follow whitespace
+fn headline_tags(s: &str) -> Option<Range<usize>> {
+    let start = s.rfind([' ', '\t']).map_or(0, |i| i + 1);
+    let tags = &s[start..];
+    let valid = tags.len() >= 2
+        && tags.starts_with(':')
+        && tags.ends_with(':')
+        && tags
+            .chars()
+            .all(|c| c == ':' || c.is_alphanumeric() || matches!(c, '_' | '@' | '#' | '%'));
+    valid.then_some(start..s.len())
+}
+
+/// Returns ranges of path and description, and length of `[[PATH][DESCRIPTION]]`
+fn link(s: &str) -> Option<(Range<usize>, Option<Range<usize>>, usize)> {
+    let rest = s.strip_prefix("[[")?;
+    let path_end = 2 + rest.find(']')?;
+    if path_end == 2 {
+        return None;
+    }
+
+    let rest = &s[path_end..];
+    if rest.starts_with("]]") {
+        Some((2..path_end, None, path_end + 2))
+    } else if let Some(description) = rest.strip_prefix("][") {
+        let description_end = path_end + 2 + description.find("]]")?;
+        Some((
+            2..path_end,
+            Some(path_end + 2..description_end),
+            description_end + 2,
+        ))
+    } else {
+        None
+    }
+}
+
+#[test]
+fn stream() {
+    let input = r#":PROPERTIES:
+:ID: document
+:END:
+#+TITLE: stream
+#+TODO: NEXT | DONE
+* NEXT [#B] meeting <2023-01-01 Sun 10:00> :work:urgent:
+  DEADLINE: <2023-01-02 Mon> SCHEDULED: [2023-01-01 Sun]
+  :PROPERTIES:
+  :ID:       1
+  :EMPTY:
+  :END:
+notes [[file:a.org][a *file*]] and <2023-01-03 Tue>--<2023-01-04 Wed>
+#+begin_src org
+[2023-01-05 Thu]
+#+end_src
+** WAIT not a keyword, DONE it is :notatag
+*** DONE [#A]
+"#;
+
+    let config = ParseConfig::default();
+    let events: Vec<_> = config.stream(input).collect();
+    insta::assert_debug_snapshot!(events, @r###"
+    [
+        Property {
+            key: "ID",
+            value: "document",
+        },
+        Keyword {
+            key: "TITLE",
+            value: "stream",
+        },
+        Keyword {
+            key: "TODO",
+            value: "NEXT | DONE",
+        },
+        Headline(
+            StreamHeadline {
+                level: 1,
+                keyword: Some(
+                    "NEXT",
+                ),
+                priority: Some(
+                    'B',
+                ),
+                title: "meeting <2023-01-01 Sun 10:00>",
+                tags: ":work:urgent:",
+            },
+        ),
+        Timestamp(
+            "<2023-01-01 Sun 10:00>",
+        ),
+        Planning {
+            keyword: "DEADLINE",
+            timestamp: "<2023-01-02 Mon>",
+        },
+        Planning {
+            keyword: "SCHEDULED",
+            timestamp: "[2023-01-01 Sun]",
+        },
+        Property {
+            key: "ID",
+            value: "1",
+        },
+        Property {
+            key: "EMPTY",
+            value: "",
+        },
+        Line(
+            "notes [[file:a.org][a *file*]] and <2023-01-03 Tue>--<2023-01-04 Wed>",
+        ),
+        Link {
+            path: "file:a.org",
+            description: Some(
+                "a *file*",
+            ),
+        },
+        Timestamp(
+            "<2023-01-03 Tue>--<2023-01-04 Wed>",
+        ),
+        Line(
+            "#+begin_src org",
+        ),
+        Line(
+            "[2023-01-05 Thu]",
+        ),
+        Line(
+            "#+end_src",
+        ),
+        Headline(
+            StreamHeadline {
+                level: 2,
+                keyword: None,
+                priority: None,
+                title: "WAIT not a keyword, DONE it is :notatag",
+                tags: "",
+            },
+        ),
+        Headline(
+            StreamHeadline {
+                level: 3,
+                keyword: Some(
+                    "DONE",
+                ),
+                priority: Some(
+                    'A',
+                ),
+                title: "",
+                tags: "",
+            },
+        ),
+    ]
+    "###);
+
+    let mut reader = config.stream_reader(input.as_bytes());
+    let mut count = 0;
+    while let Some(event) = reader.next_event().unwrap() {
+        assert_eq!(event, events[count]);
+        count += 1;
+    }
+    assert_eq!(count, events.len());
+
+    let events: Vec<_> = config
+        .stream("\r\n* a\r\n[2023-01-01 Sun]")
+        .section_objects(false)
+        .collect();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0], StreamEvent::Line(""));
+    assert_eq!(events[2], StreamEvent::Line("[2023-01-01 Sun]"));
+
+    // in-buffer keywords replace configured ones from the next line
+    let keywords: Vec<_> = config
+        .stream("* TODO a\n#+TODO: NEXT | DONE\n* TODO b\n* NEXT c\n* WAIT d\n")
+        .filter_map(|event| match event {
+            StreamEvent::Headline(headline) => Some(headline.keyword),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(keywords, [Some("TODO"), None, Some("NEXT"), None]);
+}
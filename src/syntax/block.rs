@@ -1,9 +1,9 @@
 use nom::{
     branch::alt,
-    bytes::complete::{tag, tag_no_case, take_while, take_while1},
-    character::complete::{alpha1, space0, space1},
-    combinator::{cond, opt},
-    sequence::{separated_pair, tuple},
+    bytes::complete::{tag, tag_no_case, take_till, take_while, take_while1},
+    character::complete::{alpha1, digit1, space0, space1},
+    combinator::{cond, opt, recognize, verify},
+    sequence::{delimited, separated_pair, tuple},
     IResult, InputTake,
 };
 
@@ -12,6 +12,7 @@ use super::{
         blank_lines, eol_or_eof, line_starts_iter, node, token, trim_line_end, GreenElement,
         NodeBuilder,
     },
+    diagnostic::ParseDiagnosticKind::UnterminatedBlock,
     element::element_nodes,
     input::Input,
     keyword::affiliated_keyword_nodes,
@@ -20,6 +21,7 @@ use super::{
 
 fn block_node_base(input: Input) -> IResult<Input, GreenElement, ()> {
     let (input, affiliated_keywords) = affiliated_keyword_nodes(input)?;
+    let begin_input = input;
     let (input, (block_begin, name)) = block_begin_node(input)?;
     let (input, pre_blank) = blank_lines(input)?;
 
@@ -53,6 +55,13 @@ fn block_node_base(input: Input) -> IResult<Input, GreenElement, ()> {
         }
     }
 
+    begin_input.report_line(
+        UnterminatedBlock,
+        format!(
+            "{} block is never closed, expected #+END_{name}",
+            name.to_ascii_uppercase()
+        ),
+    );
     Err(nom::Err::Error(()))
 }
 
@@ -120,10 +129,21 @@ fn source_block_switches(input: Input) -> IResult<Input, Input, ()> {
         match tuple::<_, _, (), _>((
             cond(i.len() != input.len(), space1),
             alt((
+                separated_pair(alt((tag("-n"), tag("+n"))), space1, digit1),
                 separated_pair(
-                    alt((tag("-l"), tag("-n"))),
+                    tag("-l"),
                     space1,
-                    take_while1(|c: char| c != ' ' && c != '\t' && c != '\n' && c != '\r'),
+                    alt((
+                        recognize(delimited(
+                            tag("\""),
+                            take_till(|c| c == '"' || c == '\n' || c == '\r'),
+                            tag("\""),
+                        )),
+                        verify(
+                            take_while1(|c: char| c != ' ' && c != '\t' && c != '\n' && c != '\r'),
+                            |arg: &Input| !arg.starts_with(':'),
+                        ),
+                    )),
                 ),
                 tuple((tag("+"), alpha1)),
                 tuple((tag("-"), alpha1)),
@@ -165,18 +185,29 @@ fn comma_quoted_text_nodes(input: Input) -> Vec<GreenElement> {
 
     let mut start = 0;
     for i in line_starts_iter(s) {
-        // line must start with either ",*" or ",#+"
-        if s.get(i..i + 2) != Some(",*") && s.get(i..i + 3) != Some(",#+") {
+        // line must start with either ",*" or ",#+", optionally preceded by
+        // whitespace and extra commas. only the last comma will be removed.
+        let line = &s.as_bytes()[i..];
+        let indent = line
+            .iter()
+            .take_while(|&&b| b == b' ' || b == b'\t')
+            .count();
+        let commas = line[indent..].iter().take_while(|&&b| b == b',').count();
+        if commas == 0 {
+            continue;
+        }
+        let comma = i + indent + commas - 1;
+        if s.get(comma..comma + 2) != Some(",*") && s.get(comma..comma + 3) != Some(",#+") {
             continue;
         }
 
-        let text = &s[start..i];
+        let text = &s[start..comma];
         if !text.is_empty() {
             nodes.push(token(TEXT, text));
         }
 
         nodes.push(token(COMMA, ","));
-        start = i + 1;
+        start = comma + 1;
     }
 
     if !s[start..].is_empty() {
@@ -301,5 +332,101 @@ alert('Hello World!');
     "###
     );
 
+    insta::assert_debug_snapshot!(
+        to_src_block(
+r#"#+BEGIN_SRC
+  ,#+END_SRC
+,,* not a headline
+,text
+#+END_SRC"#
+        ).syntax,
+        @r###"
+    SOURCE_BLOCK@0..59
+      BLOCK_BEGIN@0..12
+        TEXT@0..8 "#+BEGIN_"
+        TEXT@8..11 "SRC"
+        NEW_LINE@11..12 "\n"
+      BLOCK_CONTENT@12..50
+        TEXT@12..14 "  "
+        COMMA@14..15 ","
+        TEXT@15..26 "#+END_SRC\n,"
+        COMMA@26..27 ","
+        TEXT@27..50 "* not a headline\n,text\n"
+      BLOCK_END@50..59
+        TEXT@50..56 "#+END_"
+        TEXT@56..59 "SRC"
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        to_src_block(
+r#"#+begin_src sh -n :results output
+#+end_src"#
+        ).syntax,
+        @r###"
+    SOURCE_BLOCK@0..43
+      BLOCK_BEGIN@0..34
+        TEXT@0..8 "#+begin_"
+        TEXT@8..11 "src"
+        WHITESPACE@11..12 " "
+        SRC_BLOCK_LANGUAGE@12..14 "sh"
+        WHITESPACE@14..15 " "
+        SRC_BLOCK_SWITCHES@15..17 "-n"
+        WHITESPACE@17..18 " "
+        SRC_BLOCK_PARAMETERS@18..33 ":results output"
+        NEW_LINE@33..34 "\n"
+      BLOCK_CONTENT@34..34
+      BLOCK_END@34..43
+        TEXT@34..40 "#+end_"
+        TEXT@40..43 "src"
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        to_src_block(
+r#"#+begin_src sh +n 10 -l "(ref:%s)" :results output
+#+end_src"#
+        ).syntax,
+        @r###"
+    SOURCE_BLOCK@0..60
+      BLOCK_BEGIN@0..51
+        TEXT@0..8 "#+begin_"
+        TEXT@8..11 "src"
+        WHITESPACE@11..12 " "
+        SRC_BLOCK_LANGUAGE@12..14 "sh"
+        WHITESPACE@14..15 " "
+        SRC_BLOCK_SWITCHES@15..34 "+n 10 -l \"(ref:%s)\""
+        WHITESPACE@34..35 " "
+        SRC_BLOCK_PARAMETERS@35..50 ":results output"
+        NEW_LINE@50..51 "\n"
+      BLOCK_CONTENT@51..51
+      BLOCK_END@51..60
+        TEXT@51..57 "#+end_"
+        TEXT@57..60 "src"
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        to_src_block(
+r#"#+begin_src c -l "// (%s)" -r
+#+end_src"#
+        ).syntax,
+        @r###"
+    SOURCE_BLOCK@0..39
+      BLOCK_BEGIN@0..30
+        TEXT@0..8 "#+begin_"
+        TEXT@8..11 "src"
+        WHITESPACE@11..12 " "
+        SRC_BLOCK_LANGUAGE@12..13 "c"
+        WHITESPACE@13..14 " "
+        SRC_BLOCK_SWITCHES@14..29 "-l \"// (%s)\" -r"
+        NEW_LINE@29..30 "\n"
+      BLOCK_CONTENT@30..30
+      BLOCK_END@30..39
+        TEXT@30..36 "#+end_"
+        TEXT@36..39 "src"
+    "###
+    );
+
     // TODO: more testing
 }
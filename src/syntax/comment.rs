@@ -70,6 +70,7 @@ fn parse() {
             comment_node(Input {
                 s: input,
                 c: &ParseConfig::default(),
+                d: None,
             })
             .unwrap()
             .1
@@ -0,0 +1,171 @@
+use rowan::{TextRange, TextSize};
+use std::cell::RefCell;
+use std::ops::Range;
+
+/// Kinds of parse diagnostic
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ParseDiagnosticKind {
+    /// `#+BEGIN_` line without matching `#+END_` line
+    UnterminatedBlock,
+    /// Drawer without `:END:` line
+    UnterminatedDrawer,
+    /// `\begin{...}` line without matching `\end{...}` line
+    UnterminatedLatexEnvironment,
+    /// Property drawer containing a line which is not a node property
+    MalformedPropertyDrawer,
+    /// Timestamp-like text which can't be parsed as a timestamp, e.g. `<2023-1-01>`
+    MalformedTimestamp,
+}
+
+/// A problem found while parsing
+///
+/// Malformed constructs are still parsed as paragraphs (or drawers), so the
+/// resulting tree is always lossless, diagnostics explain why it happened.
+///
+/// ```rust
+/// use orgize::{Org, ParseDiagnosticKind};
+///
+/// let org = Org::parse("#+BEGIN_SRC rust\nfn main() {}");
+/// let diagnostics = org.diagnostics();
+///
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].kind, ParseDiagnosticKind::UnterminatedBlock);
+/// assert_eq!(&org.to_org()[diagnostics[0].range], "#+BEGIN_SRC rust");
+/// assert_eq!(diagnostics[0].message, "SRC block is never closed, expected #+END_SRC");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseDiagnostic {
+    pub kind: ParseDiagnosticKind,
+    /// Byte range in the original input
+    pub range: TextRange,
+    pub message: String,
+}
+
+/// Collects diagnostics during parsing
+#[derive(Debug)]
+pub(crate) struct DiagnosticSink {
+    /// Address range of the original input, used to compute offsets of sub-inputs
+    root: Range<usize>,
+    diagnostics: RefCell<Vec<ParseDiagnostic>>,
+}
+
+impl DiagnosticSink {
+    pub fn new(root: &str) -> Self {
+        let root = root.as_bytes().as_ptr_range();
+        DiagnosticSink {
+            root: root.start as usize..root.end as usize,
+            diagnostics: RefCell::new(vec![]),
+        }
+    }
+
+    /// Reports a diagnostic at the beginning of `s`, which must be a slice of
+    /// the original input
+    pub fn report(&self, s: &str, len: usize, kind: ParseDiagnosticKind, message: String) {
+        let range = s.as_bytes().as_ptr_range();
+        let start = (range.start as usize)
+            .checked_sub(self.root.start)
+            .filter(|_| range.end as usize <= self.root.end);
+        debug_assert!(
+            start.is_some(),
+            "input is not a slice of the original input"
+        );
+        let Some(start) = start else {
+            return;
+        };
+        let range = TextRange::at(
+            TextSize::from(start as u32),
+            TextSize::from(len.min(s.len()) as u32),
+        );
+        self.diagnostics.borrow_mut().push(ParseDiagnostic {
+            kind,
+            range,
+            message,
+        });
+    }
+
+    /// Returns diagnostics sorted by range
+    ///
+    /// Parsers may be invoked several times at the same position, so
+    /// duplicated diagnostics are removed.
+    pub fn finish(self) -> Vec<ParseDiagnostic> {
+        let mut diagnostics = self.diagnostics.into_inner();
+        diagnostics.sort_by_key(|d| (d.range.start(), d.range.end(), d.kind));
+        diagnostics.dedup();
+        diagnostics
+    }
+}
+
+#[test]
+fn parse() {
+    use crate::{Org, ParseDiagnosticKind::*};
+
+    let t = |input: &str| {
+        let org = Org::parse(input);
+        let s = org.to_org();
+        org.diagnostics()
+            .iter()
+            .map(|d| (d.kind, s[d.range].to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        t("* a\n#+begin_quote\n#+begin_src\n#+end_quote\n** b\n#+BEGIN: clocktable\n"),
+        vec![(UnterminatedBlock, "#+begin_src".into())]
+    );
+
+    assert_eq!(
+        t("* a\n:PROPERTIES:\n:ID: a\n:LOGBOOK:\n\n* b\n:PROPERTIES:\n:ID: b\nc\n:END:"),
+        vec![
+            (UnterminatedDrawer, ":PROPERTIES:".into()),
+            (UnterminatedDrawer, ":LOGBOOK:".into()),
+            (MalformedPropertyDrawer, "c".into()),
+        ]
+    );
+
+    assert_eq!(
+        t("<2023-1-01> [2023-01-01 Mon 9:0] <2023-01-01 Mon 9:00> SCHEDULED: <2023-01-01 Mon\n\\begin{align}\n"),
+        vec![
+            (MalformedTimestamp, "<2023-1-01>".into()),
+            (MalformedTimestamp, "[2023-01-01 Mon 9:0]".into()),
+            (MalformedTimestamp, "<2023-01-01 Mon".into()),
+            (UnterminatedLatexEnvironment, "\\begin{align}".into()),
+        ]
+    );
+
+    assert!(t("#+begin_src\n#+end_src\n<2023-01-01>\n:drawer:\n:end:\n<tag>").is_empty());
+}
+
+#[test]
+fn offsets() {
+    let root = "0123456789";
+    let sink = DiagnosticSink::new(root);
+    sink.report(
+        &root[4..],
+        2,
+        ParseDiagnosticKind::UnterminatedBlock,
+        "".into(),
+    );
+    sink.report(
+        &root[10..],
+        2,
+        ParseDiagnosticKind::UnterminatedBlock,
+        "".into(),
+    );
+    let ranges = sink.finish().iter().map(|d| d.range).collect::<Vec<_>>();
+    assert_eq!(
+        ranges,
+        [
+            TextRange::new(4.into(), 6.into()),
+            TextRange::new(10.into(), 10.into())
+        ]
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "input is not a slice of the original input")]
+fn foreign_input() {
+    let sink = DiagnosticSink::new("0123456789");
+    let other = String::from("0123456789");
+    sink.report(&other, 1, ParseDiagnosticKind::UnterminatedBlock, "".into());
+}
@@ -42,6 +42,83 @@ fn document_node_base(input: Input) -> IResult<Input, GreenElement, ()> {
     Ok((i, node(DOCUMENT, children)))
 }
 
+/// Parses document by splitting it at level-1 headlines, and parsing chunks in parallel
+///
+/// Level-1 headlines never nest in other headlines, and sections always end
+/// at the next headline, so each chunk parses into the same nodes as it does
+/// in a sequential parse. Returns the green node of document along with
+/// diagnostics, whose ranges are relative to the whole input.
+#[cfg(feature = "rayon")]
+pub fn parallel_document_node(
+    s: &str,
+    config: &crate::ParseConfig,
+) -> (GreenElement, Vec<super::diagnostic::ParseDiagnostic>) {
+    use rayon::prelude::*;
+    use rowan::NodeOrToken;
+
+    use super::{combinator::line_starts_iter, diagnostic::DiagnosticSink};
+
+    /// Minimal length of each chunk, so small documents don't pay for threading
+    const CHUNK_LEN: usize = 64 * 1024;
+
+    let mut chunks = vec![];
+    let mut start = 0;
+    for i in line_starts_iter(s) {
+        if i - start >= CHUNK_LEN && s[i..].starts_with("* ") {
+            chunks.push(&s[start..i]);
+            start = i;
+        }
+    }
+    chunks.push(&s[start..]);
+
+    let results: Vec<_> = chunks
+        .into_par_iter()
+        .enumerate()
+        .map(|(idx, chunk)| {
+            let sink = DiagnosticSink::new(s);
+            let input = Input {
+                s: chunk,
+                c: config,
+                d: Some(&sink),
+            };
+
+            let children = if idx == 0 {
+                let (_, document) = document_node(input).unwrap();
+                document
+                    .into_node()
+                    .unwrap()
+                    .children()
+                    .map(|child| match child {
+                        NodeOrToken::Node(node) => NodeOrToken::Node(node.to_owned()),
+                        NodeOrToken::Token(token) => NodeOrToken::Token(token.to_owned()),
+                    })
+                    .collect()
+            } else {
+                let mut children = vec![];
+                let mut i = input;
+                while !i.is_empty() {
+                    let (input, headline) = headline_node(i).unwrap();
+                    debug_assert!(i.len() > input.len(), "{} > {}", i.len(), input.len());
+                    i = input;
+                    children.push(headline);
+                }
+                children
+            };
+
+            (children, sink.finish())
+        })
+        .collect();
+
+    let mut children = vec![];
+    let mut diagnostics = vec![];
+    for (c, d) in results {
+        children.extend(c);
+        diagnostics.extend(d);
+    }
+
+    (node(DOCUMENT, children), diagnostics)
+}
+
 #[test]
 fn parse() {
     use crate::ast::Document;
@@ -127,3 +204,46 @@ fn parse() {
     "###
     );
 }
+
+#[test]
+#[cfg(feature = "rayon")]
+fn parallel() {
+    use std::{fs, path::Path};
+
+    use crate::syntax::diagnostic::DiagnosticSink;
+    use crate::ParseConfig;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut inputs = vec![
+        String::new(),
+        "\n\n* a".to_string(),
+        "** a\n*** b\n** c\n* d\n#+begin_src\n* e\n#+end_src\n".repeat(10_000),
+        "text\n<2023-1-01>\n* a\n#+begin_quote\n\n".repeat(10_000),
+    ];
+    for dir in ["tests/org-element", "benches"] {
+        for path in fs::read_dir(root.join(dir))
+            .unwrap()
+            .flatten()
+            .map(|e| e.path())
+        {
+            if path.extension() == Some("org".as_ref()) {
+                inputs.push(fs::read_to_string(path).unwrap());
+            }
+        }
+    }
+
+    let config = ParseConfig::default();
+    for s in &inputs {
+        let sink = DiagnosticSink::new(s);
+        let input = Input {
+            s,
+            c: &config,
+            d: Some(&sink),
+        };
+        let (_, expected) = document_node(input).unwrap();
+
+        let (document, diagnostics) = parallel_document_node(s, &config);
+        assert_eq!(document, expected);
+        assert_eq!(diagnostics, sink.finish());
+    }
+}
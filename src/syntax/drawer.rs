@@ -3,7 +3,7 @@ use nom::{
     character::complete::{space0, space1},
     combinator::{iterator, map, opt},
     sequence::tuple,
-    IResult, InputTake,
+    IResult, InputTake, Slice,
 };
 
 use super::{
@@ -11,8 +11,10 @@ use super::{
         blank_lines, colon_token, eol_or_eof, line_starts_iter, node, plus_token, trim_line_end,
         GreenElement, NodeBuilder,
     },
+    diagnostic::ParseDiagnosticKind::{MalformedPropertyDrawer, UnterminatedDrawer},
     element::element_nodes,
     input::Input,
+    keyword::affiliated_keyword_nodes,
     SyntaxKind::*,
 };
 
@@ -60,14 +62,17 @@ fn drawer_end_node(input: Input) -> IResult<Input, GreenElement, ()> {
 }
 
 fn drawer_node_base(input: Input) -> IResult<Input, GreenElement, ()> {
-    let (input, (begin, _)) = drawer_begin_node(input)?;
+    let (input, affiliated_keywords) = affiliated_keyword_nodes(input)?;
+    let begin_input = input;
+    let (input, (begin, name)) = drawer_begin_node(input)?;
 
     let (input, pre_blank) = blank_lines(input)?;
 
     for (input, contents) in line_starts_iter(input.as_str()).map(|i| input.take_split(i)) {
         if let Ok((input, end)) = drawer_end_node(input) {
             let (input, post_blank) = blank_lines(input)?;
-            let mut children = vec![begin];
+            let mut children = affiliated_keywords;
+            children.push(begin);
             children.extend(pre_blank);
             if !contents.is_empty() {
                 children.push(node(DRAWER_CONTENT, element_nodes(contents)?));
@@ -81,6 +86,10 @@ fn drawer_node_base(input: Input) -> IResult<Input, GreenElement, ()> {
         }
     }
 
+    begin_input.report_line(
+        UnterminatedDrawer,
+        format!("{name} drawer is never closed, expected :END:"),
+    );
     Err(nom::Err::Error(()))
 }
 
@@ -96,7 +105,20 @@ fn property_drawer_node_base(input: Input) -> IResult<Input, GreenElement, ()> {
     let mut it = iterator(input, node_property_node);
     children.extend(&mut it);
     let (input, _) = it.finish()?;
-    let (input, end) = drawer_end_node(input)?;
+    let (input, end) = drawer_end_node(input).inspect_err(|_| {
+        // without a closing line in current section, it's reported as an
+        // unterminated drawer instead
+        let closed = line_starts_iter(input.as_str())
+            .map(|i| input.slice(i..))
+            .take_while(|i| !i.starts_with('*'))
+            .any(|i| drawer_end_node(i).is_ok());
+        if closed {
+            input.report_line(
+                MalformedPropertyDrawer,
+                "invalid node property, expected :KEY: VALUE or :END:".into(),
+            );
+        }
+    })?;
 
     children.push(end);
 
@@ -108,7 +130,7 @@ fn node_property_node(input: Input) -> IResult<Input, GreenElement, ()> {
         tuple((
             space0,
             colon_token,
-            take_while1(|c| c != ':' && c != '+'),
+            node_property_name,
             opt(plus_token),
             colon_token,
             space1,
@@ -130,6 +152,34 @@ fn node_property_node(input: Input) -> IResult<Input, GreenElement, ()> {
     )(input)
 }
 
+/// Recognizes property name, which may contain colons, e.g. `header-args:sh`
+fn node_property_name(input: Input) -> IResult<Input, Input, ()> {
+    let bytes = input.as_bytes();
+    let line = bytes
+        .iter()
+        .position(|&b| b == b'\n' || b == b'\r')
+        .map_or(bytes, |i| &bytes[..=i]);
+
+    // name ends with the first colon followed by whitespace, on the same line
+    let Some(colon) = line.iter().enumerate().position(|(i, &b)| {
+        b == b':' && matches!(line.get(i + 1), None | Some(b' ' | b'\t' | b'\r' | b'\n'))
+    }) else {
+        return Err(nom::Err::Error(()));
+    };
+
+    let end = if colon > 0 && bytes[colon - 1] == b'+' {
+        colon - 1
+    } else {
+        colon
+    };
+
+    if end == 0 || bytes[..end].iter().any(|b| b.is_ascii_whitespace()) {
+        Err(nom::Err::Error(()))
+    } else {
+        Ok(input.take_split(end))
+    }
+}
+
 #[cfg_attr(
     feature = "tracing",
     tracing::instrument(level = "debug", skip(input), fields(input = input.s))
@@ -212,4 +262,86 @@ fn parse() {
 
     // https://github.com/PoiScript/orgize/issues/9
     assert!(drawer_node((":SPAGHETTI:\n", config).into()).is_err());
+
+    insta::assert_debug_snapshot!(
+        to_drawer("#+RESULTS: hello\n:results:\nworld\n:end:").syntax,
+        @r###"
+    DRAWER@0..38
+      AFFILIATED_KEYWORD@0..17
+        HASH_PLUS@0..2 "#+"
+        TEXT@2..9 "RESULTS"
+        COLON@9..10 ":"
+        TEXT@10..16 " hello"
+        NEW_LINE@16..17 "\n"
+      DRAWER_BEGIN@17..27
+        COLON@17..18 ":"
+        TEXT@18..25 "results"
+        COLON@25..26 ":"
+        NEW_LINE@26..27 "\n"
+      DRAWER_CONTENT@27..33
+        PARAGRAPH@27..33
+          TEXT@27..33 "world\n"
+      DRAWER_END@33..38
+        COLON@33..34 ":"
+        TEXT@34..37 "end"
+        COLON@37..38 ":"
+    "###
+    );
+
+    let to_property_drawer = to_ast::<crate::ast::PropertyDrawer>(property_drawer_node);
+
+    insta::assert_debug_snapshot!(
+        to_property_drawer(
+            ":PROPERTIES:\n:header-args:sh: :dir /tmp\n:header-args+: :noweb yes\n:END:"
+        ).syntax,
+        @r###"
+    PROPERTY_DRAWER@0..71
+      DRAWER_BEGIN@0..13
+        COLON@0..1 ":"
+        TEXT@1..11 "PROPERTIES"
+        COLON@11..12 ":"
+        NEW_LINE@12..13 "\n"
+      NODE_PROPERTY@13..40
+        COLON@13..14 ":"
+        TEXT@14..28 "header-args:sh"
+        COLON@28..29 ":"
+        WHITESPACE@29..30 " "
+        TEXT@30..39 ":dir /tmp"
+        NEW_LINE@39..40 "\n"
+      NODE_PROPERTY@40..66
+        COLON@40..41 ":"
+        TEXT@41..52 "header-args"
+        PLUS@52..53 "+"
+        COLON@53..54 ":"
+        WHITESPACE@54..55 " "
+        TEXT@55..65 ":noweb yes"
+        NEW_LINE@65..66 "\n"
+      DRAWER_END@66..71
+        COLON@66..67 ":"
+        TEXT@67..70 "END"
+        COLON@70..71 ":"
+    "###
+    );
+}
+
+#[test]
+fn property_name() {
+    use crate::ParseConfig;
+
+    let config = &ParseConfig::default();
+    let name = |s: &str| {
+        node_property_name((s, config).into())
+            .ok()
+            .map(|(_, name)| name.as_str().to_string())
+    };
+
+    assert_eq!(
+        name("header-args:sh: :dir /tmp"),
+        Some("header-args:sh".into())
+    );
+    assert_eq!(name("header-args+: yes"), Some("header-args".into()));
+    assert_eq!(name("KEY:\n"), Some("KEY".into()));
+    assert_eq!(name("KEY\n:END:"), None);
+    assert_eq!(name("KEY\r\n:b: c"), None);
+    assert_eq!(name("KEY"), None);
 }
@@ -10,6 +10,7 @@ use std::{
 
 use super::{
     combinator::{token, GreenElement},
+    diagnostic::{DiagnosticSink, ParseDiagnosticKind},
     SyntaxKind,
 };
 use crate::config::ParseConfig;
@@ -21,12 +22,18 @@ use crate::config::ParseConfig;
 pub struct Input<'a> {
     pub(crate) s: &'a str,
     pub(crate) c: &'a ParseConfig,
+    /// Collects parse diagnostics, `None` if not needed
+    pub(crate) d: Option<&'a DiagnosticSink>,
 }
 
 impl<'a> Input<'a> {
     #[inline]
     pub(crate) fn of(&self, i: &'a str) -> Input<'a> {
-        Input { s: i, c: self.c }
+        Input {
+            s: i,
+            c: self.c,
+            d: self.d,
+        }
     }
 
     #[inline]
@@ -53,6 +60,19 @@ impl<'a> Input<'a> {
     pub fn nl_token(&self) -> GreenElement {
         token(SyntaxKind::NEW_LINE, self.s)
     }
+
+    /// Reports a diagnostic covering the first `len` bytes of input
+    pub(crate) fn report(&self, len: usize, kind: ParseDiagnosticKind, message: String) {
+        if let Some(d) = self.d {
+            d.report(self.s, len, kind, message);
+        }
+    }
+
+    /// Reports a diagnostic covering the first line of input, excluding line ending
+    pub(crate) fn report_line(&self, kind: ParseDiagnosticKind, message: String) {
+        let len = self.s.find(['\r', '\n']).unwrap_or(self.s.len());
+        self.report(len, kind, message);
+    }
 }
 
 impl<'a> Deref for Input<'a> {
@@ -69,6 +89,7 @@ impl<'a> From<(&'a str, &'a ParseConfig)> for Input<'a> {
         Input {
             s: value.0,
             c: value.1,
+            d: None,
         }
     }
 }
@@ -2,7 +2,7 @@
 
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_till, take_while1},
+    bytes::complete::{tag, tag_no_case, take_till, take_while1},
     character::complete::space0,
     combinator::{recognize, verify},
     sequence::tuple,
@@ -21,6 +21,20 @@ use super::{
 )]
 pub fn keyword_node(input: Input) -> IResult<Input, GreenElement, ()> {
     fn f(input: Input) -> IResult<Input, GreenElement, ()> {
+        // babel call is the only keyword can have affiliated keywords, e.g. #+NAME
+        let (i, affiliated_keywords) = affiliated_keyword_nodes(input)?;
+        if !affiliated_keywords.is_empty() {
+            if let Ok((i, (key, nodes))) = keyword_node_base(i) {
+                if key == "CALL" {
+                    let (i, post_blank) = blank_lines(i)?;
+                    let mut children = affiliated_keywords;
+                    children.extend(nodes);
+                    children.extend(post_blank);
+                    return Ok((i, node(SyntaxKind::BABEL_CALL, children)));
+                }
+            }
+        }
+
         let (input, (key, mut nodes)) = keyword_node_base(input)?;
         let (input, post_blank) = blank_lines(input)?;
         nodes.extend(post_blank);
@@ -54,6 +68,18 @@ pub fn affiliated_keyword_nodes(input: Input) -> IResult<Input, Vec<GreenElement
             break;
         };
 
+        if input_
+            .c
+            .affiliated_keywords
+            .iter()
+            .all(|w| !w.eq_ignore_ascii_case(key))
+            && !key
+                .get(..5)
+                .is_some_and(|p| p.eq_ignore_ascii_case("ATTR_"))
+        {
+            break;
+        }
+
         let (input_, post_blank) = blank_lines(input_)?;
 
         // affiliated keyword can not followed by blank lines or eof
@@ -61,10 +87,6 @@ pub fn affiliated_keyword_nodes(input: Input) -> IResult<Input, Vec<GreenElement
             return Ok((input, vec![]));
         }
 
-        if input_.c.affiliated_keywords.iter().all(|w| w != key) && !key.starts_with("ATTR_") {
-            break;
-        }
-
         debug_assert!(i.len() > input_.len(), "{} > {}", i.len(), input_.len());
         i = input_;
         children.push(node(SyntaxKind::AFFILIATED_KEYWORD, nodes));
@@ -140,7 +162,7 @@ fn key_with_optional(
     input: Input,
 ) -> IResult<Input, (Input, Option<(Input, Input, Input)>, Input), ()> {
     let (input, (key, r_backer, optional, l_backer, colon)) = tuple((
-        alt((tag("CAPTION"), tag("RESULTS"))),
+        alt((tag_no_case("CAPTION"), tag_no_case("RESULTS"))),
         tag("["),
         take_till(|c| c == '\r' || c == '\n' || c == ']'),
         tag("]"),
@@ -247,6 +269,25 @@ fn parse() {
     "###
     );
 
+    insta::assert_debug_snapshot!(
+        to_babel_call("#+NAME: quadruple\n#+CALL: double(n=4)\n\n").syntax,
+        @r###"
+    BABEL_CALL@0..39
+      AFFILIATED_KEYWORD@0..18
+        HASH_PLUS@0..2 "#+"
+        TEXT@2..6 "NAME"
+        COLON@6..7 ":"
+        TEXT@7..17 " quadruple"
+        NEW_LINE@17..18 "\n"
+      HASH_PLUS@18..20 "#+"
+      TEXT@20..24 "CALL"
+      COLON@24..25 ":"
+      TEXT@25..37 " double(n=4)"
+      NEW_LINE@37..38 "\n"
+      BLANK_LINE@38..39 "\n"
+    "###
+    );
+
     insta::assert_debug_snapshot!(
         to_keyword("#+ABC[OPTIONAL]: Longer value.").syntax,
         @r###"
@@ -283,6 +324,44 @@ fn parse() {
     "###
     );
 
+    // keys are case-insensitive
+    insta::assert_debug_snapshot!(
+        to_keyword("#+results[a1b2]: value").syntax,
+        @r###"
+    KEYWORD@0..22
+      HASH_PLUS@0..2 "#+"
+      TEXT@2..9 "results"
+      L_BRACKET@9..10 "["
+      TEXT@10..14 "a1b2"
+      R_BRACKET@14..15 "]"
+      COLON@15..16 ":"
+      TEXT@16..22 " value"
+    "###
+    );
+
+    insta::assert_debug_snapshot!(
+        to_babel_call("#+name: n\n#+attr_html: :width 10\n#+CALL: f()").syntax,
+        @r###"
+    BABEL_CALL@0..44
+      AFFILIATED_KEYWORD@0..10
+        HASH_PLUS@0..2 "#+"
+        TEXT@2..6 "name"
+        COLON@6..7 ":"
+        TEXT@7..9 " n"
+        NEW_LINE@9..10 "\n"
+      AFFILIATED_KEYWORD@10..33
+        HASH_PLUS@10..12 "#+"
+        TEXT@12..21 "attr_html"
+        COLON@21..22 ":"
+        TEXT@22..32 " :width 10"
+        NEW_LINE@32..33 "\n"
+      HASH_PLUS@33..35 "#+"
+      TEXT@35..39 "CALL"
+      COLON@39..40 ":"
+      TEXT@40..44 " f()"
+    "###
+    );
+
     let config = &ParseConfig::default();
 
     assert!(keyword_node(("#+KE Y: VALUE", config).into()).is_err());
@@ -9,7 +9,9 @@ use crate::SyntaxKind;
 
 use super::{
     combinator::{eol_or_eof, l_curly_token, line_starts_iter, node, r_curly_token, GreenElement},
+    diagnostic::ParseDiagnosticKind::UnterminatedLatexEnvironment,
     input::Input,
+    keyword::affiliated_keyword_nodes,
 };
 
 #[cfg_attr(
@@ -21,6 +23,8 @@ pub fn latex_environment_node(input: Input) -> IResult<Input, GreenElement, ()>
 }
 
 fn latex_environment_node_base(input: Input) -> IResult<Input, GreenElement, ()> {
+    let (input, mut children) = affiliated_keyword_nodes(input)?;
+    let begin_input = input;
     let (input, (ws1, begin, l1, name1, r1)) = tuple((
         space0,
         tag("\\begin"),
@@ -40,30 +44,32 @@ fn latex_environment_node_base(input: Input) -> IResult<Input, GreenElement, ()>
             eol_or_eof,
         ))(input)
         {
-            return Ok((
-                input,
-                node(
-                    SyntaxKind::LATEX_ENVIRONMENT,
-                    [
-                        ws1.ws_token(),
-                        begin.text_token(),
-                        l1,
-                        name1.text_token(),
-                        r1,
-                        contents.text_token(),
-                        ws2.ws_token(),
-                        end.text_token(),
-                        l2,
-                        name2.text_token(),
-                        r2,
-                        ws3.ws_token(),
-                        nl.nl_token(),
-                    ],
-                ),
-            ));
+            children.extend([
+                ws1.ws_token(),
+                begin.text_token(),
+                l1,
+                name1.text_token(),
+                r1,
+                contents.text_token(),
+                ws2.ws_token(),
+                end.text_token(),
+                l2,
+                name2.text_token(),
+                r2,
+                ws3.ws_token(),
+                nl.nl_token(),
+            ]);
+            return Ok((input, node(SyntaxKind::LATEX_ENVIRONMENT, children)));
         }
     }
 
+    begin_input.report_line(
+        UnterminatedLatexEnvironment,
+        format!(
+            "{name1} environment is never closed, expected \\end{{{name1}}}",
+            name1 = name1.s
+        ),
+    );
     Err(nom::Err::Error(()))
 }
 
@@ -120,6 +126,32 @@ fn parse() {
     "###
     );
 
+    insta::assert_debug_snapshot!(
+        to_latex("#+NAME: pythagoras\n\\begin{equation}\na^2 + b^2 = c^2\n\\end{equation}").syntax,
+        @r###"
+    LATEX_ENVIRONMENT@0..66
+      AFFILIATED_KEYWORD@0..19
+        HASH_PLUS@0..2 "#+"
+        TEXT@2..6 "NAME"
+        COLON@6..7 ":"
+        TEXT@7..18 " pythagoras"
+        NEW_LINE@18..19 "\n"
+      WHITESPACE@19..19 ""
+      TEXT@19..25 "\\begin"
+      L_CURLY@25..26 "{"
+      TEXT@26..34 "equation"
+      R_CURLY@34..35 "}"
+      TEXT@35..52 "\na^2 + b^2 = c^2\n"
+      WHITESPACE@52..52 ""
+      TEXT@52..56 "\\end"
+      L_CURLY@56..57 "{"
+      TEXT@57..65 "equation"
+      R_CURLY@65..66 "}"
+      WHITESPACE@66..66 ""
+      NEW_LINE@66..66 ""
+    "###
+    );
+
     let c = ParseConfig::default();
 
     assert!(latex_environment_node((r"\begin{equation}\end{align}", &c).into()).is_err());
@@ -5,6 +5,7 @@ pub mod clock;
 pub mod combinator;
 pub mod comment;
 pub mod cookie;
+pub mod diagnostic;
 pub mod document;
 pub mod drawer;
 pub mod dyn_block;
@@ -1,7 +1,6 @@
 use nom::{
     bytes::complete::take_while,
     character::complete::{multispace0, space0},
-    combinator::iterator,
     sequence::tuple,
     Err, IResult, InputTake, Slice,
 };
@@ -9,13 +8,13 @@ use nom::{
 use super::{
     combinator::{blank_lines, line_ends_iter, node, pipe_token, GreenElement, NodeBuilder},
     input::Input,
-    keyword::tblfm_keyword_nodes,
+    keyword::{affiliated_keyword_nodes, tblfm_keyword_nodes},
     object::standard_object_nodes,
     SyntaxKind::*,
 };
 
 fn org_table_node_base(input: Input) -> IResult<Input, GreenElement, ()> {
-    let mut children = vec![];
+    let (input, mut children) = affiliated_keyword_nodes(input)?;
 
     let mut start = 0;
     for i in line_ends_iter(input.as_str()) {
@@ -59,35 +58,37 @@ fn table_standard_row_node(input: Input) -> Result<GreenElement, nom::Err<()>> {
 
     b.ws(ws);
 
-    let mut it = iterator(
-        input,
-        tuple((pipe_token, multispace0, take_while(|c: char| c != '|'))),
-    );
-
-    it.for_each(|(pipe, ws, input)| {
+    let mut input = input;
+    while let Ok((rest, (pipe, ws, cell))) =
+        tuple((pipe_token, multispace0, take_while(|c: char| c != '|')))(input)
+    {
+        input = rest;
         b.push(pipe);
         b.ws(ws);
 
-        if input.is_empty() {
-            return;
+        if cell.is_empty() {
+            // empty cell between two pipes, e.g. `| |`
+            if !input.is_empty() {
+                b.push(node(ORG_TABLE_CELL, []));
+            }
+            continue;
         }
 
-        match input
+        match cell
             .as_bytes()
             .iter()
             .rposition(|b| !b.is_ascii_whitespace())
         {
             Some(idx) => {
-                let (ws, cell) = input.take_split(idx + 1);
+                let (ws, cell) = cell.take_split(idx + 1);
                 b.push(node(ORG_TABLE_CELL, standard_object_nodes(cell)));
                 b.ws(ws);
             }
             _ => {
-                b.push(node(ORG_TABLE_CELL, standard_object_nodes(input)));
+                b.push(node(ORG_TABLE_CELL, standard_object_nodes(cell)));
             }
         }
-    });
-    let (input, _) = it.finish()?;
+    }
     debug_assert!(input.is_empty());
 
     Ok(b.finish(ORG_TABLE_STANDARD_ROW))
@@ -234,6 +235,47 @@ r#"|
         TEXT@29..35 " test2"
     "###
     );
+    insta::assert_debug_snapshot!(
+        to_org_table("#+NAME: tbl\n| a |\n").syntax,
+        @r###"
+    ORG_TABLE@0..18
+      AFFILIATED_KEYWORD@0..12
+        HASH_PLUS@0..2 "#+"
+        TEXT@2..6 "NAME"
+        COLON@6..7 ":"
+        TEXT@7..11 " tbl"
+        NEW_LINE@11..12 "\n"
+      ORG_TABLE_STANDARD_ROW@12..18
+        PIPE@12..13 "|"
+        WHITESPACE@13..14 " "
+        ORG_TABLE_CELL@14..15
+          TEXT@14..15 "a"
+        WHITESPACE@15..16 " "
+        PIPE@16..17 "|"
+        WHITESPACE@17..18 "\n"
+    "###
+    );
+    insta::assert_debug_snapshot!(
+        to_org_table("| a |  | b |").syntax,
+        @r###"
+    ORG_TABLE@0..12
+      ORG_TABLE_STANDARD_ROW@0..12
+        PIPE@0..1 "|"
+        WHITESPACE@1..2 " "
+        ORG_TABLE_CELL@2..3
+          TEXT@2..3 "a"
+        WHITESPACE@3..4 " "
+        PIPE@4..5 "|"
+        WHITESPACE@5..7 "  "
+        ORG_TABLE_CELL@7..7
+        PIPE@7..8 "|"
+        WHITESPACE@8..9 " "
+        ORG_TABLE_CELL@9..10
+          TEXT@9..10 "b"
+        WHITESPACE@10..11 " "
+        PIPE@11..12 "|"
+    "###
+    );
 }
 
 #[test]
@@ -13,6 +13,7 @@ use super::{
         node, percent2_token, r_angle_token, r_bracket_token, r_parens_token, GreenElement,
         NodeBuilder,
     },
+    diagnostic::ParseDiagnosticKind::MalformedTimestamp,
     input::Input,
     SyntaxKind::*,
 };
@@ -87,7 +88,7 @@ fn dayname(i: Input) -> IResult<Input, GreenElement, ()> {
 fn time(i: Input) -> IResult<Input, [GreenElement; 3], ()> {
     map(
         tuple((
-            take_while_m_n(2, 2, |c: char| c.is_ascii_digit()),
+            take_while_m_n(1, 2, |c: char| c.is_ascii_digit()),
             colon_token,
             take_while_m_n(2, 2, |c: char| c.is_ascii_digit()),
         )),
@@ -225,13 +226,26 @@ fn timestamp_node_base(
     }
 }
 
+/// Reports text like `<2023-1-01>` or `[2023-01-01 Mon 9:0]`, which starts
+/// like a timestamp but can't be parsed
+fn report_malformed(input: Input, close: char, err: nom::Err<()>) -> nom::Err<()> {
+    let bytes = input.as_bytes();
+    if bytes.len() > 5 && bytes[1..5].iter().all(u8::is_ascii_digit) && bytes[5] == b'-' {
+        let line = input.find(['\r', '\n']).unwrap_or(input.len());
+        let len = input[..line].find(close).map_or(line, |i| i + 1);
+        input.report(len, MalformedTimestamp, "malformed timestamp".into());
+    }
+    err
+}
+
 #[cfg_attr(
     feature = "tracing",
     tracing::instrument(level = "debug", skip(input), fields(input = input.s))
 )]
 pub fn timestamp_active_node(input: Input) -> IResult<Input, GreenElement, ()> {
     fn parser(input: Input) -> IResult<Input, GreenElement, ()> {
-        let (input, children) = timestamp_node_base(input, l_angle_token, r_angle_token)?;
+        let (input, children) = timestamp_node_base(input, l_angle_token, r_angle_token)
+            .map_err(|err| report_malformed(input, '>', err))?;
         Ok((input, node(TIMESTAMP_ACTIVE, children)))
     }
     crate::lossless_parser!(parser, input)
@@ -243,7 +257,8 @@ pub fn timestamp_active_node(input: Input) -> IResult<Input, GreenElement, ()> {
 )]
 pub fn timestamp_inactive_node(input: Input) -> IResult<Input, GreenElement, ()> {
     fn parser(input: Input) -> IResult<Input, GreenElement, ()> {
-        let (input, children) = timestamp_node_base(input, l_bracket_token, r_bracket_token)?;
+        let (input, children) = timestamp_node_base(input, l_bracket_token, r_bracket_token)
+            .map_err(|err| report_malformed(input, ']', err))?;
         Ok((input, node(TIMESTAMP_INACTIVE, children)))
     }
     crate::lossless_parser!(parser, input)
@@ -270,6 +285,11 @@ fn parse() {
     to_timestamp("[2003-09-16 09:09]--[2003-09-16  +1w .+1d --1d ]");
     to_timestamp("[2003-09-16 Tue 09:09 +1w]--[2003-09-16 .+1d --1d ]");
     to_timestamp("[2003-09-16 09:09-10:19 +1w --1d]");
+    to_timestamp("[2003-09-16 Tue 9:09-10:19]");
+
+    let ts = to_timestamp("[2003-09-16 Tue 9:09]");
+    assert_eq!(ts.hour_start().unwrap().as_ref(), "9");
+    assert_eq!(ts.minute_start().unwrap().as_ref(), "09");
 
     let ts = to_timestamp("[2003-09-16 Tue +1w]");
     assert!(!ts.is_range());
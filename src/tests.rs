@@ -15,6 +15,7 @@ pub fn to_ast<N: AstNode>(
         let input = Input {
             s,
             c: &ParseConfig::default(),
+            d: None,
         };
         let element = parser(input).unwrap().1;
         let node = element.into_node().unwrap();
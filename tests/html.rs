@@ -1,4 +1,12 @@
-use orgize::Org;
+use orgize::{
+    ast::{Link, SourceBlock},
+    export::{
+        Container, Event, HtmlDocument, HtmlEscape, HtmlExport, HtmlHandler, HtmlTraverser,
+        TraversalContext, Traverser, Visit,
+    },
+    rowan::ast::AstNode,
+    Org,
+};
 
 #[test]
 fn emphasis() {
@@ -165,6 +173,15 @@ fn table() {
 "#).to_html(),
         @"<main><section><table><thead><tr></tr></thead><tbody><tr></tr></tbody><tbody><tr></tr></tbody></table></section></main>"
     );
+
+    // empty cells are kept, so columns stay aligned
+    insta::assert_snapshot!(
+        Org::parse(r#"
+| a |   | c |
+|   | b |   |
+"#).to_html(),
+        @"<main><section><table><tbody><tr><td>a</td><td></td><td>c</td></tr><tr><td></td><td>b</td><td></td></tr></tbody></table></section></main>"
+    );
 }
 
 #[test]
@@ -174,3 +191,316 @@ fn line_break() {
         @r###""<main><section><p>aa<br/>bb</p></section></main>""###
     );
 }
+
+#[test]
+fn source_block_line_numbers() {
+    insta::assert_snapshot!(
+        Org::parse(r#"#+begin_src rust -n 9
+let a = 1; (ref:decl)
+let b = a + 1;
+#+end_src
+
+#+begin_src rust +n -r
+a < b (ref:cmp)
+#+end_src
+
+See [[(decl)]] and [[(cmp)][this line]].
+"#).to_html(),
+        @r###"
+    <main><section><pre><code class="language-rust"><span id="coderef-decl" class="coderef-off"><span class="linenr"> 9: </span>let a = 1; (decl)</span>
+    <span class="linenr">10: </span>let b = a + 1;
+    </code></pre><pre><code class="language-rust"><span id="coderef-cmp" class="coderef-off"><span class="linenr">11: </span>a &lt; b</span>
+    </code></pre><p>See <a href="#coderef-decl" class="coderef">decl</a> and <a href="#coderef-cmp" class="coderef">this line</a>.
+    </p></section></main>
+    "###
+    );
+}
+
+#[test]
+fn handler() {
+    #[derive(Default)]
+    struct External(HtmlExport);
+
+    impl HtmlHandler for External {
+        fn html(&mut self) -> &mut HtmlExport {
+            &mut self.0
+        }
+
+        fn link(&mut self, link: Link, visit: Visit, ctx: &mut TraversalContext) {
+            match visit {
+                Visit::Enter if link.path().starts_with("http") => self.0.push_str(format!(
+                    r#"<a href="{}" rel="external">"#,
+                    HtmlEscape(link.path())
+                )),
+                Visit::Leave if link.path().starts_with("http") => self.0.push_str("</a>"),
+                _ => self.0.link(link, visit, ctx),
+            }
+        }
+    }
+
+    let mut html = External::default();
+    Org::parse("* [[https://a.com][a]]\n[[https://b.com][b]] [[c.html][c]]")
+        .traverse(&mut HtmlTraverser(&mut html));
+    insta::assert_snapshot!(html.0.finish(), @r###"<main><h1><a href="https://a.com" rel="external">a</a></h1><section><p><a href="https://b.com" rel="external">b</a> <a href="c.html">c</a></p></section></main>"###);
+
+    // handlers can have their own traverser implementation, e.g. skipping
+    // headlines before passing events to the html handler
+    #[derive(Default)]
+    struct NoHeadlines(HtmlExport);
+
+    impl HtmlHandler for NoHeadlines {
+        fn html(&mut self) -> &mut HtmlExport {
+            &mut self.0
+        }
+    }
+
+    impl Traverser for NoHeadlines {
+        fn event(&mut self, event: Event, ctx: &mut TraversalContext) {
+            match event {
+                Event::Enter(Container::Headline(_)) => ctx.skip(),
+                event => HtmlTraverser(self).event(event, ctx),
+            }
+        }
+    }
+
+    let mut html = NoHeadlines::default();
+    Org::parse("text\n* headline\nsection").traverse(&mut html);
+    assert_eq!(
+        html.0.finish(),
+        "<main><section><p>text\n</p></section></main>"
+    );
+}
+
+#[test]
+fn document() {
+    let org = Org::parse(
+        r#"#+TITLE: Report
+#+AUTHOR: Jane <jane@example.com>
+#+DATE: 2024-01-01
+#+DESCRIPTION: A report
+#+KEYWORDS: org html
+#+LANGUAGE: de
+#+HTML_HEAD: <style>body { margin: 0 }</style>
+* Intro
+Text[fn:1] and[fn::inline *note*].
+** Detail
+:PROPERTIES:
+:CUSTOM_ID: detail
+:END:
+Again[fn:1].
+* Footnotes
+[fn:1] First note
+"#,
+    );
+
+    let config = HtmlDocument {
+        stylesheets: vec!["style.css".into()],
+        scripts: vec!["main.js".into()],
+        ..Default::default()
+    };
+
+    insta::assert_snapshot!(config.export(&org), @r###"
+    <!DOCTYPE html>
+    <html lang="de">
+    <head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>Report</title>
+    <meta name="author" content="Jane &lt;jane@example.com&gt;">
+    <meta name="description" content="A report">
+    <meta name="keywords" content="org html">
+    <link rel="stylesheet" href="style.css">
+    <script src="main.js"></script>
+    <style>body { margin: 0 }</style>
+    </head>
+    <body>
+    <header><h1 class="title">Report</h1></header><nav id="table-of-contents"><h2>Table of Contents</h2><ul><li><a href="#sec-1">Intro</a><ul><li><a href="#detail">Detail</a></li></ul></li><li><a href="#sec-2">Footnotes</a></li></ul></nav><main><section></section><h1 id="sec-1">Intro</h1><section><p>Text<sup><a id="fnr.1" class="footref" href="#fn.1" role="doc-noteref">1</a></sup> and<sup><a id="fnr.2" class="footref" href="#fn.2" role="doc-noteref">2</a></sup>.
+    </p></section><h2 id="detail">Detail</h2><section><p>Again<sup><a class="footref" href="#fn.1" role="doc-noteref">1</a></sup>.
+    </p></section><h1 id="sec-2">Footnotes</h1><section></section></main><div id="footnotes"><h2 class="footnotes">Footnotes</h2><div class="footdef"><sup><a id="fn.1" class="footnum" href="#fnr.1" role="doc-backlink">1</a></sup> <div class="footpara"> First note
+    </div></div><div class="footdef"><sup><a id="fn.2" class="footnum" href="#fnr.2" role="doc-backlink">2</a></sup> <div class="footpara">inline <b>note</b></div></div></div><footer><p class="author">Author: Jane &lt;jane@example.com&gt;</p><p class="date">Date: 2024-01-01</p></footer>
+    </body>
+    </html>
+    "###);
+}
+
+#[test]
+fn attributes_and_captions() {
+    insta::assert_snapshot!(
+        Org::parse(r#"#+CAPTION: A cat
+#+ATTR_HTML: :width 300 :alt a cat
+[[./cat.png]]
+
+#+ATTR_HTML: :width 100
+Inline [[./dog.png]] image
+
+#+CAPTION: Results
+#+ATTR_HTML: :class wide :border 1
+| a |
+
+#+ATTR_HTML: :class quote
+#+ATTR_HTML: :class ignored :id q
+#+begin_quote
+text
+#+end_quote
+"#).to_html(),
+        @r###"
+    <main><section><figure><img src="./cat.png" width="300" alt="a cat">
+    <figcaption><span class="figure-number">Figure 1:</span> A cat</figcaption></figure><p>Inline <img src="./dog.png"> image
+    </p><table class="wide" border="1"><caption><span class="table-number">Table 1:</span> Results</caption><tbody><tr><td>a</td></tr></tbody></table><blockquote class="quote" id="q"><p>text
+    </p></blockquote></section></main>
+    "###
+    );
+}
+
+#[test]
+fn cross_references() {
+    insta::assert_snapshot!(
+        Org::parse(r#"See [[prices]], [[hello][the listing]], [[cat]] and [[pythagoras]].
+
+#+CAPTION: Prices
+#+NAME: prices
+| pen | 3 |
+
+#+NAME: cat
+#+CAPTION: A cat
+[[./cat.png]]
+
+#+CAPTION: Hello
+#+NAME: hello
+#+begin_src c
+puts("hello");
+#+end_src
+
+#+NAME: pythagoras
+\begin{equation}
+a^2 + b^2 = c^2
+\end{equation}
+"#).to_html(),
+        @r###"
+    <main><section><p>See <a href="#prices">Table 1</a>, <a href="#hello">the listing</a>, <a href="#cat">Figure 1</a> and <a href="#pythagoras">Equation 1</a>.
+    </p><table id="prices"><caption><span class="table-number">Table 1:</span> Prices</caption><tbody><tr><td>pen</td><td>3</td></tr></tbody></table><figure id="cat"><img src="./cat.png">
+    <figcaption><span class="figure-number">Figure 1:</span> A cat</figcaption></figure><label class="org-src-name"><span class="listing-number">Listing 1:</span> Hello</label><pre id="hello"><code class="language-c">puts(&quot;hello&quot;);
+    </code></pre><div id="pythagoras" class="equation-container"><span class="equation">\begin{equation}
+    a^2 + b^2 = c^2
+    \end{equation}
+    </span><span class="equation-label">(1)</span></div></section></main>
+    "###
+    );
+}
+
+#[test]
+fn highlighter() {
+    use orgize::export::Highlighter;
+
+    struct Upper;
+
+    impl Highlighter for Upper {
+        fn highlight(&self, language: &str, code: &str) -> Option<String> {
+            (language == "sh").then(|| format!("<b>{}</b>", code.to_uppercase()))
+        }
+    }
+
+    let mut html = HtmlExport::default().with_highlighter(Upper);
+    Org::parse(
+        r#"#+begin_src sh
+echo hi
+#+end_src
+
+#+begin_src python
+print("hi")
+#+end_src
+
+#+begin_src sh -n
+echo numbered
+cd dir (ref:cd)
+#+end_src
+
+Run src_sh{ls} or src_python[:exports code]{len(x) < 1}."#,
+    )
+    .traverse(&mut html);
+
+    insta::assert_snapshot!(html.finish(), @r###"
+    <main><section><pre><code class="language-sh"><b>ECHO HI
+    </b></code></pre><pre><code class="language-python">print(&quot;hi&quot;)
+    </code></pre><pre><code class="language-sh"><span class="linenr">1: </span><b>ECHO NUMBERED</b>
+    <span id="coderef-cd" class="coderef-off"><span class="linenr">2: </span><b>CD DIR</b> (cd)</span>
+    </code></pre><p>Run <code class="src src-sh"><b>LS</b></code> or <code class="src src-python">len(x) &lt; 1</code>.</p></section></main>
+    "###);
+}
+
+#[test]
+fn mathml() {
+    let mut html = HtmlExport::default().with_mathml(true);
+    Org::parse(
+        r#"Inline $a+b$ and \(\alpha\), but unsupported \(\foo{x}\).
+
+#+NAME: matrix
+\begin{equation}
+I = \begin{bmatrix} 1 & 0 \\ 0 & 1 \end{bmatrix}
+\end{equation}
+
+\begin{tikzpicture}
+\end{tikzpicture}
+"#,
+    )
+    .traverse(&mut html);
+
+    insta::assert_snapshot!(html.finish(), @r###"
+    <main><section><p>Inline <math><mi>a</mi><mo>+</mo><mi>b</mi></math> and <math><mi>α</mi></math>, but unsupported \(\foo{x}\).
+    </p><div id="matrix" class="equation-container"><span class="equation"><math display="block"><mrow><mi>I</mi><mo>=</mo><mrow><mo>[</mo><mtable columnalign="center center"><mtr><mtd><mn>1</mn></mtd><mtd><mn>0</mn></mtd></mtr><mtr><mtd><mn>0</mn></mtd><mtd><mn>1</mn></mtd></mtr></mtable><mo>]</mo></mrow></mrow></math>
+    </span><span class="equation-label">(1)</span></div><p></p>\begin{tikzpicture}
+    \end{tikzpicture}
+    </section></main>
+    "###);
+}
+
+#[test]
+fn source_block_line_numbers_from_zero() {
+    let org = Org::parse("#+begin_src c -n 0\na\nb\n#+end_src\n#+begin_src c +n\nc\n#+end_src\n#+begin_src c -n 0\n#+end_src\n#+begin_src c +n\nd\n#+end_src\n#+begin_src c +n 10 :exports code\ne\n#+end_src");
+    let numbers: Vec<_> = org
+        .document()
+        .syntax()
+        .descendants()
+        .filter_map(SourceBlock::cast)
+        .map(|block| block.first_line_number())
+        .collect();
+    assert_eq!(numbers, [Some(0), Some(2), Some(0), Some(0), Some(10)]);
+}
+
+#[test]
+fn export_block() {
+    // html blocks are emitted raw, blocks for other backends are dropped
+    insta::assert_snapshot!(
+        Org::parse("#+begin_export html\n<hr class=\"a&b\">\n#+end_export\n#+begin_export HTML\n<br>\n#+end_export\n#+begin_export latex\n\\newpage\n#+end_export\ntext").to_html(),
+        @r###"
+    <main><section><hr class="a&b">
+    <br>
+    <p>text</p></section></main>
+    "###
+    );
+}
+
+#[test]
+fn dynamic_block_keywords() {
+    insta::assert_snapshot!(
+        Org::parse("#+BEGIN: clocktable :scope file\n| a | *b* |\n#+END:\n").to_html(),
+        @r###"<main><section><table><tbody><tr><td>a</td><td><b>b</b></td></tr></tbody></table></section></main>"###
+    );
+}
+
+#[cfg(feature = "syntect")]
+#[test]
+fn syntect_line_numbers() {
+    use orgize::export::SyntectHighlighter;
+
+    let org = Org::parse("#+begin_src c -n\n/* start\n   still comment */\nint x;\n#+end_src");
+
+    let mut html = HtmlExport::default().with_highlighter(SyntectHighlighter::classed());
+    org.traverse(&mut html);
+    insta::assert_snapshot!(html.finish(), @r###"
+    <main><section><pre><code class="language-c"><span class="linenr">1: </span><span class="syn-source syn-c"><span class="syn-comment syn-block syn-c"><span class="syn-punctuation syn-definition syn-comment syn-c">/*</span> start</span></span>
+    <span class="linenr">2: </span><span class="syn-source syn-c"><span class="syn-comment syn-block syn-c">   still comment <span class="syn-punctuation syn-definition syn-comment syn-c">*/</span></span></span>
+    <span class="linenr">3: </span><span class="syn-source syn-c"><span class="syn-storage syn-type syn-c">int</span> x<span class="syn-punctuation syn-terminator syn-c">;</span></span>
+    </code></pre></section></main>
+    "###);
+}
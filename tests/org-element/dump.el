@@ -0,0 +1,110 @@
+;;; dump.el --- Dump org-element trees for orgize -*- lexical-binding: t; -*-
+
+;; Prints the output of `org-element-parse-buffer' for each org file given on
+;; command line, in the same format as `Org::to_org_element_sexp', and writes
+;; it to expected/NAME.el next to this file:
+;;
+;;   emacs -Q --batch -l tests/org-element/dump.el tests/org-element/*.org benches/*.org
+
+;;; Code:
+
+(require 'cl-lib)
+(require 'org)
+(require 'org-element)
+
+(defconst orgize-dump-directory
+  (expand-file-name "expected" (file-name-directory (or load-file-name buffer-file-name)))
+  "Directory of expected dumps.")
+
+(defconst orgize-dump-properties
+  '((headline :level :raw-value :todo-keyword :todo-type :priority :tags :archivedp :commentedp)
+    (node-property :key :value)
+    (table-row :type)
+    (plain-list :type)
+    (item :bullet :checkbox :counter)
+    (drawer :drawer-name)
+    (keyword :key :value)
+    (babel-call :call :value)
+    (footnote-definition :label)
+    (comment :value)
+    (fixed-width :value)
+    (example-block :value)
+    (export-block :type)
+    (src-block :language :switches :parameters :value)
+    (inline-babel-call :call)
+    (inline-src-block :language :parameters :value)
+    (link :type :path :raw-link)
+    (footnote-reference :label)
+    (macro :key :args)
+    (export-snippet :back-end :value)
+    (entity :name)
+    (timestamp :type :raw-value
+               :year-start :month-start :day-start :hour-start :minute-start
+               :year-end :month-end :day-end :hour-end :minute-end))
+  "Properties dumped for each type, besides :begin, :end, :post-blank and :name.")
+
+(defun orgize-dump--value (property value)
+  "Normalize VALUE of PROPERTY, symbols are converted into strings."
+  (cond ((eq property :priority) (char-to-string value))
+        ((eq value t) t)
+        ((stringp value) (substring-no-properties value))
+        ((symbolp value) (symbol-name value))
+        ((consp value) (mapcar #'substring-no-properties value))
+        (t value)))
+
+(defun orgize-dump--properties (data)
+  "Return dumped properties of DATA as a plist."
+  (let* ((type (org-element-type data))
+         (object (memq type org-element-all-objects))
+         (plist (list :begin (org-element-property :begin data)
+                      :end (org-element-property :end data))))
+    (unless object
+      (setq plist (append plist (list :post-blank (or (org-element-property :post-blank data) 0)))))
+    (dolist (property (append (cdr (assq type orgize-dump-properties))
+                              (unless object '(:name))))
+      (let ((value (org-element-property property data)))
+        (when value
+          (setq plist (append plist (list property (orgize-dump--value property value)))))))
+    plist))
+
+(defun orgize-dump--contents (data)
+  "Return contents of DATA, merging adjacent strings and dropping empty ones."
+  (let (contents)
+    (dolist (child (org-element-contents data))
+      (if (and (stringp child) (stringp (car contents)))
+          (setcar contents (concat (car contents) child))
+        (push child contents)))
+    (cl-remove-if (lambda (child) (equal child "")) (nreverse contents))))
+
+(defun orgize-dump--print (data depth)
+  "Insert DATA at DEPTH into current buffer."
+  (if (stringp data)
+      (prin1 (substring-no-properties data) (current-buffer))
+    (insert "(" (symbol-name (org-element-type data)) " ")
+    (prin1 (orgize-dump--properties data) (current-buffer))
+    (dolist (child (orgize-dump--contents data))
+      (insert "\n" (make-string (* 2 (1+ depth)) ?\s))
+      (orgize-dump--print child (1+ depth)))
+    (insert ")")))
+
+(let ((print-escape-newlines nil)
+      (print-escape-nonascii nil)
+      (print-escape-multibyte nil)
+      (print-length nil)
+      (print-level nil))
+  (make-directory orgize-dump-directory t)
+  (dolist (file command-line-args-left)
+    (let ((tree (with-temp-buffer
+                  (insert-file-contents file)
+                  (org-mode)
+                  (org-element-parse-buffer)))
+          (output (expand-file-name (concat (file-name-base file) ".el")
+                                    orgize-dump-directory)))
+      (with-temp-file output
+        (orgize-dump--print tree 0)
+        (insert "\n"))
+      (message "Wrote %s" output))))
+
+(setq command-line-args-left nil)
+
+;;; dump.el ends here
@@ -0,0 +1,70 @@
+//! Compares `Org::to_org_element_sexp` with dumps of `org-element-parse-buffer`
+//!
+//! Dumps are generated by Emacs with `tests/org-element/dump.el` into
+//! `tests/org-element/expected`, see `development.md`. A missing dump is a
+//! failure.
+//!
+//! The expected dumps are not checked in yet, so this test is ignored and CI
+//! compares nothing. Remove `#[ignore]` once they are committed.
+
+use std::{fs, path::Path};
+
+use orgize::Org;
+
+#[test]
+#[ignore = "expected dumps from tests/org-element/dump.el are not checked in yet"]
+fn org_element() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let expected_dir = root.join("tests/org-element/expected");
+
+    let mut failures = vec![];
+
+    for dir in ["tests/org-element", "benches"] {
+        let Ok(entries) = fs::read_dir(root.join(dir)) else {
+            continue;
+        };
+
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension() != Some("org".as_ref()) {
+                continue;
+            }
+
+            let stem = path.file_stem().unwrap().to_string_lossy();
+            let dump = expected_dir.join(format!("{stem}.el"));
+            let Ok(expected) = fs::read_to_string(&dump) else {
+                failures.push(format!(
+                    "{}: missing dump {}",
+                    path.display(),
+                    dump.display()
+                ));
+                continue;
+            };
+
+            let actual = Org::parse(fs::read_to_string(&path).unwrap()).to_org_element_sexp();
+
+            let mismatch = actual
+                .lines()
+                .zip(expected.lines())
+                .enumerate()
+                .find(|(_, (a, e))| a != e)
+                .map(|(line, (a, e))| (line + 1, a.to_string(), e.to_string()))
+                .or_else(|| {
+                    let (a, e) = (actual.lines().count(), expected.lines().count());
+                    (a != e).then(|| (a.min(e) + 1, format!("{a} lines"), format!("{e} lines")))
+                });
+
+            if let Some((line, actual, expected)) = mismatch {
+                failures.push(format!(
+                    "{}:{line}\n  actual:   {actual}\n  expected: {expected}",
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "org-element divergences:\n{}",
+        failures.join("\n")
+    );
+}